@@ -0,0 +1,528 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    collections::HashMap,
+    env,
+    fmt::{self, Write as _},
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    os::unix::{
+        fs::{DirBuilderExt, FileTypeExt, MetadataExt, PermissionsExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, anyhow, bail};
+use rustix::process::{self as rlimit, getuid};
+use scryptenc::{DERIVED_KEY_SIZE, Decryptor};
+use zeroize::Zeroize;
+
+/// The environment variable which holds the path of the socket.
+pub const SOCKET_ENV: &str = "RSCRYPT_AGENT_SOCK";
+
+/// The timeout for communicating with the agent.
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The interval at which expired keys are removed.
+const PURGE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Returns the default path of the socket.
+///
+/// This is `$XDG_RUNTIME_DIR/rscrypt/agent.sock`, or
+/// `/tmp/rscrypt-$UID/agent.sock` if `XDG_RUNTIME_DIR` is not set.
+fn default_socket_path() -> PathBuf {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .map_or_else(
+            || env::temp_dir().join(format!("rscrypt-{}", getuid().as_raw())),
+            |dir| dir.join("rscrypt"),
+        )
+        .join("agent.sock")
+}
+
+/// Returns the path of the socket.
+///
+/// This is the value of the `RSCRYPT_AGENT_SOCK` environment variable if it is
+/// set, and the default path otherwise.
+pub fn socket_path() -> PathBuf {
+    env::var_os(SOCKET_ENV)
+        .filter(|path| !path.is_empty())
+        .map_or_else(default_socket_path, PathBuf::from)
+}
+
+/// The identifier of the derived key.
+///
+/// The derived key is determined by the passphrase, the salt and the scrypt
+/// parameters, so the derived key for the same salt and parameters can be
+/// reused as long as it verifies the MAC of the header.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Key {
+    log_n: u8,
+    r: u32,
+    p: u32,
+    salt: [u8; 32],
+}
+
+impl Key {
+    /// Creates a new `Key` from the header of the encrypted data.
+    pub fn new(data: &[u8]) -> anyhow::Result<Self> {
+        let params = scryptenc::Params::new(data)?;
+        let salt = data[16..48]
+            .try_into()
+            .expect("size of salt should be 32 bytes");
+        Ok(Self {
+            log_n: params.log_n(),
+            r: params.r(),
+            p: params.p(),
+            salt,
+        })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.log_n,
+            self.r,
+            self.p,
+            encode_hex(&self.salt)
+        )
+    }
+}
+
+impl FromStr for Key {
+    type Err = anyhow::Error;
+
+    fn from_str(key: &str) -> anyhow::Result<Self> {
+        let mut fields = key.split(' ');
+        let (Some(log_n), Some(r), Some(p), Some(salt), None) = (
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+        ) else {
+            bail!("key is not in the form LOG_N R P SALT");
+        };
+        Ok(Self {
+            log_n: log_n.parse()?,
+            r: r.parse()?,
+            p: p.parse()?,
+            salt: decode_hex(salt)?,
+        })
+    }
+}
+
+/// Encodes `bytes` as a lowercase hexadecimal string.
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{b:02x}");
+        s
+    })
+}
+
+/// Decodes a hexadecimal string into a byte array.
+fn decode_hex<const N: usize>(s: &str) -> anyhow::Result<[u8; N]> {
+    if s.len() != N * 2 || !s.is_ascii() {
+        bail!("hexadecimal string is not {N} bytes");
+    }
+    let mut bytes = [u8::default(); N];
+    for (byte, digits) in bytes.iter_mut().zip(s.as_bytes().chunks(2)) {
+        let digits = std::str::from_utf8(digits).expect("digits should be ASCII");
+        *byte = u8::from_str_radix(digits, 16)
+            .map_err(|err| anyhow!("hexadecimal string is invalid: {err}"))?;
+    }
+    Ok(bytes)
+}
+
+/// The maximum number of derived keys held by the agent.
+const CAPACITY: usize = 256;
+
+/// The derived keys held by the agent.
+///
+/// The derived keys are stored in a buffer which is locked in memory so that
+/// they are not written to the swap, and are zeroed when they are removed.
+struct Cache {
+    lock: Option<region::LockGuard>,
+    slots: Box<[[u8; DERIVED_KEY_SIZE]]>,
+    entries: HashMap<Key, (usize, Instant)>,
+    ttl: Duration,
+}
+
+impl Cache {
+    /// Creates a new `Cache` which holds derived keys for `ttl`.
+    ///
+    /// Locking the memory may fail if the limit of locked memory is exceeded,
+    /// in which case the derived keys are held in ordinary memory.
+    fn new(ttl: Duration) -> Self {
+        let slots = vec![[u8::default(); DERIVED_KEY_SIZE]; CAPACITY].into_boxed_slice();
+        let lock = region::lock(slots.as_ptr(), CAPACITY * DERIVED_KEY_SIZE).ok();
+        Self {
+            lock,
+            slots,
+            entries: HashMap::new(),
+            ttl,
+        }
+    }
+
+    /// Returns the derived key for `key` if it has not expired.
+    fn get(&self, key: &Key) -> Option<&[u8; DERIVED_KEY_SIZE]> {
+        self.entries
+            .get(key)
+            .filter(|(_, added)| added.elapsed() < self.ttl)
+            .map(|(slot, _)| &self.slots[*slot])
+    }
+
+    /// Adds the derived key for `key`.
+    ///
+    /// If the cache is full, the oldest derived key is removed.
+    fn insert(&mut self, key: Key, dk: [u8; DERIVED_KEY_SIZE]) {
+        let slot = if let Some((slot, _)) = self.entries.remove(&key) {
+            slot
+        } else if let Some(slot) =
+            (0..CAPACITY).find(|i| self.entries.values().all(|(slot, _)| slot != i))
+        {
+            slot
+        } else {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, added))| *added)
+                .map(|(key, _)| key.clone())
+                .expect("cache should not be empty");
+            self.entries
+                .remove(&oldest)
+                .expect("oldest key should exist")
+                .0
+        };
+        self.slots[slot] = dk;
+        self.entries.insert(key, (slot, Instant::now()));
+    }
+
+    /// Removes the expired derived keys.
+    fn purge(&mut self) {
+        let ttl = self.ttl;
+        let slots = &mut self.slots;
+        self.entries.retain(|_, (slot, added)| {
+            let valid = added.elapsed() < ttl;
+            if !valid {
+                slots[*slot].zeroize();
+            }
+            valid
+        });
+    }
+
+    /// Removes all derived keys.
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.slots.zeroize();
+    }
+}
+
+impl Drop for Cache {
+    fn drop(&mut self) {
+        self.slots.zeroize();
+    }
+}
+
+/// Handles a request to the agent.
+///
+/// The request is one of the following lines:
+///
+/// - `GET <KEY>`, which is answered with `KEY <DK>` or `NONE`.
+/// - `PUT <KEY> <DK>`, which is answered with `OK`.
+/// - `CLEAR`, which is answered with `OK`.
+fn handle(cache: &Mutex<Cache>, request: &str) -> anyhow::Result<String> {
+    let lock = || cache.lock().unwrap_or_else(PoisonError::into_inner);
+    match request.split_once(' ') {
+        Some(("GET", key)) => {
+            let key = key.parse()?;
+            Ok(lock()
+                .get(&key)
+                .map_or_else(|| "NONE".to_owned(), |dk| format!("KEY {}", encode_hex(dk))))
+        }
+        Some(("PUT", args)) => {
+            let (key, dk) = args
+                .rsplit_once(' ')
+                .context("request does not have the derived key")?;
+            let (key, dk) = (key.parse()?, decode_hex(dk)?);
+            lock().insert(key, dk);
+            Ok("OK".to_owned())
+        }
+        None if request == "CLEAR" => {
+            lock().clear();
+            Ok("OK".to_owned())
+        }
+        _ => bail!("unknown request"),
+    }
+}
+
+/// Serves a connection to the agent.
+fn serve_connection(cache: &Mutex<Cache>, stream: &UnixStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut writer = stream;
+    let mut request = String::new();
+    while reader.read_line(&mut request)? != 0 {
+        let mut response = handle(cache, request.trim_end())
+            .unwrap_or_else(|err| format!("ERROR {}", format!("{err}").replace('\n', " ")));
+        let result = writeln!(writer, "{response}");
+        request.zeroize();
+        response.zeroize();
+        result?;
+    }
+    Ok(())
+}
+
+/// Ensures that `dir` is a directory which is owned by the current user and is
+/// accessible only by the owner (mode 0700).
+///
+/// Otherwise, another user who created the directory could replace the socket.
+fn ensure_private_dir(dir: &Path) -> anyhow::Result<()> {
+    let metadata =
+        fs::symlink_metadata(dir).with_context(|| format!("could not access {}", dir.display()))?;
+    if !metadata.is_dir() {
+        bail!("{} is not a directory", dir.display());
+    }
+    if metadata.uid() != getuid().as_raw() {
+        bail!("{} is not owned by the current user", dir.display());
+    }
+    if metadata.mode() & 0o777 != 0o700 {
+        bail!(
+            "{} is accessible by other users (mode {:04o})",
+            dir.display(),
+            metadata.mode() & 0o777
+        );
+    }
+    Ok(())
+}
+
+/// Binds the socket at `path`.
+///
+/// The parent directory is created with mode 0700 and the socket is made
+/// accessible only by the owner. If the socket is at the default path, the
+/// existing parent directory must also be owned by the current user and have
+/// mode 0700. A stale socket which no agent is listening on is removed.
+fn bind(path: &Path) -> anyhow::Result<UnixListener> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)
+            .with_context(|| format!("could not create {}", dir.display()))?;
+        if default_socket_path().parent() == Some(dir) {
+            ensure_private_dir(dir)?;
+        }
+    }
+    if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+        if UnixStream::connect(path).is_ok() {
+            bail!("an agent is already listening on {}", path.display());
+        }
+        fs::remove_file(path).with_context(|| format!("could not remove {}", path.display()))?;
+    }
+    let listener =
+        UnixListener::bind(path).with_context(|| format!("could not bind {}", path.display()))?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+        .with_context(|| format!("could not set the permissions of {}", path.display()))?;
+    Ok(listener)
+}
+
+/// Runs the agent which holds derived keys for `ttl` on the socket at `path`.
+///
+/// This does not return unless an error occurs.
+pub fn serve(path: &Path, ttl: Duration) -> anyhow::Result<()> {
+    // Prevent the derived keys from being written to a core dump.
+    let _ = rlimit::setrlimit(
+        rlimit::Resource::Core,
+        rlimit::Rlimit {
+            current: Some(0),
+            maximum: Some(0),
+        },
+    );
+
+    let listener = bind(path)?;
+    let cache = Cache::new(ttl);
+    if cache.lock.is_none() {
+        eprintln!("Warning: could not lock memory, derived keys may be written to the swap");
+    }
+    eprintln!("Listening on {}", path.display());
+    let cache = Arc::new(Mutex::new(cache));
+    {
+        let cache = Arc::clone(&cache);
+        thread::spawn(move || {
+            loop {
+                thread::sleep(PURGE_INTERVAL);
+                cache.lock().unwrap_or_else(PoisonError::into_inner).purge();
+            }
+        });
+    }
+    // Errors on a connection only affect the client.
+    for stream in listener.incoming().flatten() {
+        let _ = serve_connection(&cache, &stream);
+    }
+    Ok(())
+}
+
+/// Sends `request` to the agent on the socket at `path` and returns the
+/// response.
+///
+/// The socket must be owned by the current user so that the derived keys are
+/// not sent to an agent run by another user.
+fn request(path: &Path, request: &str) -> anyhow::Result<String> {
+    let metadata =
+        fs::metadata(path).with_context(|| format!("could not access {}", path.display()))?;
+    if metadata.uid() != getuid().as_raw() {
+        bail!("{} is not owned by the current user", path.display());
+    }
+    let stream = UnixStream::connect(path)
+        .with_context(|| format!("could not connect to the agent on {}", path.display()))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    writeln!(&stream, "{request}")?;
+    let mut response = String::new();
+    BufReader::new(&stream).read_line(&mut response)?;
+    let response = response.trim_end();
+    if let Some(err) = response.strip_prefix("ERROR ") {
+        bail!("the agent returned an error: {err}");
+    }
+    Ok(response.to_owned())
+}
+
+/// Returns the derived key for the encrypted data held by the agent.
+///
+/// Returns [`None`] if the agent is not running or does not hold the derived
+/// key.
+pub fn get(path: &Path, data: &[u8]) -> Option<[u8; DERIVED_KEY_SIZE]> {
+    let key = Key::new(data).ok()?;
+    let response = request(path, &format!("GET {key}")).ok()?;
+    decode_hex(response.strip_prefix("KEY ")?).ok()
+}
+
+/// Returns the [`Decryptor`] with the derived key for the encrypted data held by
+/// the agent.
+///
+/// Returns [`None`] if the agent does not hold the valid derived key.
+pub fn decryptor<'c>(path: &Path, data: &'c impl AsRef<[u8]>) -> Option<Decryptor<'c>> {
+    get(path, data.as_ref()).and_then(|dk| Decryptor::with_derived_key(data, dk).ok())
+}
+
+/// Makes the agent hold the derived key for the encrypted data.
+///
+/// Errors are ignored, since the agent is only a cache.
+pub fn put(path: &Path, data: &[u8], dk: &[u8; DERIVED_KEY_SIZE]) {
+    if let Ok(key) = Key::new(data) {
+        let _ = request(path, &format!("PUT {key} {}", encode_hex(dk)));
+    }
+}
+
+/// Makes the agent forget all derived keys.
+pub fn clear(path: &Path) -> anyhow::Result<()> {
+    match request(path, "CLEAR")?.as_str() {
+        "OK" => Ok(()),
+        response => bail!("the agent returned an unexpected response: {response}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> Key {
+        Key {
+            log_n: 10,
+            r: 8,
+            p: 1,
+            salt: [u8::MAX; 32],
+        }
+    }
+
+    #[test]
+    fn new_key() {
+        let data = include_bytes!("../tests/data/data.txt.scrypt");
+        let key = Key::new(data).unwrap();
+        assert_eq!((key.log_n, key.r, key.p), (10, 8, 1));
+        assert_eq!(key.salt, data[16..48]);
+    }
+
+    #[test]
+    fn key_round_trip() {
+        let key = key();
+        assert_eq!(Key::from_str(&key.to_string()).unwrap(), key);
+    }
+
+    #[test]
+    fn from_str_key_with_invalid_key() {
+        assert!(Key::from_str("").is_err());
+        assert!(Key::from_str("10 8 1").is_err());
+        assert!(Key::from_str("10 8 1 ffff").is_err());
+        assert!(Key::from_str(&format!("{} ff", key())).is_err());
+        assert!(Key::from_str(&format!("10 8 1 {}", "zz".repeat(32))).is_err());
+    }
+
+    #[test]
+    fn private_dir() {
+        let dir = env::temp_dir().join(format!("rscrypt-test-{}-private-dir", std::process::id()));
+        fs::DirBuilder::new().mode(0o700).create(&dir).unwrap();
+        assert!(ensure_private_dir(&dir).is_ok());
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(ensure_private_dir(&dir).is_err());
+        fs::remove_dir(&dir).unwrap();
+        assert!(ensure_private_dir(&dir).is_err());
+    }
+
+    #[test]
+    fn handle_request() {
+        let cache = Mutex::new(Cache::new(Duration::from_secs(60)));
+        let request = format!("GET {}", key());
+        assert_eq!(handle(&cache, &request).unwrap(), "NONE");
+        let dk = encode_hex(&[1; DERIVED_KEY_SIZE]);
+        assert_eq!(
+            handle(&cache, &format!("PUT {} {dk}", key())).unwrap(),
+            "OK"
+        );
+        assert_eq!(handle(&cache, &request).unwrap(), format!("KEY {dk}"));
+        assert_eq!(handle(&cache, "CLEAR").unwrap(), "OK");
+        assert_eq!(handle(&cache, &request).unwrap(), "NONE");
+        assert!(handle(&cache, "REMOVE").is_err());
+    }
+
+    #[test]
+    fn expire() {
+        let mut cache = Cache::new(Duration::ZERO);
+        cache.insert(key(), [1; DERIVED_KEY_SIZE]);
+        assert!(cache.get(&key()).is_none());
+        cache.purge();
+        assert!(cache.entries.is_empty());
+        assert_eq!(cache.slots[0], [u8::default(); DERIVED_KEY_SIZE]);
+    }
+
+    #[test]
+    fn evict_oldest() {
+        let mut cache = Cache::new(Duration::from_secs(60));
+        for i in 0..=CAPACITY {
+            let mut salt = [u8::default(); 32];
+            salt[..8].copy_from_slice(&u64::try_from(i).unwrap().to_be_bytes());
+            cache.insert(Key { salt, ..key() }, [1; DERIVED_KEY_SIZE]);
+        }
+        assert_eq!(cache.entries.len(), CAPACITY);
+        assert!(
+            cache
+                .get(&Key {
+                    salt: [u8::default(); 32],
+                    ..key()
+                })
+                .is_none()
+        );
+    }
+}
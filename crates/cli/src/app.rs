@@ -2,15 +2,32 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::path::Path;
+#[cfg(feature = "json")]
+use std::time::Instant;
+use std::{fs, path::Path, time::Duration};
 
 use anyhow::{Context, bail};
-use clap::Parser;
-use scryptenc::{Decryptor, Error as ScryptencError, scrypt};
+use scryptenc::{Decryptor, Encryptor, Error as ScryptencError, armor, compress, envelope, scrypt};
 
+#[cfg(feature = "x25519")]
+use crate::cli::Keygen;
+#[cfg(feature = "x25519")]
+use crate::recipient::{self, Recipient};
+#[cfg(feature = "json")]
+use crate::report;
+#[cfg(unix)]
+use crate::{agent, cli::Agent};
 use crate::{
-    cli::{Command, Opt},
-    input, output, params, passphrase,
+    audit, bench, calibration, check,
+    cli::{
+        AddPassphrase, Audit, Bench, Byte, Check, Command, ConfigCommand, Decrypt, Edit, Encrypt,
+        GitFilter, GitFilterMode, GitInit, Information, ListPassphrases, Opt, Overwrite,
+        PassphraseCommand, RemovePassphrase, Upgrade,
+    },
+    config::{self, Origin, Setting},
+    edit, git, info, input, output, params, passphrase,
+    progress::Progress,
+    slot,
 };
 
 /// Ensures that there are no conflicts if reading the passphrase from standard
@@ -22,151 +39,1386 @@ fn ensure_stdin_does_not_conflict(path: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Runs the program and returns the result.
-#[allow(clippy::too_many_lines)]
-pub fn run() -> anyhow::Result<()> {
-    let opt = Opt::parse();
+/// Verifies that the encrypted data has been written to `path` intact.
+///
+/// The file is flushed to the storage device, read back and compared with
+/// `ciphertext`, and then decrypted with `passphrase` to verify the MACs. If
+/// `passphrase` is [`None`], the MACs are not verified.
+fn verify_written_file(
+    path: &Path,
+    ciphertext: &[u8],
+    passphrase: Option<&[u8]>,
+) -> anyhow::Result<()> {
+    output::sync(path)?;
+    let written = input::read(path)?;
+    if written != ciphertext {
+        bail!("{} does not match the encrypted data", path.display());
+    }
+    let Some(passphrase) = passphrase else {
+        return Ok(());
+    };
+    let data = if armor::is_armored(&written) {
+        armor::decode(written).context("could not decode the ASCII-armored data")?
+    } else {
+        written
+    };
+    if envelope::is_envelope(&data) {
+        envelope::decrypt(&data, passphrase)
+            .map(drop)
+            .with_context(|| format!("could not verify {}", path.display()))
+    } else {
+        Decryptor::new(&data, passphrase)
+            .and_then(|cipher| cipher.verify())
+            .with_context(|| format!("could not verify {}", path.display()))
+    }
+}
 
-    match opt.command {
-        Command::Encrypt(arg) => {
-            let input = input::read(&arg.input)?;
-
-            let passphrase = match (
-                arg.passphrase_from_tty,
-                arg.passphrase_from_stdin,
-                arg.passphrase_from_tty_once,
-                arg.passphrase_from_env,
-                arg.passphrase_from_file,
-            ) {
-                (_, true, ..) => {
-                    ensure_stdin_does_not_conflict(&arg.input)?;
-                    passphrase::read_passphrase_from_stdin()
-                }
-                (_, _, true, ..) => passphrase::read_passphrase_from_tty_once(),
-                (.., Some(env), _) => passphrase::read_passphrase_from_env(&env),
-                (.., Some(file)) => passphrase::read_passphrase_from_file(&file),
-                _ => passphrase::read_passphrase_from_tty(),
-            }?;
-
-            let params = if let (Some(log_n), Some(r), Some(p)) = (arg.log_n, arg.r, arg.p) {
-                scrypt::Params::new(log_n, r, p, scrypt::Params::RECOMMENDED_LEN)
-                    .expect("encryption parameters should be valid")
-            } else {
-                params::new(arg.max_memory, arg.max_memory_fraction, arg.max_time)
-            };
+/// Encrypts `input` into the envelope for the passphrase and the recipients.
+#[cfg_attr(
+    not(feature = "x25519"),
+    allow(unused_variables, clippy::unnecessary_wraps)
+)]
+fn encrypt_envelope(
+    arg: &Encrypt,
+    input: &[u8],
+    passphrase: Option<&[u8]>,
+    params: scrypt::Params,
+) -> anyhow::Result<Vec<u8>> {
+    #[cfg(feature = "x25519")]
+    if arg.has_recipients() {
+        let recipients = arg
+            .recipient
+            .iter()
+            .map(|r| *r.public_key())
+            .collect::<Vec<_>>();
+        return envelope::encrypt_with_recipients(
+            input,
+            passphrase.as_slice(),
+            &recipients,
+            params,
+        )
+        .context("could not encrypt data for the recipients");
+    }
+    Ok(envelope::encrypt(input, passphrase.as_slice(), params)
+        .expect("envelope should have one slot"))
+}
 
-            if arg.verbose {
-                if arg.force {
-                    params::displayln_without_resources(params.log_n(), params.r(), params.p());
-                } else {
-                    params::displayln_with_resources(
-                        params.log_n(),
-                        params.r(),
-                        params.p(),
-                        arg.max_memory,
-                        arg.max_memory_fraction,
-                        arg.max_time,
-                    );
-                }
-            }
+/// Writes the decrypted data to the output file or standard output.
+fn write_plaintext(
+    arg: &Decrypt,
+    plaintext: &[u8],
+    overwrite: Overwrite,
+    metadata: Option<&output::Metadata>,
+) -> anyhow::Result<()> {
+    if let Some(file) = arg.output_file() {
+        // The file is created private so that the plaintext is never exposed,
+        // and the preserved permissions are restored after it is written.
+        output::write_to_file(file, plaintext, overwrite, true)?;
+        if let Some(metadata) = metadata {
+            metadata.apply(file)?;
+        }
+    } else {
+        if !arg.force_tty && output::is_binary(plaintext) {
+            output::ensure_stdout_is_not_terminal()?;
+        }
+        output::write_to_stdout(plaintext)?;
+    }
+    Ok(())
+}
 
-            if !arg.force {
-                params::check(
-                    arg.max_memory,
-                    arg.max_memory_fraction,
-                    arg.max_time,
-                    params.log_n(),
-                    params.r(),
-                    params.p(),
-                )?;
-            }
+/// Decompresses `plaintext` if it was compressed before encryption.
+///
+/// The decompressed data must not be larger than `limit`, or the available
+/// memory if it is not specified.
+fn decompress(plaintext: Vec<u8>, limit: Option<Byte>) -> anyhow::Result<Vec<u8>> {
+    if compress::detect(&plaintext).is_none() {
+        return Ok(plaintext);
+    }
+    let limit = limit.map_or_else(params::available_memory, |limit| limit.as_u64());
+    compress::decompress_with_limit(plaintext, limit).context("could not decompress data")
+}
+
+/// Reads the envelope to modify.
+///
+/// Returns the envelope and whether it was ASCII-armored.
+fn read_envelope(path: &Path) -> anyhow::Result<(Vec<u8>, bool)> {
+    let input = input::read(path)?;
+    let armored = armor::is_armored(&input);
+    let input = if armored {
+        armor::decode(input).context("could not decode the ASCII-armored data")?
+    } else {
+        input
+    };
+    if !envelope::is_envelope(&input) {
+        bail!(
+            "{} is not an envelope, encrypt it with --envelope first",
+            path.display()
+        );
+    }
+    Ok((input, armored))
+}
 
-            let ciphertext = scryptenc::encrypt_with_params(input, passphrase, params);
+/// Writes the modified envelope back to `path`.
+fn replace_envelope(path: &Path, envelope: Vec<u8>, armored: bool) -> anyhow::Result<()> {
+    let output = if armored {
+        armor::encode(envelope).into_bytes()
+    } else {
+        envelope
+    };
+    output::replace_file(path, &output)
+}
 
-            if let Some(file) = arg.output {
-                output::write_to_file(&file, &ciphertext)?;
-            } else {
-                output::write_to_stdout(&ciphertext)?;
-            }
+/// Adds the context to the error of the envelope.
+fn envelope_context<T>(result: Result<T, envelope::Error>) -> anyhow::Result<T> {
+    match result {
+        r @ Err(envelope::Error::NoMatchingSlot) => r.context("passphrase is incorrect"),
+        #[cfg(feature = "x25519")]
+        r @ Err(envelope::Error::NoMatchingIdentity) => r.context("identity is incorrect"),
+        r @ Err(envelope::Error::Body(ScryptencError::InvalidMac(_))) => {
+            r.context("the encrypted data is corrupted")
         }
-        Command::Decrypt(arg) => {
-            let input = input::read(&arg.input)?;
-
-            let passphrase = match (
-                arg.passphrase_from_tty,
-                arg.passphrase_from_stdin,
-                arg.passphrase_from_env,
-                arg.passphrase_from_file,
-            ) {
-                (_, true, ..) => {
-                    ensure_stdin_does_not_conflict(&arg.input)?;
-                    passphrase::read_passphrase_from_stdin()
-                }
-                (.., Some(env), _) => passphrase::read_passphrase_from_env(&env),
-                (.., Some(file)) => passphrase::read_passphrase_from_file(&file),
-                _ => passphrase::read_passphrase_from_tty_once(),
-            }?;
-
-            let params = params::get(&input)?;
-            if arg.verbose {
-                if arg.force {
-                    params::displayln_without_resources(params.log_n(), params.r(), params.p());
-                } else {
-                    params::displayln_with_resources(
-                        params.log_n(),
-                        params.r(),
-                        params.p(),
-                        arg.max_memory,
-                        arg.max_memory_fraction,
-                        arg.max_time,
-                    );
+        r => r.context("the envelope is invalid"),
+    }
+}
+
+/// Reads the metadata of the input file if `preserve` is `true`.
+fn read_metadata(
+    input: &Path,
+    output: Option<&Path>,
+    preserve: bool,
+) -> anyhow::Result<Option<output::Metadata>> {
+    if !preserve {
+        return Ok(None);
+    }
+    if input == Path::new("-") {
+        bail!("cannot preserve the metadata of standard input");
+    }
+    if output.is_none() {
+        bail!("cannot preserve the metadata in standard output");
+    }
+    output::Metadata::read(input).map(Some)
+}
+
+/// Reads the passphrase from the source.
+///
+/// If `keyfile` is specified and the source is not specified on the command
+/// line, this returns [`None`].
+fn read_passphrase(
+    source: &Setting<passphrase::Source>,
+    input: &Path,
+    keyfile: Option<&Path>,
+    confirm: bool,
+) -> anyhow::Result<Option<String>> {
+    if keyfile.is_some() && source.origin() != &Origin::CommandLine {
+        return Ok(None);
+    }
+    if source.is_stdin() {
+        ensure_stdin_does_not_conflict(input)?;
+    }
+    source.read(confirm).map(Some)
+}
+
+/// Prints the encryption parameters if `verbose` is `true`, and checks that
+/// they do not exceed the resource limits unless `force` is `true`.
+fn check_params(
+    limits: &config::Limits,
+    (log_n, r, p): (u8, u32, u32),
+    verbose: bool,
+    force: bool,
+) -> anyhow::Result<()> {
+    let (max_memory, max_memory_fraction, max_time) = (
+        *limits.max_memory,
+        *limits.max_memory_fraction,
+        *limits.max_time,
+    );
+    if verbose {
+        if force {
+            params::displayln_without_resources(log_n, r, p);
+        } else {
+            params::displayln_with_resources(
+                log_n,
+                r,
+                p,
+                max_memory,
+                max_memory_fraction,
+                max_time,
+            );
+        }
+    }
+    if !force {
+        params::check(max_memory, max_memory_fraction, max_time, log_n, r, p)?;
+    }
+    Ok(())
+}
+
+/// Ensures that the input file can be removed after it is encrypted if
+/// `--remove-input` or `--shred-input` is specified.
+fn ensure_input_is_removable(arg: &Encrypt) -> anyhow::Result<()> {
+    if !(arg.remove_input || arg.shred_input) {
+        return Ok(());
+    }
+    if arg.input == Path::new("-") {
+        bail!("cannot remove standard input");
+    }
+    match arg.output_file() {
+        None => bail!("cannot verify the encrypted data written to standard output"),
+        Some(file) if output::is_same_file(&arg.input, file)? => {
+            bail!("cannot remove the input file which is also the output file");
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Generates or reads the passphrase to encrypt with if `use_passphrase` is
+/// `true`.
+fn read_encryption_passphrase(
+    arg: &Encrypt,
+    settings: &config::EncryptSettings,
+    use_passphrase: bool,
+) -> anyhow::Result<Option<String>> {
+    if !use_passphrase {
+        return Ok(None);
+    }
+    if !arg.generate_passphrase {
+        return read_passphrase(
+            &settings.passphrase,
+            &arg.input,
+            arg.passphrase.keyfile.as_deref(),
+            true,
+        );
+    }
+    let passphrase = passphrase::generate(arg.words, arg.chars);
+    if let Some(file) = &arg.passphrase_out {
+        passphrase::write_passphrase_to_file(file, &passphrase)?;
+    } else {
+        eprintln!("Generated passphrase: {passphrase}");
+    }
+    Ok(Some(passphrase))
+}
+
+/// Checks the strength of the passphrase, and returns the password used to
+/// derive the key.
+fn encryption_password(arg: &Encrypt, passphrase: Option<String>) -> anyhow::Result<Vec<u8>> {
+    if let (Some(passphrase), None) = (&passphrase, &arg.passphrase.keyfile) {
+        passphrase::check_strength(passphrase, arg.min_entropy, arg.allow_weak_passphrase)?;
+    }
+    passphrase::to_password(
+        passphrase,
+        arg.passphrase.keyfile.as_deref(),
+        arg.passphrase.keyfile_digest,
+    )
+}
+
+/// Returns the encryption parameters from the settings, or the ones which are
+/// computed from the resource limits.
+fn encryption_params(settings: &config::EncryptSettings, use_passphrase: bool) -> scrypt::Params {
+    if let Some((log_n, r, p)) = *settings.params {
+        scrypt::Params::new(log_n, r, p, scrypt::Params::RECOMMENDED_LEN)
+            .expect("encryption parameters should be valid")
+    } else if use_passphrase {
+        params::new(
+            *settings.limits.max_memory,
+            *settings.limits.max_memory_fraction,
+            *settings.limits.max_time,
+        )
+    } else {
+        // The parameters are only stored in the headers which are not
+        // used for deriving the key.
+        scrypt::Params::recommended()
+    }
+}
+
+/// Writes the encrypted data to the output file or standard output.
+///
+/// If the input file is removed, the written file is verified with
+/// `passphrase` first.
+fn write_ciphertext(
+    arg: &Encrypt,
+    ciphertext: &[u8],
+    overwrite: Overwrite,
+    metadata: Option<&output::Metadata>,
+    passphrase: Option<&[u8]>,
+    progress: Progress,
+    estimated_time: Duration,
+) -> anyhow::Result<()> {
+    let Some(file) = arg.output_file() else {
+        return output::write_to_stdout(ciphertext);
+    };
+    output::write_to_file(file, ciphertext, overwrite, false)?;
+    if let Some(metadata) = metadata {
+        metadata.apply(file)?;
+    }
+    if arg.remove_input || arg.shred_input {
+        progress.spin("Verifying", estimated_time, || {
+            verify_written_file(file, ciphertext, passphrase)
+        })?;
+        if arg.shred_input {
+            output::shred(&arg.input)
+        } else {
+            fs::remove_file(&arg.input)
+        }
+        .with_context(|| format!("could not remove {}", arg.input.display()))?;
+    }
+    Ok(())
+}
+
+/// Runs the `enc` command.
+fn run_encrypt(
+    arg: &Encrypt,
+    config: Option<&Path>,
+    #[cfg(feature = "json")] json: bool,
+) -> anyhow::Result<()> {
+    calibration::set_mode(calibration::Mode::new(
+        arg.recalibrate,
+        arg.no_calibration_cache,
+    ));
+    let mut settings = config::load(config)?.encrypt;
+    settings.apply(arg, &Origin::CommandLine);
+    if arg.force && settings.params.is_none() {
+        bail!("`--force` requires the encryption parameters");
+    }
+
+    let output = arg.output_file();
+    ensure_input_is_removable(arg)?;
+    let metadata = read_metadata(&arg.input, output, arg.preserve)?;
+    if output.is_none() && !*settings.armor && !arg.force_tty {
+        output::ensure_stdout_is_not_terminal()?;
+    }
+    let input = input::read(&arg.input)?;
+
+    // With recipients, the passphrase is optional and is only read if
+    // it is requested explicitly.
+    let use_passphrase = !arg.has_recipients()
+        || arg.generate_passphrase
+        || arg.passphrase.keyfile.is_some()
+        || settings.passphrase.origin() == &Origin::CommandLine;
+    let passphrase = read_encryption_passphrase(arg, &settings, use_passphrase)?;
+
+    let params = encryption_params(&settings, use_passphrase);
+    if use_passphrase {
+        check_params(
+            &settings.limits,
+            (params.log_n(), params.r(), params.p()),
+            arg.verbose,
+            arg.force,
+        )?;
+    }
+
+    let passphrase = use_passphrase
+        .then(|| encryption_password(arg, passphrase))
+        .transpose()?;
+
+    #[cfg(feature = "json")]
+    let input_size = input.len();
+    let input = if let Some(compression) = arg.compress {
+        compress::compress(input, compression.into()).context("could not compress data")?
+    } else {
+        input
+    };
+
+    let progress = Progress::new(arg.progress);
+    let estimated_time = if use_passphrase {
+        params::estimate_time(params.log_n(), params.r(), params.p())
+    } else {
+        Duration::ZERO
+    };
+    #[cfg(feature = "json")]
+    let start = Instant::now();
+    #[cfg(feature = "json")]
+    let actual_time;
+    let mut ciphertext = if let (false, false, Some(passphrase)) =
+        (arg.envelope, arg.has_recipients(), &passphrase)
+    {
+        let cipher = progress.spin("Deriving key", estimated_time, || {
+            Encryptor::with_params(&input, passphrase, params)
+        });
+        #[cfg(feature = "json")]
+        {
+            actual_time = start.elapsed();
+        }
+        let mut ciphertext = vec![u8::default(); cipher.out_len()];
+        cipher.encrypt_with_progress(&mut ciphertext, progress.bar("Encrypting", input.len()));
+        ciphertext
+    } else {
+        let ciphertext = progress.spin("Deriving key", estimated_time, || {
+            encrypt_envelope(arg, &input, passphrase.as_deref(), params)
+        })?;
+        #[cfg(feature = "json")]
+        {
+            actual_time = start.elapsed();
+        }
+        ciphertext
+    };
+    if *settings.armor {
+        ciphertext = armor::encode(ciphertext).into_bytes();
+    }
+    write_ciphertext(
+        arg,
+        &ciphertext,
+        *settings.overwrite,
+        metadata.as_ref(),
+        passphrase.as_deref(),
+        progress,
+        estimated_time,
+    )?;
+
+    #[cfg(feature = "json")]
+    if json {
+        report::Report::new(
+            report::Operation::Encrypt,
+            use_passphrase.then(|| params.into()),
+            input_size,
+            ciphertext.len(),
+            estimated_time,
+            actual_time,
+            output,
+        )
+        .print()?;
+    }
+    Ok(())
+}
+
+/// Decompresses the decrypted data unless `--no-decompress` is specified, and
+/// writes it.
+///
+/// Returns the data which was written.
+fn output_plaintext(
+    arg: &Decrypt,
+    plaintext: Vec<u8>,
+    overwrite: Overwrite,
+    metadata: Option<&output::Metadata>,
+) -> anyhow::Result<Vec<u8>> {
+    let plaintext = if arg.no_decompress {
+        plaintext
+    } else {
+        decompress(plaintext, arg.max_decompressed_size)?
+    };
+    write_plaintext(arg, &plaintext, overwrite, metadata)?;
+    Ok(plaintext)
+}
+
+/// Decrypts the envelope with the identities.
+#[cfg(feature = "x25519")]
+fn decrypt_with_identity(
+    arg: &Decrypt,
+    input: &[u8],
+    settings: &config::DecryptSettings,
+    metadata: Option<&output::Metadata>,
+    #[cfg(feature = "json")] json: bool,
+) -> anyhow::Result<()> {
+    let mut identities = Vec::new();
+    for file in &arg.identity {
+        identities.extend(recipient::read_identity_file(file)?);
+    }
+    if !envelope::is_envelope(input) {
+        bail!(
+            "{} is not an envelope, it cannot be decrypted with --identity",
+            arg.input.display()
+        );
+    }
+    #[cfg(feature = "json")]
+    let start = Instant::now();
+    let plaintext = identities
+        .iter()
+        .map(|identity| envelope::decrypt_with_identity(input, identity))
+        .find(|p| !matches!(p, Err(envelope::Error::NoMatchingIdentity)))
+        .unwrap_or(Err(envelope::Error::NoMatchingIdentity));
+    #[cfg(feature = "json")]
+    let actual_time = start.elapsed();
+    let plaintext = envelope_context(plaintext)?;
+    #[cfg_attr(not(feature = "json"), allow(unused_variables))]
+    let plaintext = output_plaintext(arg, plaintext, *settings.overwrite, metadata)?;
+
+    #[cfg(feature = "json")]
+    if json {
+        report::Report::new(
+            report::Operation::Decrypt,
+            None,
+            input.len(),
+            plaintext.len(),
+            Duration::ZERO,
+            actual_time,
+            arg.output_file(),
+        )
+        .print()?;
+    }
+    Ok(())
+}
+
+/// Decrypts the envelope with the passphrase.
+fn decrypt_envelope(
+    arg: &Decrypt,
+    input: &[u8],
+    settings: &config::DecryptSettings,
+    metadata: Option<&output::Metadata>,
+    read_password: impl Fn() -> anyhow::Result<(Vec<u8>, bool)>,
+    #[cfg(feature = "json")] json: bool,
+) -> anyhow::Result<()> {
+    let slots = envelope::slots(input).context("the envelope is invalid")?;
+    if slots.is_empty() {
+        bail!(
+            "{} has no passphrase, decrypt it with --identity",
+            arg.input.display()
+        );
+    }
+    for params in &slots {
+        check_params(
+            &settings.limits,
+            (params.log_n(), params.r(), params.p()),
+            arg.verbose,
+            arg.force,
+        )?;
+    }
+
+    let (mut passphrase, retryable) = read_password()?;
+    let progress = Progress::new(arg.progress);
+    let estimated_time = slot::total_estimated_time(&slots);
+    #[cfg(feature = "json")]
+    let mut start = Instant::now();
+    let mut tries = arg.tries;
+    let plaintext = loop {
+        let plaintext = progress.spin("Deriving key", estimated_time, || {
+            envelope::decrypt(input, &passphrase)
+        });
+        match plaintext {
+            Err(envelope::Error::NoMatchingSlot) if retryable && tries > 1 => {
+                eprintln!("Passphrase is incorrect, try again.");
+                tries -= 1;
+                (passphrase, _) = read_password()?;
+                #[cfg(feature = "json")]
+                {
+                    start = Instant::now();
                 }
             }
+            p => break p,
+        }
+    };
+    #[cfg(feature = "json")]
+    let actual_time = start.elapsed();
+    let plaintext = envelope_context(plaintext)?;
+    #[cfg_attr(not(feature = "json"), allow(unused_variables))]
+    let plaintext = output_plaintext(arg, plaintext, *settings.overwrite, metadata)?;
 
-            if !arg.force {
-                params::check(
-                    arg.max_memory,
-                    arg.max_memory_fraction,
-                    arg.max_time,
-                    params.log_n(),
-                    params.r(),
-                    params.p(),
-                )?;
-            }
+    #[cfg(feature = "json")]
+    if json {
+        let params = slots
+            .iter()
+            .max_by_key(|p| params::estimate_time(p.log_n(), p.r(), p.p()))
+            .expect("envelope should have at least one slot");
+        report::Report::new(
+            report::Operation::Decrypt,
+            Some(params::Params::new(*params)),
+            input.len(),
+            plaintext.len(),
+            estimated_time,
+            actual_time,
+            arg.output_file(),
+        )
+        .print()?;
+    }
+    Ok(())
+}
 
-            let cipher = match Decryptor::new(&input, passphrase) {
-                c @ Err(ScryptencError::InvalidHeaderMac(_)) => {
-                    c.context("passphrase is incorrect")
+/// Decrypts the data with the passphrase, or with the derived key cached by
+/// the agent.
+fn decrypt_data(
+    arg: &Decrypt,
+    input: &[u8],
+    settings: &config::DecryptSettings,
+    metadata: Option<&output::Metadata>,
+    read_password: impl Fn() -> anyhow::Result<(Vec<u8>, bool)>,
+    #[cfg(feature = "json")] json: bool,
+) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    let agent = (!arg.no_agent).then(agent::socket_path);
+    #[cfg(unix)]
+    let cached = agent
+        .as_deref()
+        .and_then(|socket| agent::decryptor(socket, &input));
+    #[cfg(not(unix))]
+    let cached = None;
+    let (mut passphrase, retryable) = if cached.is_some() {
+        (Vec::new(), false)
+    } else {
+        read_password()?
+    };
+
+    let params = params::get(input)?;
+    check_params(
+        &settings.limits,
+        (params.log_n(), params.r(), params.p()),
+        arg.verbose,
+        arg.force,
+    )?;
+
+    let progress = Progress::new(arg.progress);
+    let estimated_time = params::estimate_time(params.log_n(), params.r(), params.p());
+    #[cfg(feature = "json")]
+    let mut start = Instant::now();
+    let mut tries = arg.tries;
+    #[cfg(unix)]
+    let derived = cached.is_none();
+    let cipher = if let Some(cipher) = cached {
+        Ok(cipher)
+    } else {
+        loop {
+            let cipher = progress.spin("Deriving key", estimated_time, || {
+                Decryptor::new(&input, &passphrase)
+            });
+            match cipher {
+                Err(ScryptencError::InvalidHeaderMac(_)) if retryable && tries > 1 => {
+                    eprintln!("Passphrase is incorrect, try again.");
+                    tries -= 1;
+                    (passphrase, _) = read_password()?;
+                    #[cfg(feature = "json")]
+                    {
+                        start = Instant::now();
+                    }
                 }
-                c => c.context("the header in the encrypted data is invalid"),
-            }?;
-            let plaintext = cipher
-                .decrypt_to_vec()
-                .context("the encrypted data is corrupted")?;
-
-            if let Some(file) = arg.output {
-                output::write_to_file(&file, &plaintext)?;
-            } else {
-                output::write_to_stdout(&plaintext)?;
+                c => break c,
             }
         }
-        Command::Information(arg) => {
-            let input = input::read(&arg.input)?;
+    };
+    #[cfg(feature = "json")]
+    let actual_time = start.elapsed();
+    let cipher = match cipher {
+        c @ Err(ScryptencError::InvalidHeaderMac(_)) => c.context("passphrase is incorrect"),
+        c => c.context("the header in the encrypted data is invalid"),
+    }?;
+    #[cfg(unix)]
+    if let (Some(socket), true) = (&agent, derived) {
+        agent::put(socket, input, &cipher.derived_key());
+    }
+    let mut plaintext = vec![u8::default(); cipher.out_len()];
+    cipher
+        .decrypt_with_progress(&mut plaintext, progress.bar("Decrypting", cipher.out_len()))
+        .context("the encrypted data is corrupted")?;
+    #[cfg_attr(not(feature = "json"), allow(unused_variables))]
+    let plaintext = output_plaintext(arg, plaintext, *settings.overwrite, metadata)?;
+
+    #[cfg(feature = "json")]
+    if json {
+        report::Report::new(
+            report::Operation::Decrypt,
+            Some(params::Params::new(params)),
+            input.len(),
+            plaintext.len(),
+            estimated_time,
+            actual_time,
+            arg.output_file(),
+        )
+        .print()?;
+    }
+    Ok(())
+}
+
+/// Runs the `dec` command.
+fn run_decrypt(
+    arg: &Decrypt,
+    config: Option<&Path>,
+    #[cfg(feature = "json")] json: bool,
+) -> anyhow::Result<()> {
+    calibration::set_mode(calibration::Mode::new(
+        arg.recalibrate,
+        arg.no_calibration_cache,
+    ));
+    let mut settings = config::load(config)?.decrypt;
+    settings.apply(arg, &Origin::CommandLine);
 
-            let params = params::get(&input)?;
+    let metadata = read_metadata(&arg.input, arg.output_file(), arg.preserve)?;
+    let input = input::read_encrypted(&arg.input)?;
+
+    let read_password = || {
+        read_passphrase(
+            &settings.passphrase,
+            &arg.input,
+            arg.passphrase.keyfile.as_deref(),
+            false,
+        )
+        .and_then(|passphrase| {
+            let retryable = passphrase.is_some() && settings.passphrase.is_tty();
+            passphrase::to_password(
+                passphrase,
+                arg.passphrase.keyfile.as_deref(),
+                arg.passphrase.keyfile_digest,
+            )
+            .map(|password| (password, retryable))
+        })
+    };
+    #[cfg(feature = "x25519")]
+    if !arg.identity.is_empty() {
+        return decrypt_with_identity(
+            arg,
+            &input,
+            &settings,
+            metadata.as_ref(),
             #[cfg(feature = "json")]
-            if arg.json {
-                let params = params::Params::new(params);
-                let output =
-                    serde_json::to_string(&params).context("could not serialize as JSON")?;
-                println!("{output}");
-                return Ok(());
-            }
-            params::displayln_without_resources(params.log_n(), params.r(), params.p());
+            json,
+        );
+    }
+    if envelope::is_envelope(&input) {
+        return decrypt_envelope(
+            arg,
+            &input,
+            &settings,
+            metadata.as_ref(),
+            read_password,
+            #[cfg(feature = "json")]
+            json,
+        );
+    }
+    decrypt_data(
+        arg,
+        &input,
+        &settings,
+        metadata.as_ref(),
+        read_password,
+        #[cfg(feature = "json")]
+        json,
+    )
+}
+
+/// Runs the `info` command.
+fn run_information(arg: &Information, #[cfg(feature = "json")] json: bool) -> anyhow::Result<()> {
+    let mut infos = Vec::with_capacity(arg.input.len());
+    for path in &arg.input {
+        let input = input::read_encrypted(path)?;
+        let info = info::Information::new(path, &input)
+            .with_context(|| format!("could not read information from {}", path.display()))?;
+        infos.push(info);
+    }
+
+    #[cfg(feature = "json")]
+    if json {
+        for info in &infos {
+            let output = serde_json::to_string(&info::Report::new(info))
+                .context("could not serialize as JSON")?;
+            println!("{output}");
+        }
+        return Ok(());
+    }
+    if let [info] = infos.as_slice() {
+        info::display(info);
+    } else {
+        info::display_table(&infos);
+    }
+    Ok(())
+}
+
+/// Runs the `audit` command.
+fn run_audit(arg: &Audit, #[cfg(feature = "json")] json: bool) -> anyhow::Result<()> {
+    let policy = audit::Policy {
+        min_log_n: arg.min_log_n,
+        min_memory: arg.min_memory.map(|b| b.as_u64()),
+    };
+    let entries = audit::run(&arg.paths, arg.recursive, &policy);
+
+    #[cfg(feature = "json")]
+    if json {
+        for entry in &entries {
+            let output = serde_json::to_string(&audit::Report::new(entry))
+                .context("could not serialize as JSON")?;
+            println!("{output}");
+        }
+        return audit::result(&entries).map_err(anyhow::Error::from);
+    }
+    audit::display(&entries);
+    audit::result(&entries)?;
+    Ok(())
+}
+
+/// Runs the `upgrade` command.
+fn run_upgrade(arg: &Upgrade, config: Option<&Path>) -> anyhow::Result<()> {
+    let settings = config::load(config)?;
+    let mut encrypt_settings = settings.encrypt;
+    let origin = Origin::CommandLine;
+    encrypt_settings.limits.apply(&arg.limits, &origin);
+    let (max_memory, max_memory_fraction, max_time) = (
+        *encrypt_settings.limits.max_memory,
+        *encrypt_settings.limits.max_memory_fraction,
+        *encrypt_settings.limits.max_time,
+    );
+    let mut passphrase_source = settings.decrypt.passphrase;
+    passphrase_source.set(arg.passphrase.source(), &origin);
+
+    let input = input::read(&arg.input)?;
+    let armored = armor::is_armored(&input);
+    let input = if armored {
+        armor::decode(input).context("could not decode the ASCII-armored data")?
+    } else {
+        input
+    };
+    if envelope::is_envelope(&input) {
+        bail!(
+            "cannot upgrade an envelope; add a passphrase with `rscrypt passphrase add` \
+             and remove the old one instead"
+        );
+    }
+    let old_params = params::get(&input)?;
+    let new_params = params::new(max_memory, max_memory_fraction, max_time);
+    if arg.verbose {
+        params::displayln_without_resources(old_params.log_n(), old_params.r(), old_params.p());
+        params::displayln_with_resources(
+            new_params.log_n(),
+            new_params.r(),
+            new_params.p(),
+            max_memory,
+            max_memory_fraction,
+            max_time,
+        );
+    }
+    if old_params.meets(new_params) {
+        eprintln!(
+            "The encryption parameters of {} already meet the target, skipping",
+            arg.input.display()
+        );
+        return Ok(());
+    }
+    if !arg.force {
+        params::check(
+            *settings.decrypt.limits.max_memory,
+            *settings.decrypt.limits.max_memory_fraction,
+            *settings.decrypt.limits.max_time,
+            old_params.log_n(),
+            old_params.r(),
+            old_params.p(),
+        )?;
+    }
+
+    let passphrase = read_passphrase(
+        &passphrase_source,
+        &arg.input,
+        arg.passphrase.keyfile.as_deref(),
+        false,
+    )?;
+    let passphrase = passphrase::to_password(
+        passphrase,
+        arg.passphrase.keyfile.as_deref(),
+        arg.passphrase.keyfile_digest,
+    )?;
+    let output = match scryptenc::upgrade(&input, passphrase, new_params) {
+        e @ Err(ScryptencError::InvalidHeaderMac(_)) => e.context("passphrase is incorrect"),
+        e @ Err(ScryptencError::InvalidMac(_)) => e.context("the encrypted data is corrupted"),
+        o => o.context("the header in the encrypted data is invalid"),
+    }?
+    .expect("encryption parameters should not meet the target");
+    let output = if armored {
+        armor::encode(output).into_bytes()
+    } else {
+        output
+    };
+    output::replace_file(&arg.input, &output)?;
+    Ok(())
+}
+
+/// Runs the `check` command.
+fn run_check(
+    arg: &Check,
+    config: Option<&Path>,
+    #[cfg(feature = "json")] json: bool,
+) -> anyhow::Result<()> {
+    let mut settings = config::load(config)?.decrypt;
+    let origin = Origin::CommandLine;
+    settings.limits.apply(&arg.limits, &origin);
+    settings.passphrase.set(arg.passphrase.source(), &origin);
+    let limits = check::Limits {
+        max_memory: *settings.limits.max_memory,
+        max_memory_fraction: *settings.limits.max_memory_fraction,
+        max_time: *settings.limits.max_time,
+        force: arg.force,
+    };
+
+    if settings.passphrase.is_stdin() {
+        for path in &arg.input {
+            ensure_stdin_does_not_conflict(path)?;
+        }
+    }
+    let read_password = || {
+        let passphrase =
+            if arg.passphrase.keyfile.is_some() && settings.passphrase.origin() != &origin {
+                None
+            } else {
+                Some(settings.passphrase.read(false)?)
+            };
+        passphrase::to_password(
+            passphrase,
+            arg.passphrase.keyfile.as_deref(),
+            arg.passphrase.keyfile_digest,
+        )
+    };
+    #[cfg(unix)]
+    let agent = (!arg.no_agent).then(agent::socket_path);
+    #[cfg(not(unix))]
+    let agent: Option<std::path::PathBuf> = None;
+    let entries = check::run(&arg.input, read_password, agent.as_deref(), &limits)?;
+
+    #[cfg(feature = "json")]
+    if json {
+        for entry in &entries {
+            let output = serde_json::to_string(&check::Report::new(entry))
+                .context("could not serialize as JSON")?;
+            println!("{output}");
+        }
+        return check::result(&entries).map_err(anyhow::Error::from);
+    }
+    check::display(&entries);
+    check::result(&entries)?;
+    Ok(())
+}
+
+/// Returns the encryption parameters to encrypt the edited data with.
+///
+/// The parameters of the file are kept unless `limits` is specified and the
+/// parameters computed from it are stronger.
+fn edited_params(old_params: scryptenc::Params, limits: Option<&config::Limits>) -> scrypt::Params {
+    let params = scrypt::Params::new(
+        old_params.log_n(),
+        old_params.r(),
+        old_params.p(),
+        scrypt::Params::RECOMMENDED_LEN,
+    )
+    .expect("encryption parameters should be valid");
+    let Some(limits) = limits else {
+        return params;
+    };
+    let new_params = params::new(
+        *limits.max_memory,
+        *limits.max_memory_fraction,
+        *limits.max_time,
+    );
+    if old_params.meets(new_params) {
+        params
+    } else {
+        new_params
+    }
+}
+
+/// Runs the `edit` command.
+fn run_edit(arg: &Edit, config: Option<&Path>) -> anyhow::Result<()> {
+    let settings = config::load(config)?;
+    let origin = Origin::CommandLine;
+    let mut decrypt_settings = settings.decrypt;
+    decrypt_settings
+        .passphrase
+        .set(arg.passphrase.source(), &origin);
+    let mut encrypt_settings = settings.encrypt;
+    encrypt_settings.limits.apply(&arg.limits, &origin);
+
+    let input = input::read(&arg.input)?;
+    let armored = armor::is_armored(&input);
+    let input = if armored {
+        armor::decode(input).context("could not decode the ASCII-armored data")?
+    } else {
+        input
+    };
+    if envelope::is_envelope(&input) {
+        bail!("cannot edit an envelope; decrypt it and encrypt it again instead");
+    }
+    let old_params = params::get(&input)?;
+    check_params(
+        &decrypt_settings.limits,
+        (old_params.log_n(), old_params.r(), old_params.p()),
+        false,
+        arg.force,
+    )?;
+
+    let passphrase = read_passphrase(
+        &decrypt_settings.passphrase,
+        &arg.input,
+        arg.passphrase.keyfile.as_deref(),
+        false,
+    )?;
+    let passphrase = passphrase::to_password(
+        passphrase,
+        arg.passphrase.keyfile.as_deref(),
+        arg.passphrase.keyfile_digest,
+    )?;
+    let cipher = match Decryptor::new(&input, &passphrase) {
+        c @ Err(ScryptencError::InvalidHeaderMac(_)) => c.context("passphrase is incorrect"),
+        c => c.context("the header in the encrypted data is invalid"),
+    }?;
+    let plaintext = cipher
+        .decrypt_to_vec()
+        .context("the encrypted data is corrupted")?;
+    let compression = if arg.no_decompress {
+        None
+    } else {
+        compress::detect(&plaintext)
+    };
+    let plaintext = if compression.is_some() {
+        decompress(plaintext, arg.max_decompressed_size)?
+    } else {
+        plaintext
+    };
+
+    // The temporary file is shredded on any error until the contents
+    // are changed, since there are no changes to recover until then.
+    let file = edit::TempFile::new(&arg.input, &plaintext)?;
+    edit::launch_editor(file.path())?;
+    let edited = file.read()?;
+    if edited == plaintext {
+        eprintln!(
+            "{} was not changed, skipping re-encryption",
+            arg.input.display()
+        );
+        return Ok(());
+    }
+
+    let result = (|| {
+        let params = edited_params(old_params, arg.upgrade.then_some(&encrypt_settings.limits));
+        let edited = if let Some(compression) = compression {
+            compress::compress(edited, compression).context("could not compress data")?
+        } else {
+            edited
+        };
+        let output = Encryptor::with_params(&edited, passphrase, params).encrypt_to_vec();
+        let output = if armored {
+            armor::encode(output).into_bytes()
+        } else {
+            output
+        };
+        output::replace_file(&arg.input, &output)
+    })();
+    if result.is_err() {
+        eprintln!("the edited data was kept in {}", file.keep().display());
+    }
+    result?;
+    Ok(())
+}
+
+/// Runs the `passphrase add` command.
+fn run_add_passphrase(arg: &AddPassphrase, config: Option<&Path>) -> anyhow::Result<()> {
+    let settings = config::load(config)?;
+    let mut encrypt_settings = settings.encrypt;
+    let origin = Origin::CommandLine;
+    encrypt_settings.limits.apply(&arg.limits, &origin);
+    let mut passphrase_source = settings.decrypt.passphrase;
+    passphrase_source.set(arg.passphrase.source(), &origin);
+
+    let (input, armored) = read_envelope(&arg.input)?;
+    let params = params::new(
+        *encrypt_settings.limits.max_memory,
+        *encrypt_settings.limits.max_memory_fraction,
+        *encrypt_settings.limits.max_time,
+    );
+
+    let new_passphrase_source = arg.new_passphrase_source();
+    if !arg.generate_passphrase && new_passphrase_source.is_stdin() && passphrase_source.is_stdin()
+    {
+        bail!("cannot read both passphrase and new passphrase from standard input");
+    }
+
+    let passphrase = read_passphrase(
+        &passphrase_source,
+        &arg.input,
+        arg.passphrase.keyfile.as_deref(),
+        false,
+    )?;
+    let passphrase = passphrase::to_password(
+        passphrase,
+        arg.passphrase.keyfile.as_deref(),
+        arg.passphrase.keyfile_digest,
+    )?;
+    let new_passphrase = if arg.generate_passphrase {
+        let passphrase = passphrase::generate(None, None);
+        eprintln!("Generated passphrase: {passphrase}");
+        passphrase
+    } else {
+        let source = new_passphrase_source;
+        if source.is_tty() {
+            eprintln!("Enter the new passphrase.");
+        }
+        let passphrase = source.read(true)?;
+        passphrase::check_strength(&passphrase, arg.min_entropy, arg.allow_weak_passphrase)?;
+        passphrase
+    };
+
+    let output = envelope_context(envelope::add_passphrase(
+        &input,
+        passphrase,
+        new_passphrase,
+        params,
+    ))?;
+    replace_envelope(&arg.input, output, armored)?;
+    Ok(())
+}
+
+/// Runs the `passphrase remove` command.
+fn run_remove_passphrase(arg: &RemovePassphrase, config: Option<&Path>) -> anyhow::Result<()> {
+    let (input, armored) = read_envelope(&arg.input)?;
+    let output = if let Some(index) = arg.slot {
+        envelope_context(envelope::remove_slot(&input, index))?
+    } else {
+        let mut passphrase_source = config::load(config)?.decrypt.passphrase;
+        passphrase_source.set(arg.passphrase.source(), &Origin::CommandLine);
+        let passphrase = read_passphrase(
+            &passphrase_source,
+            &arg.input,
+            arg.passphrase.keyfile.as_deref(),
+            false,
+        )?;
+        let passphrase = passphrase::to_password(
+            passphrase,
+            arg.passphrase.keyfile.as_deref(),
+            arg.passphrase.keyfile_digest,
+        )?;
+        envelope_context(envelope::remove_passphrase(&input, passphrase))?
+    };
+    replace_envelope(&arg.input, output, armored)?;
+    Ok(())
+}
+
+/// Runs the `passphrase list` command.
+fn run_list_passphrases(
+    arg: &ListPassphrases,
+    #[cfg(feature = "json")] json: bool,
+) -> anyhow::Result<()> {
+    let input = input::read_encrypted(&arg.input)?;
+    let slots = envelope_context(envelope::slots(&input))?;
+
+    #[cfg(feature = "json")]
+    if json {
+        for (index, params) in slots.iter().enumerate() {
+            let output = serde_json::to_string(&slot::Report::new(index, params))
+                .context("could not serialize as JSON")?;
+            println!("{output}");
+        }
+        return Ok(());
+    }
+    slot::display(&slots);
+    Ok(())
+}
+
+/// Runs the `git-filter` command.
+fn run_git_filter(arg: &GitFilter, config: Option<&Path>) -> anyhow::Result<()> {
+    let input_path = match (arg.mode, &arg.path) {
+        (GitFilterMode::Diff, Some(path)) => path,
+        _ => Path::new("-"),
+    };
+    let passphrase_source = arg.passphrase.source();
+    if passphrase_source
+        .as_ref()
+        .is_some_and(passphrase::Source::is_stdin)
+    {
+        ensure_stdin_does_not_conflict(input_path)?;
+    }
+    let read_password = || {
+        let passphrase = match (&passphrase_source, &arg.passphrase.keyfile) {
+            (None, Some(_)) => None,
+            (source, _) => Some(
+                source
+                    .as_ref()
+                    .unwrap_or(&passphrase::Source::TtyOnce)
+                    .read(false)?,
+            ),
+        };
+        passphrase::to_password(
+            passphrase,
+            arg.passphrase.keyfile.as_deref(),
+            arg.passphrase.keyfile_digest,
+        )
+    };
+
+    let input = input::read(input_path)?;
+    let output = if arg.mode == GitFilterMode::Clean {
+        let params = scrypt::Params::new(arg.log_n, arg.r, arg.p, scrypt::Params::RECOMMENDED_LEN)
+            .expect("encryption parameters should be valid");
+        let path = arg.path.as_deref().unwrap_or_else(|| Path::new(""));
+        let passphrase = read_password()?;
+        git::Cleaner::new(&passphrase, params, git::repository_salt()?).clean(input, path)
+    } else {
+        if let Ok(params) = scryptenc::Params::new(&input) {
+            let settings = config::load(config)?.decrypt;
+            params::check(
+                *settings.limits.max_memory,
+                *settings.limits.max_memory_fraction,
+                *settings.limits.max_time,
+                params.log_n(),
+                params.r(),
+                params.p(),
+            )?;
         }
+        #[cfg(unix)]
+        let agent = (!arg.no_agent).then(agent::socket_path);
+        #[cfg(not(unix))]
+        let agent: Option<std::path::PathBuf> = None;
+        git::smudge(input, read_password, agent.as_deref())?
+    };
+    output::write_to_stdout(&output)?;
+    Ok(())
+}
+
+/// Runs the `git-init` command.
+fn run_git_init(arg: GitInit) -> anyhow::Result<()> {
+    let options = git::FilterOptions {
+        params: scrypt::Params::new(arg.log_n, arg.r, arg.p, scrypt::Params::RECOMMENDED_LEN)
+            .expect("encryption parameters should be valid"),
+        passphrase_from_env: arg.passphrase_from_env,
+        passphrase_from_file: arg.passphrase_from_file,
+        passphrase_from_keyring: arg.passphrase_from_keyring,
+        passphrase_from_command: arg.passphrase_from_command,
+        keyfile: arg.keyfile,
+        keyfile_digest: arg.keyfile_digest,
+    };
+    git::init(&arg.patterns, &options)?;
+    Ok(())
+}
+
+/// Runs the `agent` command.
+#[cfg(unix)]
+fn run_agent(arg: Agent) -> anyhow::Result<()> {
+    let socket = arg.socket.unwrap_or_else(agent::socket_path);
+    if arg.clear {
+        agent::clear(&socket)?;
+    } else {
+        agent::serve(&socket, *arg.ttl)?;
+    }
+    Ok(())
+}
+
+/// Runs the `keygen` command.
+#[cfg(feature = "x25519")]
+fn run_keygen(arg: &Keygen) -> anyhow::Result<()> {
+    if let Some(file) = &arg.to_public {
+        for identity in recipient::read_identity_file(file)? {
+            println!("{}", Recipient::from(&identity));
+        }
+    } else {
+        let identity = recipient::generate();
+        if let Some(file) = &arg.output {
+            recipient::write_identity_file(file, &identity)?;
+        } else {
+            print!("{}", recipient::to_identity_file(&identity));
+        }
+        eprintln!("Public key: {}", Recipient::from(&identity));
+    }
+    Ok(())
+}
+
+/// Runs the `bench` command.
+fn run_bench(
+    arg: &Bench,
+    config: Option<&Path>,
+    #[cfg(feature = "json")] json: bool,
+) -> anyhow::Result<()> {
+    calibration::set_mode(calibration::Mode::new(true, arg.no_calibration_cache));
+    let mut settings = config::load(config)?.encrypt;
+    let origin = Origin::CommandLine;
+    settings.limits.apply(&arg.limits, &origin);
+    let (max_memory, max_memory_fraction, max_time) = (
+        *settings.limits.max_memory,
+        *settings.limits.max_memory_fraction,
+        *settings.limits.max_time,
+    );
+
+    let params = params::new(max_memory, max_memory_fraction, max_time);
+    let mem_limit = params::get_memory_to_use(max_memory, max_memory_fraction);
+    let measurements = bench::run(&arg.log_n, &arg.r, &arg.p, mem_limit);
+
+    #[cfg(feature = "json")]
+    if json {
+        let report = bench::Report::new(
+            params::operations_per_second(),
+            mem_limit,
+            *max_time,
+            params,
+            &measurements,
+        );
+        let output = serde_json::to_string(&report).context("could not serialize as JSON")?;
+        println!("{output}");
+        return Ok(());
+    }
+    bench::display(
+        params::operations_per_second(),
+        mem_limit,
+        *max_time,
+        params,
+        &measurements,
+    );
+    Ok(())
+}
+
+/// Runs the `config show` command.
+fn run_show_config(config: Option<&Path>) -> anyhow::Result<()> {
+    let mut settings = config::load(config)?;
+    settings
+        .encrypt
+        .apply(&Encrypt::from_env()?, &Origin::Environment);
+    settings
+        .decrypt
+        .apply(&Decrypt::from_env()?, &Origin::Environment);
+    settings.display();
+    Ok(())
+}
+
+/// Runs the program and returns the result.
+pub fn run(opt: Opt) -> anyhow::Result<()> {
+    let config = opt.config.as_deref();
+    #[cfg(feature = "json")]
+    let json = opt.json;
+
+    match opt.command {
+        Command::Encrypt(arg) => run_encrypt(
+            &arg,
+            config,
+            #[cfg(feature = "json")]
+            json,
+        ),
+        Command::Decrypt(arg) => run_decrypt(
+            &arg,
+            config,
+            #[cfg(feature = "json")]
+            json,
+        ),
+        Command::Information(arg) => run_information(
+            &arg,
+            #[cfg(feature = "json")]
+            json,
+        ),
+        Command::Audit(arg) => run_audit(
+            &arg,
+            #[cfg(feature = "json")]
+            json,
+        ),
+        Command::Upgrade(arg) => run_upgrade(&arg, config),
+        Command::Check(arg) => run_check(
+            &arg,
+            config,
+            #[cfg(feature = "json")]
+            json,
+        ),
+        Command::Edit(arg) => run_edit(&arg, config),
+        Command::Passphrase(arg) => match arg.command {
+            PassphraseCommand::Add(arg) => run_add_passphrase(&arg, config),
+            PassphraseCommand::Remove(arg) => run_remove_passphrase(&arg, config),
+            PassphraseCommand::List(arg) => run_list_passphrases(
+                &arg,
+                #[cfg(feature = "json")]
+                json,
+            ),
+        },
+        Command::GitFilter(arg) => run_git_filter(&arg, config),
+        Command::GitInit(arg) => run_git_init(arg),
+        #[cfg(unix)]
+        Command::Agent(arg) => run_agent(arg),
+        Command::GeneratePassphrase(arg) => {
+            println!("{}", passphrase::generate(arg.words, arg.chars));
+            Ok(())
+        }
+        #[cfg(feature = "x25519")]
+        Command::Keygen(arg) => run_keygen(&arg),
+        Command::Bench(arg) => run_bench(
+            &arg,
+            config,
+            #[cfg(feature = "json")]
+            json,
+        ),
+        Command::Config(arg) => match arg.command {
+            ConfigCommand::Show => run_show_config(config),
+        },
         Command::Completion(arg) => {
             Opt::print_completion(arg.shell);
+            Ok(())
         }
     }
-    Ok(())
 }
@@ -0,0 +1,358 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    fmt,
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use byte_unit::UnitType;
+use scryptenc::{HEADER_SIZE, TAG_SIZE, armor, envelope};
+use thiserror::Error;
+
+use crate::{input, params};
+
+/// The error type for this module.
+#[derive(Debug, Error)]
+#[error("found {non_compliant} non-compliant files and {invalid} files which could not be checked")]
+pub struct Error {
+    non_compliant: usize,
+    invalid: usize,
+}
+
+/// The parameter policy.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Policy {
+    pub min_log_n: Option<u8>,
+    pub min_memory: Option<u64>,
+}
+
+impl Policy {
+    /// Returns the ways in which `params` does not comply with this policy.
+    pub fn check(&self, params: scryptenc::Params) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        if let Some(min) = self.min_log_n.filter(|&min| params.log_n() < min) {
+            violations.push(Violation::LogN {
+                actual: params.log_n(),
+                min,
+            });
+        }
+        let memory = 128 * params.n() * u64::from(params.r());
+        if let Some(min) = self.min_memory.filter(|&min| memory < min) {
+            violations.push(Violation::Memory {
+                actual: memory,
+                min,
+            });
+        }
+        violations
+    }
+}
+
+/// A way in which the encryption parameters do not comply with the policy.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Violation {
+    /// The work parameter N is too small.
+    LogN { actual: u8, min: u8 },
+
+    /// Computing the derived key requires too little memory.
+    Memory { actual: u64, min: u64 },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LogN { actual, min } => write!(f, "N = 2^{actual} is less than 2^{min}"),
+            Self::Memory { actual, min } => {
+                let format_bytes = |bytes: u64| {
+                    format!(
+                        "{:#.1}",
+                        byte_unit::Byte::from(bytes).get_appropriate_unit(UnitType::Binary)
+                    )
+                };
+                write!(
+                    f,
+                    "{} of memory is less than {}",
+                    format_bytes(*actual),
+                    format_bytes(*min)
+                )
+            }
+        }
+    }
+}
+
+/// The result of checking a file.
+#[derive(Debug)]
+pub enum Status {
+    /// The file complies with the policy.
+    ///
+    /// This has no encryption parameters if the file is an envelope without
+    /// passphrase slots.
+    Compliant(#[cfg_attr(not(feature = "json"), allow(dead_code))] Option<scryptenc::Params>),
+
+    /// The file does not comply with the policy.
+    NonCompliant(scryptenc::Params, Vec<Violation>),
+
+    /// The file could not be read, or is not scrypt encrypted data.
+    Invalid(anyhow::Error),
+}
+
+/// The result of checking a file.
+#[derive(Debug)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub status: Status,
+}
+
+/// Reads the encryption parameters from the header of the file.
+///
+/// The encryption parameters of each passphrase slot are returned if the file
+/// is an envelope. Only the header is read unless the file is ASCII-armored or
+/// an envelope.
+fn read_params(path: &Path) -> anyhow::Result<Vec<scryptenc::Params>> {
+    let data = if path == Path::new("-") {
+        input::read_encrypted(path)?
+    } else {
+        let mut file = File::open(path)
+            .with_context(|| format!("could not read data from {}", path.display()))?;
+        let mut data = Vec::with_capacity(HEADER_SIZE + TAG_SIZE);
+        (&mut file)
+            .take((HEADER_SIZE + TAG_SIZE) as u64)
+            .read_to_end(&mut data)
+            .with_context(|| format!("could not read data from {}", path.display()))?;
+        let armored = armor::is_armored(&data);
+        if armored || envelope::is_envelope(&data) {
+            file.read_to_end(&mut data)
+                .with_context(|| format!("could not read data from {}", path.display()))?;
+        }
+        if armored {
+            data = armor::decode(data).context("could not decode the ASCII-armored data")?;
+        }
+        data
+    };
+    if envelope::is_envelope(&data) {
+        return envelope::slots(&data).context("the envelope is invalid");
+    }
+    params::get(&data).map(|params| vec![params])
+}
+
+/// Checks the file against `policy`.
+///
+/// An envelope complies with the policy only if all of its passphrase slots
+/// do, since any of them can decrypt it.
+fn check(path: PathBuf, policy: &Policy) -> Entry {
+    let status = match read_params(&path) {
+        Ok(slots) => slots
+            .iter()
+            .find_map(|&params| {
+                let violations = policy.check(params);
+                (!violations.is_empty()).then(|| Status::NonCompliant(params, violations))
+            })
+            .unwrap_or_else(|| Status::Compliant(slots.first().copied())),
+        Err(err) => Status::Invalid(err),
+    };
+    Entry { path, status }
+}
+
+/// Checks all files under `path` against `policy`.
+fn walk(path: &Path, policy: &Policy, entries: &mut Vec<Entry>) {
+    let dir = match fs::read_dir(path) {
+        Ok(dir) => dir,
+        Err(err) => {
+            let err = anyhow::Error::from(err)
+                .context(format!("could not read directory {}", path.display()));
+            entries.push(Entry {
+                path: path.to_path_buf(),
+                status: Status::Invalid(err),
+            });
+            return;
+        }
+    };
+    let mut paths = Vec::new();
+    for entry in dir {
+        match entry {
+            Ok(entry) => paths.push(entry.path()),
+            Err(err) => {
+                let err = anyhow::Error::from(err)
+                    .context(format!("could not read an entry of {}", path.display()));
+                entries.push(Entry {
+                    path: path.to_path_buf(),
+                    status: Status::Invalid(err),
+                });
+            }
+        }
+    }
+    paths.sort();
+    for path in paths {
+        if fs::symlink_metadata(&path).is_ok_and(|m| m.is_dir()) {
+            walk(&path, policy, entries);
+        } else if !path.is_dir() {
+            entries.push(check(path, policy));
+        }
+    }
+}
+
+/// Checks all files in `paths` against `policy`.
+///
+/// Directories are scanned only if `recursive` is `true`.
+pub fn run(paths: &[PathBuf], recursive: bool, policy: &Policy) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    for path in paths {
+        if path != Path::new("-") && path.is_dir() {
+            if recursive {
+                walk(path, policy, &mut entries);
+            } else {
+                entries.push(Entry {
+                    path: path.clone(),
+                    status: Status::Invalid(anyhow::anyhow!(
+                        "{} is a directory (use --recursive to scan it)",
+                        path.display()
+                    )),
+                });
+            }
+        } else {
+            entries.push(check(path.clone(), policy));
+        }
+    }
+    entries
+}
+
+/// Returns [`Err`] if any of `entries` does not comply with the policy or could
+/// not be checked.
+pub fn result(entries: &[Entry]) -> Result<(), Error> {
+    let count = |f: fn(&Status) -> bool| entries.iter().filter(|e| f(&e.status)).count();
+    let non_compliant = count(|s| matches!(s, Status::NonCompliant(..)));
+    let invalid = count(|s| matches!(s, Status::Invalid(_)));
+    if non_compliant == 0 && invalid == 0 {
+        Ok(())
+    } else {
+        Err(Error {
+            non_compliant,
+            invalid,
+        })
+    }
+}
+
+/// Prints the files which do not comply with the policy or could not be
+/// checked.
+pub fn display(entries: &[Entry]) {
+    for entry in entries {
+        let path = entry.path.display();
+        match &entry.status {
+            Status::Compliant(_) => {}
+            Status::NonCompliant(params, violations) => {
+                let violations = violations
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                println!(
+                    "{path}: non-compliant (N = {}; r = {}; p = {};): {violations}",
+                    params.n(),
+                    params.r(),
+                    params.p()
+                );
+            }
+            Status::Invalid(err) => println!("{path}: error: {err:#}"),
+        }
+    }
+}
+
+/// The result of checking a file.
+#[cfg(feature = "json")]
+#[derive(Debug, serde::Serialize)]
+pub struct Report {
+    file: String,
+    status: &'static str,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    params: Option<params::Params>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    violations: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[cfg(feature = "json")]
+impl Report {
+    /// Creates a new `Report`.
+    pub fn new(entry: &Entry) -> Self {
+        let (status, params, violations, error) = match &entry.status {
+            Status::Compliant(params) => ("compliant", *params, Vec::new(), None),
+            Status::NonCompliant(params, violations) => (
+                "non_compliant",
+                Some(*params),
+                violations.iter().map(ToString::to_string).collect(),
+                None,
+            ),
+            Status::Invalid(err) => ("invalid", None, Vec::new(), Some(format!("{err:#}"))),
+        };
+        Self {
+            file: entry.path.display().to_string(),
+            status,
+            params: params.map(params::Params::new),
+            violations,
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = include_bytes!("../tests/data/data.txt.scrypt");
+
+    #[test]
+    fn check_policy() {
+        let params = scryptenc::Params::new(DATA).unwrap();
+        assert!(Policy::default().check(params).is_empty());
+        assert!(
+            Policy {
+                min_log_n: Some(10),
+                min_memory: Some(1024 * 1024),
+            }
+            .check(params)
+            .is_empty()
+        );
+        assert_eq!(
+            Policy {
+                min_log_n: Some(17),
+                min_memory: Some(64 * 1024 * 1024),
+            }
+            .check(params),
+            [
+                Violation::LogN {
+                    actual: 10,
+                    min: 17
+                },
+                Violation::Memory {
+                    actual: 1024 * 1024,
+                    min: 64 * 1024 * 1024
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn display_violation() {
+        assert_eq!(
+            Violation::LogN {
+                actual: 10,
+                min: 17
+            }
+            .to_string(),
+            "N = 2^10 is less than 2^17"
+        );
+        assert_eq!(
+            Violation::Memory {
+                actual: 1024 * 1024,
+                min: 64 * 1024 * 1024
+            }
+            .to_string(),
+            "1 MiB of memory is less than 64 MiB"
+        );
+    }
+}
@@ -0,0 +1,178 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::time::{Duration, Instant};
+
+use byte_unit::UnitType;
+use scryptenc::scrypt;
+
+/// The result of running scrypt with the specified parameters.
+#[derive(Clone, Copy, Debug)]
+pub struct Measurement {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+
+    /// The time taken to compute the derived key, or `None` if it was skipped
+    /// because it requires more memory than the limit.
+    pub time: Option<Duration>,
+}
+
+impl Measurement {
+    /// Returns the amount of memory required to compute the derived key.
+    pub fn memory(&self) -> u64 {
+        128 * (1 << self.log_n) * u64::from(self.r)
+    }
+}
+
+/// Computes the derived key with the specified parameters and returns the
+/// time taken.
+fn measure(log_n: u8, r: u32, p: u32) -> Duration {
+    let params = scrypt::Params::new(log_n, r, p, scrypt::Params::RECOMMENDED_LEN)
+        .expect("encryption parameters should be valid");
+    let mut dk = [u8::default(); 64];
+
+    let start = Instant::now();
+    scrypt::scrypt(Default::default(), Default::default(), &params, &mut dk)
+        .expect("derived key size should be valid");
+    start.elapsed()
+}
+
+/// Runs scrypt for each combination of the parameters.
+///
+/// The combinations which require more memory than `mem_limit` are skipped.
+pub fn run(log_n: &[u8], r: &[u32], p: &[u32], mem_limit: u64) -> Vec<Measurement> {
+    let mut measurements = Vec::with_capacity(log_n.len() * r.len() * p.len());
+    for &log_n in log_n {
+        for &r in r {
+            for &p in p {
+                let mut measurement = Measurement {
+                    log_n,
+                    r,
+                    p,
+                    time: None,
+                };
+                if measurement.memory() <= mem_limit {
+                    measurement.time = Some(measure(log_n, r, p));
+                }
+                measurements.push(measurement);
+            }
+        }
+    }
+    measurements
+}
+
+/// Prints the result of the benchmark.
+pub fn display(
+    operations_per_second: u64,
+    mem_limit: u64,
+    time_limit: Duration,
+    selected: scrypt::Params,
+    measurements: &[Measurement],
+) {
+    let mem_limit = byte_unit::Byte::from(mem_limit).get_appropriate_unit(UnitType::Binary);
+    println!("Salsa20/8 cores per second: {operations_per_second}");
+    println!(
+        "Parameters selected: N = {}; r = {}; p = {};",
+        1_u64 << selected.log_n(),
+        selected.r(),
+        selected.p()
+    );
+    println!("    for at most {mem_limit:#.1} of memory and {time_limit:.1?} of CPU time.");
+    println!();
+
+    println!(
+        "{:>5} {:>3} {:>3} {:>12} {:>12}",
+        "log_n", "r", "p", "memory", "time"
+    );
+    for measurement in measurements {
+        let memory = format!(
+            "{:#.1}",
+            byte_unit::Byte::from(measurement.memory()).get_appropriate_unit(UnitType::Binary)
+        );
+        let time = measurement
+            .time
+            .map_or_else(|| String::from("skipped"), |time| format!("{time:.1?}"));
+        println!(
+            "{:>5} {:>3} {:>3} {:>12} {:>12}",
+            measurement.log_n, measurement.r, measurement.p, memory, time
+        );
+    }
+}
+
+/// The result of the benchmark.
+#[cfg(feature = "json")]
+#[derive(Debug, serde::Serialize)]
+pub struct Report {
+    operations_per_second: u64,
+    memory_limit: u64,
+    time_limit: f64,
+    selected: crate::params::Params,
+    measurements: Vec<MeasurementReport>,
+}
+
+#[cfg(feature = "json")]
+impl Report {
+    /// Creates a new `Report`.
+    pub fn new(
+        operations_per_second: u64,
+        memory_limit: u64,
+        time_limit: Duration,
+        selected: scrypt::Params,
+        measurements: &[Measurement],
+    ) -> Self {
+        Self {
+            operations_per_second,
+            memory_limit,
+            time_limit: time_limit.as_secs_f64(),
+            selected: selected.into(),
+            measurements: measurements
+                .iter()
+                .map(|m| MeasurementReport {
+                    n: 1 << m.log_n,
+                    r: m.r,
+                    p: m.p,
+                    memory: m.memory(),
+                    time: m.time.map(|t| t.as_secs_f64()),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The result of running scrypt with the specified parameters.
+#[cfg(feature = "json")]
+#[derive(Debug, serde::Serialize)]
+struct MeasurementReport {
+    #[serde(rename = "N")]
+    n: u64,
+    r: u32,
+    p: u32,
+    memory: u64,
+    time: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory() {
+        let measurement = Measurement {
+            log_n: 10,
+            r: 8,
+            p: 1,
+            time: None,
+        };
+        assert_eq!(measurement.memory(), 1024 * 1024);
+    }
+
+    #[test]
+    fn skip_if_exceeds_memory_limit() {
+        let measurements = run(&[10, 11], &[8], &[1], 1024 * 1024);
+        assert_eq!(measurements.len(), 2);
+        assert!(measurements[0].time.is_some());
+        assert!(measurements[1].time.is_none());
+    }
+}
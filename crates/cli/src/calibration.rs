@@ -0,0 +1,241 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The duration for which the cached calibration is valid.
+const EXPIRY: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The name of the cache file.
+const CACHE_FILE_NAME: &str = "calibration";
+
+static MODE: OnceLock<Mode> = OnceLock::new();
+
+/// How to use the calibration cache.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Mode {
+    /// Use the cached calibration if it is valid, and cache the result
+    /// otherwise.
+    #[default]
+    Cache,
+
+    /// Ignore the cached calibration, and cache the result.
+    Recalibrate,
+
+    /// Neither read nor write the cache.
+    Disabled,
+}
+
+impl Mode {
+    /// Creates a new `Mode` from the command-line options.
+    pub const fn new(recalibrate: bool, no_calibration_cache: bool) -> Self {
+        match (recalibrate, no_calibration_cache) {
+            (_, true) => Self::Disabled,
+            (true, false) => Self::Recalibrate,
+            (false, false) => Self::Cache,
+        }
+    }
+}
+
+/// Sets how to use the calibration cache.
+///
+/// This must be called before the calibration is performed.
+pub fn set_mode(mode: Mode) {
+    MODE.set(mode)
+        .expect("calibration cache mode should be set only once");
+}
+
+/// The result of the calibration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Calibration {
+    version: String,
+    cpu: String,
+    timestamp: u64,
+    operations_per_second: u64,
+}
+
+impl Calibration {
+    /// Parses the contents of the cache file.
+    fn parse(s: &str) -> Option<Self> {
+        let (mut version, mut cpu, mut timestamp, mut operations_per_second) =
+            (None, None, None, None);
+        for line in s.lines() {
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            match key.trim() {
+                "version" => version = Some(value.to_owned()),
+                "cpu" => cpu = Some(value.to_owned()),
+                "timestamp" => timestamp = value.parse().ok(),
+                "operations_per_second" => operations_per_second = value.parse().ok(),
+                _ => {}
+            }
+        }
+        Some(Self {
+            version: version?,
+            cpu: cpu?,
+            timestamp: timestamp?,
+            operations_per_second: operations_per_second?,
+        })
+    }
+
+    /// Returns `true` if this was measured on the same CPU by the same version
+    /// and has not expired.
+    fn is_valid(&self, cpu: &str, now: u64) -> bool {
+        self.version == env!("CARGO_PKG_VERSION")
+            && self.cpu == cpu
+            && self.timestamp <= now
+            && now - self.timestamp < EXPIRY.as_secs()
+            && self.operations_per_second > 0
+    }
+}
+
+impl fmt::Display for Calibration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "version = {}", self.version)?;
+        writeln!(f, "cpu = {}", self.cpu)?;
+        writeln!(f, "timestamp = {}", self.timestamp)?;
+        writeln!(f, "operations_per_second = {}", self.operations_per_second)
+    }
+}
+
+/// Returns the cache directory of this program.
+///
+/// This is `$XDG_CACHE_HOME/rscrypt` or `$HOME/.cache/rscrypt` on Unix, and
+/// `%LOCALAPPDATA%\rscrypt` on Windows.
+pub fn cache_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+    };
+    base.map(|dir| dir.join("rscrypt"))
+}
+
+/// Returns the current time as seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or_else(|_| u64::default(), |d| d.as_secs())
+}
+
+/// Reads the cached calibration.
+fn read(path: &Path, cpu: &str) -> Option<u64> {
+    let calibration = Calibration::parse(&fs::read_to_string(path).ok()?)?;
+    calibration
+        .is_valid(cpu, now())
+        .then_some(calibration.operations_per_second)
+}
+
+/// Writes the calibration to the cache.
+///
+/// The cache file is replaced atomically so that a concurrent reader never
+/// sees a partially written file.
+fn write(path: &Path, cpu: &str, operations_per_second: u64) -> std::io::Result<()> {
+    let calibration = Calibration {
+        version: env!("CARGO_PKG_VERSION").to_owned(),
+        cpu: cpu.to_owned(),
+        timestamp: now(),
+        operations_per_second,
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&tmp, calibration.to_string())?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+/// Returns the number of times Salsa20/8 cores can be executed per second.
+///
+/// The result of `measure` is cached for each CPU model and version of this
+/// program. Errors related to the cache are ignored.
+pub fn operations_per_second(cpu: &str, measure: impl FnOnce() -> u64) -> u64 {
+    let mode = MODE.get().copied().unwrap_or_default();
+    let path = cache_dir().map(|dir| dir.join(CACHE_FILE_NAME));
+    let cpu = cpu.replace(['\r', '\n'], " ");
+
+    if let (Mode::Cache, Some(path)) = (mode, &path) {
+        if let Some(operations_per_second) = read(path, &cpu) {
+            return operations_per_second;
+        }
+    }
+    let operations_per_second = measure();
+    if let (Mode::Cache | Mode::Recalibrate, Some(path)) = (mode, &path) {
+        let _ = write(path, &cpu, operations_per_second);
+    }
+    operations_per_second
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calibration() -> Calibration {
+        Calibration {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            cpu: String::from("Example CPU @ 3.00GHz"),
+            timestamp: 1_000_000,
+            operations_per_second: 200_000,
+        }
+    }
+
+    #[test]
+    fn mode() {
+        assert_eq!(Mode::new(false, false), Mode::Cache);
+        assert_eq!(Mode::new(true, false), Mode::Recalibrate);
+        assert_eq!(Mode::new(false, true), Mode::Disabled);
+        assert_eq!(Mode::new(true, true), Mode::Disabled);
+    }
+
+    #[test]
+    fn round_trip() {
+        let calibration = calibration();
+        assert_eq!(
+            Calibration::parse(&calibration.to_string()),
+            Some(calibration)
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(Calibration::parse(""), None);
+        assert_eq!(Calibration::parse("version = 0.1.0\n"), None);
+        assert_eq!(
+            Calibration::parse(
+                &calibration()
+                    .to_string()
+                    .replace("200000", "two hundred thousand")
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn is_valid() {
+        let calibration = calibration();
+        let cpu = "Example CPU @ 3.00GHz";
+        assert!(calibration.is_valid(cpu, 1_000_000));
+        assert!(calibration.is_valid(cpu, 1_000_000 + EXPIRY.as_secs() - 1));
+        assert!(!calibration.is_valid(cpu, 1_000_000 + EXPIRY.as_secs()));
+        assert!(!calibration.is_valid(cpu, 999_999));
+        assert!(!calibration.is_valid("Other CPU", 1_000_000));
+        assert!(
+            !Calibration {
+                version: String::from("0.0.0"),
+                ..calibration
+            }
+            .is_valid(cpu, 1_000_000)
+        );
+    }
+}
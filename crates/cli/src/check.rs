@@ -0,0 +1,297 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use scryptenc::{
+    Decryptor, Error as ScryptencError, armor,
+    envelope::{self, Error as EnvelopeError},
+};
+use thiserror::Error;
+
+#[cfg(unix)]
+use crate::agent;
+use crate::{
+    cli::{Byte, Rate, Time},
+    input, params,
+    passphrase::LazyPassword,
+};
+
+/// The error type for this module.
+#[derive(Debug, Error)]
+#[error("{failed} of {total} files failed the check")]
+pub struct Error {
+    failed: usize,
+    total: usize,
+}
+
+/// The resource limits to compute the derived key.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    pub max_memory: Option<Byte>,
+    pub max_memory_fraction: Rate,
+    pub max_time: Time,
+    pub force: bool,
+}
+
+/// The result of checking a file.
+#[derive(Debug)]
+pub enum Status {
+    /// The file is intact.
+    Ok,
+
+    /// The passphrase is wrong.
+    WrongPassphrase,
+
+    /// The file is corrupted.
+    Corrupted(anyhow::Error),
+
+    /// The file is not scrypt encrypted data.
+    NotScrypt(anyhow::Error),
+
+    /// The file could not be checked.
+    Error(anyhow::Error),
+}
+
+#[cfg(feature = "json")]
+impl Status {
+    /// Returns the name of this status.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::WrongPassphrase => "wrong_passphrase",
+            Self::Corrupted(_) => "corrupted",
+            Self::NotScrypt(_) => "not_scrypt",
+            Self::Error(_) => "error",
+        }
+    }
+
+    /// Returns the error which caused this status.
+    pub const fn error(&self) -> Option<&anyhow::Error> {
+        match self {
+            Self::Ok | Self::WrongPassphrase => None,
+            Self::Corrupted(err) | Self::NotScrypt(err) | Self::Error(err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok => write!(f, "OK"),
+            Self::WrongPassphrase => write!(f, "wrong passphrase"),
+            Self::Corrupted(err) => write!(f, "corrupted ({err:#})"),
+            Self::NotScrypt(err) => write!(f, "not a scrypt file ({err:#})"),
+            Self::Error(err) => write!(f, "error ({err:#})"),
+        }
+    }
+}
+
+impl From<ScryptencError> for Status {
+    fn from(err: ScryptencError) -> Self {
+        match err {
+            ScryptencError::InvalidHeaderMac(_) => Self::WrongPassphrase,
+            ScryptencError::InvalidChecksum | ScryptencError::InvalidMac(_) => {
+                Self::Corrupted(err.into())
+            }
+            ScryptencError::InvalidLength
+            | ScryptencError::InvalidMagicNumber
+            | ScryptencError::UnknownVersion(_)
+            | ScryptencError::InvalidParams(_) => Self::NotScrypt(err.into()),
+        }
+    }
+}
+
+/// The result of checking a file.
+#[derive(Debug)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub status: Status,
+}
+
+/// Verifies the MAC of the data without writing the plaintext anywhere.
+///
+/// The derived key cached by the agent on the socket at `agent` is used if it
+/// is available.
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn verify(
+    data: &[u8],
+    passphrase: &mut LazyPassword<impl FnOnce() -> anyhow::Result<Vec<u8>>>,
+    agent: Option<&Path>,
+    limits: &Limits,
+) -> anyhow::Result<Status> {
+    let params = match scryptenc::Params::new(data) {
+        Ok(params) => params,
+        Err(err) => return Ok(err.into()),
+    };
+    if !limits.force {
+        if let Err(err) = params::check(
+            limits.max_memory,
+            limits.max_memory_fraction,
+            limits.max_time,
+            params.log_n(),
+            params.r(),
+            params.p(),
+        ) {
+            return Ok(Status::Error(err.into()));
+        }
+    }
+    #[cfg(unix)]
+    let cached = agent.and_then(|socket| agent::decryptor(socket, &data));
+    #[cfg(not(unix))]
+    let cached = None;
+    let cipher = if let Some(cipher) = cached {
+        cipher
+    } else {
+        match Decryptor::new(&data, passphrase.get()?) {
+            Ok(cipher) => {
+                #[cfg(unix)]
+                if let Some(socket) = agent {
+                    agent::put(socket, data, &cipher.derived_key());
+                }
+                cipher
+            }
+            Err(err) => return Ok(err.into()),
+        }
+    };
+    Ok(match cipher.verify() {
+        Ok(()) => Status::Ok,
+        Err(err) => err.into(),
+    })
+}
+
+/// Verifies the envelope by decrypting it without writing the plaintext
+/// anywhere.
+///
+/// The encryption parameters of all slots are checked against `limits`, since
+/// any of them may be tried before the matching one is found.
+fn verify_envelope(
+    data: &[u8],
+    passphrase: &mut LazyPassword<impl FnOnce() -> anyhow::Result<Vec<u8>>>,
+    limits: &Limits,
+) -> anyhow::Result<Status> {
+    let slots = match envelope::slots(data) {
+        Ok(slots) => slots,
+        Err(err) => return Ok(Status::Corrupted(err.into())),
+    };
+    if !limits.force {
+        for params in &slots {
+            if let Err(err) = params::check(
+                limits.max_memory,
+                limits.max_memory_fraction,
+                limits.max_time,
+                params.log_n(),
+                params.r(),
+                params.p(),
+            ) {
+                return Ok(Status::Error(err.into()));
+            }
+        }
+    }
+    Ok(match envelope::decrypt(data, passphrase.get()?) {
+        Ok(_) => Status::Ok,
+        Err(EnvelopeError::NoMatchingSlot) => Status::WrongPassphrase,
+        Err(err) => Status::Corrupted(err.into()),
+    })
+}
+
+/// Checks the file with `passphrase`.
+fn check(
+    path: &Path,
+    passphrase: &mut LazyPassword<impl FnOnce() -> anyhow::Result<Vec<u8>>>,
+    agent: Option<&Path>,
+    limits: &Limits,
+) -> anyhow::Result<Status> {
+    let data = match input::read(path) {
+        Ok(data) => data,
+        Err(err) => return Ok(Status::Error(err)),
+    };
+    let data = if armor::is_armored(&data) {
+        match armor::decode(data).context("could not decode the ASCII-armored data") {
+            Ok(data) => data,
+            Err(err) => return Ok(Status::Corrupted(err)),
+        }
+    } else {
+        data
+    };
+    if envelope::is_envelope(&data) {
+        return verify_envelope(&data, passphrase, limits);
+    }
+    verify(&data, passphrase, agent, limits)
+}
+
+/// Checks all files in `paths` with `passphrase`.
+///
+/// The passphrase is only read if the agent on the socket at `agent` does not
+/// hold the derived key for any of the files.
+///
+/// # Errors
+///
+/// Returns [`Err`] if reading the passphrase fails.
+pub fn run(
+    paths: &[PathBuf],
+    passphrase: impl FnOnce() -> anyhow::Result<Vec<u8>>,
+    agent: Option<&Path>,
+    limits: &Limits,
+) -> anyhow::Result<Vec<Entry>> {
+    let mut passphrase = LazyPassword::new(passphrase);
+    paths
+        .iter()
+        .map(|path| {
+            Ok(Entry {
+                path: path.clone(),
+                status: check(path, &mut passphrase, agent, limits)?,
+            })
+        })
+        .collect()
+}
+
+/// Returns [`Err`] if any of `entries` failed the check.
+pub fn result(entries: &[Entry]) -> Result<(), Error> {
+    let failed = entries
+        .iter()
+        .filter(|e| !matches!(e.status, Status::Ok))
+        .count();
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(Error {
+            failed,
+            total: entries.len(),
+        })
+    }
+}
+
+/// Prints the result for each file.
+pub fn display(entries: &[Entry]) {
+    for entry in entries {
+        println!("{}: {}", entry.path.display(), entry.status);
+    }
+}
+
+/// The result of checking a file.
+#[cfg(feature = "json")]
+#[derive(Debug, serde::Serialize)]
+pub struct Report {
+    file: String,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[cfg(feature = "json")]
+impl Report {
+    /// Creates a new `Report`.
+    pub fn new(entry: &Entry) -> Self {
+        Self {
+            file: entry.path.display().to_string(),
+            status: entry.status.name(),
+            error: entry.status.error().map(|err| format!("{err:#}")),
+        }
+    }
+}
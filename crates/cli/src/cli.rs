@@ -6,18 +6,25 @@ use std::{
     fmt,
     io::{self, Write},
     ops::Deref,
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
     time::Duration,
 };
 
 use anyhow::anyhow;
+use byte_unit::UnitType;
 use clap::{
-    ArgGroup, Args, CommandFactory, Parser, Subcommand, ValueEnum, ValueHint, value_parser,
+    ArgGroup, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum, ValueHint,
+    builder::FalseyValueParser, value_parser,
 };
 use clap_complete::Generator;
 use fraction::{Fraction, Zero};
 use jiff::Span;
+use scryptenc::compress;
+
+use crate::passphrase;
+#[cfg(feature = "x25519")]
+use crate::recipient::Recipient;
 
 #[derive(Debug, Parser)]
 #[command(
@@ -27,10 +34,30 @@ use jiff::Span;
     max_term_width(100),
     propagate_version(true),
     infer_subcommands(true),
-    arg_required_else_help(false),
-    args_conflicts_with_subcommands(true)
+    arg_required_else_help(false)
 )]
 pub struct Opt {
+    /// Read the configuration from the specified file.
+    ///
+    /// If this is not specified, the file specified by the RSCRYPT_CONFIG
+    /// environment variable or $XDG_CONFIG_HOME/rscrypt/config.toml is used.
+    #[allow(clippy::doc_markdown)]
+    #[arg(
+        long,
+        global(true),
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath)
+    )]
+    pub config: Option<PathBuf>,
+
+    /// Output the result and errors as JSON.
+    ///
+    /// This applies to the enc, dec, info, audit and bench commands. The results
+    /// of the enc and dec commands and errors are output to standard error.
+    #[cfg(feature = "json")]
+    #[arg(short, long, global(true))]
+    pub json: bool,
+
     #[command(subcommand)]
     pub command: Command,
 }
@@ -38,68 +65,239 @@ pub struct Opt {
 #[derive(Debug, Subcommand)]
 pub enum Command {
     /// Encrypt files.
-    #[command(name("enc"))]
+    #[command(name("enc"), alias("e"))]
     Encrypt(Encrypt),
 
     /// Decrypt files.
     #[command(name("dec"))]
     Decrypt(Decrypt),
 
-    /// Provides information about the encryption parameters.
+    /// Provides information about the encrypted data.
     #[command(name("info"))]
     Information(Information),
 
+    /// Check that the encrypted data complies with the parameter policy.
+    ///
+    /// This reads only the headers, so the passphrase is not required. Files
+    /// which do not comply with the policy, and files which are unreadable or
+    /// are not scrypt encrypted data are reported to standard output.
+    Audit(Audit),
+
+    /// Upgrade the encryption parameters of the encrypted file.
+    ///
+    /// This decrypts the file and encrypts it again under the same passphrase
+    /// with the encryption parameters selected for the resource limits and a
+    /// new salt. The file is replaced atomically. If the encryption parameters
+    /// of the file already meet the selected ones, the file is left unchanged.
+    Upgrade(Upgrade),
+
+    /// Check the integrity of the encrypted files.
+    ///
+    /// This verifies the MAC of each file without writing the plaintext
+    /// anywhere, and reports whether the file is intact, the passphrase is
+    /// wrong, the file is corrupted, or the file is not scrypt encrypted data.
+    Check(Check),
+
+    /// Edit the encrypted file.
+    ///
+    /// This decrypts the file into a temporary file which only the owner can
+    /// access, launches the editor, and encrypts the file again under the same
+    /// passphrase if it was changed. The temporary file is overwritten with
+    /// random data and removed after the editor exits.
+    Edit(Edit),
+
+    /// Manage the passphrases of the envelope.
+    ///
+    /// The envelope is the encrypted data which can be decrypted with any of
+    /// several passphrases, and is created by the enc command with --envelope.
+    /// Passphrases can be added to and removed from the envelope without
+    /// encrypting the data again. The file is replaced atomically.
+    Passphrase(Passphrase),
+
+    /// Run as a Git filter.
+    ///
+    /// This reads data from standard input and writes the result to standard
+    /// output. The "clean" mode encrypts the data so that the same plaintext
+    /// always results in the same encrypted data, the "smudge" mode decrypts
+    /// the data, and the "diff" mode decrypts the data for showing the
+    /// differences. Data which is not scrypt encrypted data is output as is by
+    /// the "smudge" and "diff" modes, and scrypt encrypted data is output as is
+    /// by the "clean" mode.
+    GitFilter(GitFilter),
+
+    /// Set up the Git filter in the current repository.
+    ///
+    /// This adds the attributes for the specified patterns to .gitattributes
+    /// in the top-level directory of the working tree, and adds the filter and
+    /// diff driver named "rscrypt" to the repository configuration.
+    GitInit(GitInit),
+
+    /// Run the agent which caches derived keys.
+    ///
+    /// The agent holds the derived keys computed by the dec command in locked
+    /// memory for the specified duration, and the dec command uses them
+    /// instead of asking for the passphrase and computing the derived key
+    /// again. The agent listens on a Unix domain socket which only the owner
+    /// can access, and runs until it is terminated.
+    #[cfg(unix)]
+    Agent(Agent),
+
+    /// Generate a random passphrase.
+    ///
+    /// The generated passphrase is output to standard output.
+    #[command(name("genpass"), alias("g"))]
+    GeneratePassphrase(GeneratePassphrase),
+
+    /// Generate a new identity for decrypting envelopes.
+    ///
+    /// The identity is an X25519 secret key. The envelope encrypted by the enc
+    /// command with --recipient for its public key can be decrypted by the dec
+    /// command with --identity. The identity is output to standard output or
+    /// the specified file, and the public key is printed to standard error.
+    #[cfg(feature = "x25519")]
+    Keygen(Keygen),
+
+    /// Measure the performance of scrypt.
+    ///
+    /// This reports the measured performance, the encryption parameters which
+    /// would be selected for the resource limits, and the time taken to compute
+    /// the derived key for each combination of the specified parameters.
+    Bench(Bench),
+
+    /// Manage the configuration.
+    Config(Config),
+
     /// Generate shell completion.
     ///
     /// The completion is output to standard output.
+    #[command(alias("c"))]
     Completion(Completion),
 }
 
+/// The options to read the passphrase.
 #[derive(Args, Debug)]
-#[allow(clippy::struct_excessive_bools)]
-#[command(
-    group(ArgGroup::new("passphrase")),
-    group(
-        ArgGroup::new("resources")
-            .multiple(true)
-            .conflicts_with("force")
-            .conflicts_with("parameters")
-    ),
-    group(ArgGroup::new("parameters").multiple(true))
-)]
-pub struct Encrypt {
-    /// Force the encryption to proceed even if it requires an excessive amount
-    /// of resources.
-    #[arg(short, long, requires("parameters"))]
-    pub force: bool,
+#[command(group(ArgGroup::new("passphrase")))]
+pub struct PassphraseArgs {
+    /// Read the passphrase from /dev/tty.
+    ///
+    /// This is the default behavior.
+    #[arg(long, group("passphrase"))]
+    pub passphrase_from_tty: bool,
+
+    /// Read the passphrase from standard input.
+    #[arg(long, group("passphrase"))]
+    pub passphrase_from_stdin: bool,
+
+    /// Read the passphrase from the environment variable.
+    ///
+    /// Note that storing a passphrase in an environment variable can be a
+    /// security risk.
+    #[arg(long, value_name("VAR"), group("passphrase"))]
+    pub passphrase_from_env: Option<String>,
 
+    /// Read the passphrase from the file.
+    ///
+    /// Note that storing a passphrase in a file can be a security risk.
+    #[arg(
+        long,
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath),
+        group("passphrase")
+    )]
+    pub passphrase_from_file: Option<PathBuf>,
+
+    /// Read the passphrase from the file descriptor.
+    ///
+    /// This is only supported on Unix.
+    #[arg(long, value_name("FD"), group("passphrase"))]
+    pub passphrase_from_fd: Option<u32>,
+
+    /// Read the passphrase from the keyring.
+    ///
+    /// The passphrase is looked up in the Secret Service by the "service" and
+    /// "username" attributes.
+    #[arg(long, value_name("SERVICE/ACCOUNT"), group("passphrase"))]
+    pub passphrase_from_keyring: Option<passphrase::KeyringEntry>,
+
+    /// Read the passphrase from the first line of the output of the command.
+    ///
+    /// The command is run by the shell.
+    #[arg(long, value_name("CMD"), group("passphrase"))]
+    pub passphrase_from_command: Option<String>,
+
+    /// Use the contents of the file as the passphrase.
+    ///
+    /// If any option to read the passphrase is also specified, the HMAC-SHA-256
+    /// of the passphrase keyed with the SHA-256 digest of the file is used
+    /// instead.
+    #[arg(long, value_name("FILE"), value_hint(ValueHint::FilePath))]
+    pub keyfile: Option<PathBuf>,
+
+    /// Use the SHA-256 digest of the keyfile instead of its contents.
+    ///
+    /// Note that scrypt hashes a passphrase longer than 64 bytes with SHA-256,
+    /// so this only makes a difference for a keyfile of 64 bytes or less.
+    #[arg(long, requires("keyfile"))]
+    pub keyfile_digest: bool,
+}
+
+/// The resource limits to compute the derived key.
+#[derive(Args, Debug)]
+#[allow(clippy::struct_field_names)]
+#[group(id = "resources", multiple = true)]
+pub struct LimitArgs {
     /// Use at most the specified bytes of RAM to compute the derived key.
-    #[arg(short('M'), long, value_name("BYTE"), group("resources"))]
+    ///
+    /// This can also be specified by the RSCRYPT_MAX_MEMORY environment
+    /// variable.
+    #[allow(clippy::doc_markdown)]
+    #[arg(short('M'), long, value_name("BYTE"))]
     pub max_memory: Option<Byte>,
 
     /// Use at most the specified fraction of the available RAM to compute the
     /// derived key.
-    #[arg(
-        short,
-        long,
-        default_value("0.125"),
-        value_name("RATE"),
-        group("resources")
-    )]
-    pub max_memory_fraction: Rate,
+    ///
+    /// Default is 0.125 for encryption and 0.5 for decryption.
+    ///
+    /// This can also be specified by the RSCRYPT_MAX_MEMORY_FRACTION environment
+    /// variable.
+    #[allow(clippy::doc_markdown)]
+    #[arg(short, long, value_name("RATE"))]
+    pub max_memory_fraction: Option<Rate>,
 
     /// Use at most the specified duration of CPU time to compute the derived
     /// key.
-    #[arg(
-        short('t'),
-        long,
-        default_value("5s"),
-        value_name("DURATION"),
-        group("resources")
-    )]
-    pub max_time: Time,
+    ///
+    /// Default is 5 seconds for encryption and 300 seconds for decryption.
+    ///
+    /// This can also be specified by the RSCRYPT_MAX_TIME environment
+    /// variable.
+    #[allow(clippy::doc_markdown)]
+    #[arg(short('t'), long, value_name("DURATION"))]
+    pub max_time: Option<Time>,
+}
+
+#[derive(Args, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+#[command(group(
+    ArgGroup::new("parameters")
+        .multiple(true)
+        .conflicts_with("resources")
+))]
+pub struct Encrypt {
+    /// Force the encryption to proceed even if it requires an excessive amount
+    /// of resources.
+    #[arg(short, long, conflicts_with("resources"))]
+    pub force: bool,
+
+    #[command(flatten)]
+    pub limits: LimitArgs,
 
     /// Set the work parameter N to 2^<VALUE>.
+    ///
+    /// This can also be specified by the RSCRYPT_LOG_N environment
+    /// variable.
+    #[allow(clippy::doc_markdown)]
     #[arg(
         value_parser(value_parser!(u8).range(10..=40)),
         long,
@@ -111,6 +309,10 @@ pub struct Encrypt {
     pub log_n: Option<u8>,
 
     /// Set the work parameter r.
+    ///
+    /// This can also be specified by the RSCRYPT_R environment
+    /// variable.
+    #[allow(clippy::doc_markdown)]
     #[arg(
         value_parser(value_parser!(u32).range(1..=32)),
         short,
@@ -122,6 +324,10 @@ pub struct Encrypt {
     pub r: Option<u32>,
 
     /// Set the work parameter p.
+    ///
+    /// This can also be specified by the RSCRYPT_P environment
+    /// variable.
+    #[allow(clippy::doc_markdown)]
     #[arg(
         value_parser(value_parser!(u32).range(1..=32)),
         short,
@@ -132,40 +338,174 @@ pub struct Encrypt {
     )]
     pub p: Option<u32>,
 
-    /// Read the passphrase from /dev/tty.
+    /// Compress the input data before encryption.
     ///
-    /// This is the default behavior.
-    #[arg(long, group("passphrase"))]
-    pub passphrase_from_tty: bool,
+    /// The compressed data is decompressed automatically when decrypting. Note
+    /// that compression can leak information about the plaintext through the
+    /// length of the encrypted data.
+    #[arg(long, value_enum, value_name("FORMAT"), ignore_case(true))]
+    pub compress: Option<Compression>,
 
-    /// Read the passphrase from standard input.
-    #[arg(long, group("passphrase"))]
-    pub passphrase_from_stdin: bool,
+    /// Output the encrypted data as ASCII-armored text.
+    ///
+    /// The encrypted data is encoded in Base64 and surrounded by the "-----BEGIN
+    /// SCRYPT ENCRYPTED DATA-----" and "-----END SCRYPT ENCRYPTED DATA-----"
+    /// lines.
+    #[arg(short, long)]
+    pub armor: bool,
+
+    /// Do not output the encrypted data as ASCII-armored text.
+    ///
+    /// This overrides the "armor" setting in the config file.
+    #[arg(long, conflicts_with("armor"))]
+    pub no_armor: bool,
+
+    /// Encrypt the data into an envelope.
+    ///
+    /// The envelope can be decrypted with any of several passphrases, which
+    /// can be added and removed later by the passphrase command. It is
+    /// created with the single passphrase read as usual.
+    #[arg(long)]
+    pub envelope: bool,
+
+    /// Encrypt the data for the specified recipient.
+    ///
+    /// <RECIPIENT> is a public key printed by the keygen command. This can be
+    /// specified multiple times, and implies --envelope. The passphrase is also
+    /// added to the envelope only if any option to read the passphrase or
+    /// --keyfile is specified.
+    #[cfg(feature = "x25519")]
+    #[arg(long, value_name("RECIPIENT"))]
+    pub recipient: Vec<Recipient>,
+
+    /// Remove the input file after encryption.
+    ///
+    /// The input file is removed only after the output file has been written,
+    /// flushed to the storage device and verified by decrypting it.
+    /// The output file cannot be the same file as the input file.
+    #[arg(long, requires("output"))]
+    pub remove_input: bool,
+
+    /// Overwrite the input file with random data and remove it after
+    /// encryption.
+    ///
+    /// This is done under the same conditions as --remove-input. Note that
+    /// overwriting a file does not reliably erase the data on SSDs,
+    /// copy-on-write filesystems, journaling filesystems and backups.
+    #[arg(long, requires("output"), conflicts_with("remove_input"))]
+    pub shred_input: bool,
+
+    #[command(flatten)]
+    pub passphrase: PassphraseArgs,
 
     /// Read the passphrase from /dev/tty only once.
     #[arg(long, group("passphrase"))]
     pub passphrase_from_tty_once: bool,
 
-    /// Read the passphrase from the environment variable.
+    /// Refuse the passphrase if its estimated entropy is less than the
+    /// specified bits.
+    #[arg(long, value_name("BITS"), conflicts_with("allow_weak_passphrase"))]
+    pub min_entropy: Option<u32>,
+
+    /// Allow a weak or empty passphrase.
     ///
-    /// Note that storing a passphrase in an environment variable can be a
-    /// security risk.
-    #[arg(long, value_name("VAR"), group("passphrase"))]
-    pub passphrase_from_env: Option<String>,
+    /// This also suppresses the warning about a weak passphrase.
+    #[arg(long)]
+    pub allow_weak_passphrase: bool,
 
-    /// Read the passphrase from the file.
+    /// Generate a random passphrase and use it.
     ///
-    /// Note that storing a passphrase in a file can be a security risk.
+    /// The generated passphrase is printed to standard error unless
+    /// --passphrase-out is specified. By default, the passphrase consists of 7
+    /// words.
+    #[arg(long, group("passphrase"))]
+    pub generate_passphrase: bool,
+
+    /// Generate a passphrase which consists of the specified number of words.
+    #[arg(
+        value_parser(value_parser!(u8).range(1..)),
+        long,
+        requires("generate_passphrase"),
+        conflicts_with("chars"),
+        value_name("N")
+    )]
+    pub words: Option<u8>,
+
+    /// Generate a passphrase which consists of the specified number of
+    /// alphanumeric characters.
+    #[arg(
+        value_parser(value_parser!(u8).range(1..)),
+        long,
+        requires("generate_passphrase"),
+        value_name("N")
+    )]
+    pub chars: Option<u8>,
+
+    /// Write the generated passphrase to the file instead of standard error.
+    ///
+    /// On Unix, the permissions of the file are set to 0600.
     #[arg(
         long,
+        requires("generate_passphrase"),
         value_name("FILE"),
-        value_hint(ValueHint::FilePath),
-        group("passphrase")
+        value_hint(ValueHint::FilePath)
     )]
-    pub passphrase_from_file: Option<PathBuf>,
+    pub passphrase_out: Option<PathBuf>,
+
+    /// Read the passphrase from the specified source.
+    ///
+    /// <SOURCE> is one of "tty", "tty-once", "stdin", "env:<VAR>", "file:<PATH>",
+    /// "fd:<FD>", "keyring:<SERVICE>/<ACCOUNT>" and "command:<CMD>". This is
+    /// ignored if any other option to read the passphrase is specified.
+    #[arg(long, env("RSCRYPT_PASSPHRASE_SOURCE"), value_name("SOURCE"))]
+    pub passphrase_source: Option<passphrase::Source>,
+
+    /// Measure the performance of scrypt again even if the cached result is
+    /// valid.
+    #[arg(long, conflicts_with("no_calibration_cache"))]
+    pub recalibrate: bool,
+
+    /// Neither read nor write the cached result of measuring the performance
+    /// of scrypt.
+    #[arg(long)]
+    pub no_calibration_cache: bool,
+
+    /// Whether to overwrite the output file if it already exists.
+    ///
+    /// Default is "always".
+    #[arg(long, value_enum, value_name("POLICY"), ignore_case(true))]
+    pub overwrite: Option<Overwrite>,
+
+    /// Preserve the mode and the modification time of the input file.
+    #[arg(long, requires("output"))]
+    pub preserve: bool,
+
+    /// Write the encrypted data to standard output even if it is a terminal.
+    ///
+    /// By default, the binary encrypted data is not written to a terminal.
+    #[arg(long)]
+    pub force_tty: bool,
+
+    /// When to show the progress on standard error.
+    ///
+    /// If "auto" is specified, the progress is shown only if standard error is
+    /// a terminal.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        value_name("WHEN"),
+        ignore_case(true)
+    )]
+    pub progress: ProgressMode,
 
     /// Print encryption parameters and resource limits.
-    #[arg(short, long)]
+    #[arg(
+        short,
+        long,
+        env("RSCRYPT_VERBOSE"),
+        value_parser(FalseyValueParser::new())
+    )]
     pub verbose: bool,
 
     /// Input file.
@@ -176,80 +516,129 @@ pub struct Encrypt {
 
     /// Output file.
     ///
-    /// If [OUTFILE] is not specified, the result will be write to standard
-    /// output.
+    /// If [OUTFILE] is not specified or "-" is specified, the result will be
+    /// write to standard output.
     #[arg(value_name("OUTFILE"), value_hint(ValueHint::FilePath))]
     pub output: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
 #[allow(clippy::struct_excessive_bools)]
-#[command(
-    group(ArgGroup::new("passphrase")),
-    group(ArgGroup::new("resources").multiple(true).conflicts_with("force"))
-)]
 pub struct Decrypt {
     /// Force the decryption to proceed even if it requires an excessive amount
     /// of resources.
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with("resources"))]
     pub force: bool,
 
-    /// Use at most the specified bytes of RAM to compute the derived key.
-    #[arg(short('M'), long, value_name("BYTE"), group("resources"))]
-    pub max_memory: Option<Byte>,
+    #[command(flatten)]
+    pub limits: LimitArgs,
 
-    /// Use at most the specified fraction of the available RAM to compute the
-    /// derived key.
+    #[command(flatten)]
+    pub passphrase: PassphraseArgs,
+
+    /// Read the passphrase from the specified source.
+    ///
+    /// <SOURCE> is one of "tty", "stdin", "env:<VAR>", "file:<PATH>",
+    /// "fd:<FD>", "keyring:<SERVICE>/<ACCOUNT>" and "command:<CMD>". This is
+    /// ignored if any other option to read the passphrase is specified.
+    #[arg(long, env("RSCRYPT_PASSPHRASE_SOURCE"), value_name("SOURCE"))]
+    pub passphrase_source: Option<passphrase::Source>,
+
+    /// Decrypt the envelope with the identity in the file.
+    ///
+    /// The file is generated by the keygen command. This can be specified
+    /// multiple times. The passphrase is not read if this is specified.
+    #[cfg(feature = "x25519")]
     #[arg(
-        short,
         long,
-        default_value("0.5"),
-        value_name("RATE"),
-        group("resources")
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath),
+        conflicts_with_all(["passphrase", "keyfile"])
     )]
-    pub max_memory_fraction: Rate,
+    pub identity: Vec<PathBuf>,
 
-    /// Use at most the specified duration of CPU time to compute the derived
-    /// key.
+    /// Ask for the passphrase up to the specified number of times if it is
+    /// incorrect.
+    ///
+    /// This only has an effect if the passphrase is read from /dev/tty.
     #[arg(
-        short('t'),
+        value_parser(value_parser!(u32).range(1..)),
         long,
-        default_value("300s"),
-        value_name("DURATION"),
-        group("resources")
+        default_value("1"),
+        value_name("N")
     )]
-    pub max_time: Time,
+    pub tries: u32,
 
-    /// Read the passphrase from /dev/tty.
+    /// Neither use nor update the derived keys cached by the agent.
     ///
-    /// This is the default behavior.
-    #[arg(long, group("passphrase"))]
-    pub passphrase_from_tty: bool,
+    /// By default, the derived key cached by the agent is used if it is
+    /// available, and the passphrase is not read. This is only supported on
+    /// Unix.
+    #[arg(long, env("RSCRYPT_NO_AGENT"), value_parser(FalseyValueParser::new()))]
+    pub no_agent: bool,
+
+    /// Do not decompress the decrypted data even if it was compressed before
+    /// encryption.
+    #[arg(long)]
+    pub no_decompress: bool,
+
+    /// Refuse to decompress the decrypted data if it expands to more than the
+    /// specified bytes.
+    ///
+    /// Default is the amount of the available RAM.
+    #[arg(long, value_name("BYTE"), conflicts_with("no_decompress"))]
+    pub max_decompressed_size: Option<Byte>,
 
-    /// Read the passphrase from standard input.
-    #[arg(long, group("passphrase"))]
-    pub passphrase_from_stdin: bool,
+    /// Measure the performance of scrypt again even if the cached result is
+    /// valid.
+    #[arg(long, conflicts_with("no_calibration_cache"))]
+    pub recalibrate: bool,
 
-    /// Read the passphrase from the environment variable.
+    /// Neither read nor write the cached result of measuring the performance
+    /// of scrypt.
+    #[arg(long)]
+    pub no_calibration_cache: bool,
+
+    /// Whether to overwrite the output file if it already exists.
     ///
-    /// Note that storing a passphrase in an environment variable can be a
-    /// security risk.
-    #[arg(long, value_name("VAR"), group("passphrase"))]
-    pub passphrase_from_env: Option<String>,
+    /// Default is "always".
+    #[arg(long, value_enum, value_name("POLICY"), ignore_case(true))]
+    pub overwrite: Option<Overwrite>,
 
-    /// Read the passphrase from the file.
+    /// Preserve the mode and the modification time of the input file.
     ///
-    /// Note that storing a passphrase in a file can be a security risk.
+    /// By default, the output file is made accessible only by the owner (mode
+    /// 0600) on Unix.
+    #[arg(long, requires("output"))]
+    pub preserve: bool,
+
+    /// Write the decrypted data to standard output even if it is a terminal.
+    ///
+    /// By default, the decrypted data which looks like binary data is not
+    /// written to a terminal.
+    #[arg(long)]
+    pub force_tty: bool,
+
+    /// When to show the progress on standard error.
+    ///
+    /// If "auto" is specified, the progress is shown only if standard error is
+    /// a terminal.
     #[arg(
         long,
-        value_name("FILE"),
-        value_hint(ValueHint::FilePath),
-        group("passphrase")
+        value_enum,
+        default_value_t,
+        value_name("WHEN"),
+        ignore_case(true)
     )]
-    pub passphrase_from_file: Option<PathBuf>,
+    pub progress: ProgressMode,
 
     /// Print encryption parameters and resource limits.
-    #[arg(short, long)]
+    #[arg(
+        short,
+        long,
+        env("RSCRYPT_VERBOSE"),
+        value_parser(FalseyValueParser::new())
+    )]
     pub verbose: bool,
 
     /// Input file.
@@ -260,26 +649,508 @@ pub struct Decrypt {
 
     /// Output file.
     ///
-    /// If [OUTFILE] is not specified, the result will be write to standard
-    /// output.
+    /// If [OUTFILE] is not specified or "-" is specified, the result will be
+    /// write to standard output.
     #[arg(value_name("OUTFILE"), value_hint(ValueHint::FilePath))]
     pub output: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
 pub struct Information {
-    /// Output the encryption parameters as JSON.
-    #[cfg(feature = "json")]
+    /// Input files.
+    ///
+    /// If "-" is specified, data will be read from standard input. If more
+    /// than one file is specified, the information is printed as a table.
+    #[arg(required(true), value_name("FILE"), value_hint(ValueHint::FilePath))]
+    pub input: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct Audit {
+    /// Scan directories recursively.
+    ///
+    /// Symbolic links to directories are not followed.
     #[arg(short, long)]
-    pub json: bool,
+    pub recursive: bool,
 
-    /// Input file.
+    /// Require the work parameter N to be at least 2^<VALUE>.
+    #[arg(
+        value_parser(value_parser!(u8).range(1..=63)),
+        long,
+        value_name("VALUE")
+    )]
+    pub min_log_n: Option<u8>,
+
+    /// Require at least the specified bytes of RAM to compute the derived key.
+    #[arg(long, value_name("BYTE"))]
+    pub min_memory: Option<Byte>,
+
+    /// Files or directories to check.
+    ///
+    /// If "-" is specified, data will be read from standard input.
+    #[arg(required(true), value_name("PATH"), value_hint(ValueHint::AnyPath))]
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct Upgrade {
+    /// Force the decryption to proceed even if it requires an excessive amount
+    /// of resources.
+    #[arg(short, long)]
+    pub force: bool,
+
+    #[command(flatten)]
+    pub limits: LimitArgs,
+
+    #[command(flatten)]
+    pub passphrase: PassphraseArgs,
+
+    /// Print the old and the new encryption parameters.
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// File to upgrade.
+    #[arg(value_name("FILE"), value_hint(ValueHint::FilePath))]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct Check {
+    /// Force the check to proceed even if it requires an excessive amount of
+    /// resources.
+    #[arg(short, long)]
+    pub force: bool,
+
+    #[command(flatten)]
+    pub limits: LimitArgs,
+
+    #[command(flatten)]
+    pub passphrase: PassphraseArgs,
+
+    /// Neither use nor update the derived keys cached by the agent.
+    ///
+    /// This is only supported on Unix.
+    #[arg(long, env("RSCRYPT_NO_AGENT"), value_parser(FalseyValueParser::new()))]
+    pub no_agent: bool,
+
+    /// Files to check.
+    ///
+    /// All files are checked with the same passphrase. If "-" is specified,
+    /// data will be read from standard input.
+    #[arg(required(true), value_name("FILE"), value_hint(ValueHint::FilePath))]
+    pub input: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct Edit {
+    /// Force the decryption to proceed even if it requires an excessive amount
+    /// of resources.
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// Encrypt the file with the encryption parameters selected for the
+    /// resource limits if they are stronger than the current ones.
+    ///
+    /// By default, the current encryption parameters are used. The resource
+    /// limits are ignored unless this is specified.
+    #[arg(short, long)]
+    pub upgrade: bool,
+
+    #[command(flatten)]
+    pub limits: LimitArgs,
+
+    #[command(flatten)]
+    pub passphrase: PassphraseArgs,
+
+    /// Edit the decrypted data as it is even if it was compressed before
+    /// encryption.
+    #[arg(long)]
+    pub no_decompress: bool,
+
+    /// Refuse to decompress the decrypted data if it expands to more than the
+    /// specified bytes.
+    ///
+    /// Default is the amount of the available RAM.
+    #[arg(long, value_name("BYTE"), conflicts_with("no_decompress"))]
+    pub max_decompressed_size: Option<Byte>,
+
+    /// File to edit.
+    #[arg(value_name("FILE"), value_hint(ValueHint::FilePath))]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct Passphrase {
+    #[command(subcommand)]
+    pub command: PassphraseCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PassphraseCommand {
+    /// Add a passphrase to the envelope.
+    ///
+    /// This asks for one of the existing passphrases and then for the new
+    /// passphrase. The new passphrase is protected with the encryption
+    /// parameters selected for the resource limits.
+    Add(AddPassphrase),
+
+    /// Remove a passphrase from the envelope.
+    ///
+    /// This removes the slot which the passphrase matches. The last passphrase
+    /// of the envelope cannot be removed. Note that anyone who already knows
+    /// the removed passphrase may have kept the key of the data, so encrypt the
+    /// data again to revoke their access completely.
+    Remove(RemovePassphrase),
+
+    /// List the slots of the envelope.
+    ///
+    /// This prints the encryption parameters of each slot, and does not
+    /// require any passphrase.
+    List(ListPassphrases),
+}
+
+#[derive(Args, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+#[command(group(ArgGroup::new("new_passphrase")))]
+pub struct AddPassphrase {
+    #[command(flatten)]
+    pub limits: LimitArgs,
+
+    #[command(flatten)]
+    pub passphrase: PassphraseArgs,
+
+    /// Read the new passphrase from /dev/tty.
+    ///
+    /// This is the default behavior.
+    #[arg(long, group("new_passphrase"))]
+    pub new_passphrase_from_tty: bool,
+
+    /// Read the new passphrase from standard input.
+    #[arg(long, group("new_passphrase"))]
+    pub new_passphrase_from_stdin: bool,
+
+    /// Read the new passphrase from the environment variable.
+    ///
+    /// Note that storing a passphrase in an environment variable can be a
+    /// security risk.
+    #[arg(long, value_name("VAR"), group("new_passphrase"))]
+    pub new_passphrase_from_env: Option<String>,
+
+    /// Read the new passphrase from the file.
+    ///
+    /// Note that storing a passphrase in a file can be a security risk.
+    #[arg(
+        long,
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath),
+        group("new_passphrase")
+    )]
+    pub new_passphrase_from_file: Option<PathBuf>,
+
+    /// Generate a random passphrase and add it.
+    ///
+    /// The generated passphrase is printed to standard error. This is useful
+    /// for creating a recovery code.
+    #[arg(long, group("new_passphrase"))]
+    pub generate_passphrase: bool,
+
+    /// Refuse the new passphrase if its estimated entropy is less than the
+    /// specified bits.
+    #[arg(long, value_name("BITS"), conflicts_with("allow_weak_passphrase"))]
+    pub min_entropy: Option<u32>,
+
+    /// Allow a weak or empty new passphrase.
+    ///
+    /// This also suppresses the warning about a weak passphrase.
+    #[arg(long)]
+    pub allow_weak_passphrase: bool,
+
+    /// Envelope to add the passphrase to.
+    #[arg(value_name("FILE"), value_hint(ValueHint::FilePath))]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct RemovePassphrase {
+    #[command(flatten)]
+    pub passphrase: PassphraseArgs,
+
+    /// Remove the slot at the specified index instead of the slot which the
+    /// passphrase matches.
+    ///
+    /// This does not ask for any passphrase, so this can remove a forgotten
+    /// passphrase. The index of each slot is printed by "rscrypt passphrase
+    /// list".
+    #[arg(
+        long,
+        value_name("INDEX"),
+        conflicts_with("passphrase"),
+        conflicts_with("keyfile")
+    )]
+    pub slot: Option<usize>,
+
+    /// Envelope to remove the passphrase from.
+    #[arg(value_name("FILE"), value_hint(ValueHint::FilePath))]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct ListPassphrases {
+    /// Envelope to list the slots of.
     ///
     /// If "-" is specified, data will be read from standard input.
     #[arg(value_name("FILE"), value_hint(ValueHint::FilePath))]
     pub input: PathBuf,
 }
 
+#[derive(Args, Debug)]
+pub struct GitFilter {
+    /// Set the work parameter N to 2^<VALUE> for the "clean" mode.
+    #[arg(
+        value_parser(value_parser!(u8).range(10..=40)),
+        long,
+        default_value("17"),
+        value_name("VALUE")
+    )]
+    pub log_n: u8,
+
+    /// Set the work parameter r for the "clean" mode.
+    #[arg(
+        value_parser(value_parser!(u32).range(1..=32)),
+        short,
+        default_value("8"),
+        value_name("VALUE")
+    )]
+    pub r: u32,
+
+    /// Set the work parameter p for the "clean" mode.
+    #[arg(
+        value_parser(value_parser!(u32).range(1..=32)),
+        short,
+        default_value("1"),
+        value_name("VALUE")
+    )]
+    pub p: u32,
+
+    #[command(flatten)]
+    pub passphrase: PassphraseArgs,
+
+    /// Neither use nor update the derived keys cached by the agent.
+    ///
+    /// This is only supported on Unix.
+    #[arg(long, env("RSCRYPT_NO_AGENT"), value_parser(FalseyValueParser::new()))]
+    pub no_agent: bool,
+
+    /// The mode of the filter.
+    #[arg(value_enum, value_name("MODE"))]
+    pub mode: GitFilterMode,
+
+    /// The path of the file.
+    ///
+    /// For the "clean" mode, this is the path of the file in the repository,
+    /// which is used to derive the salt. For the "diff" mode, data is read
+    /// from this file instead of standard input. This is ignored for the
+    /// "smudge" mode.
+    #[arg(value_name("PATH"), value_hint(ValueHint::FilePath))]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("passphrase")))]
+pub struct GitInit {
+    /// Set the work parameter N to 2^<VALUE> for encrypting files.
+    #[arg(
+        value_parser(value_parser!(u8).range(10..=40)),
+        long,
+        default_value("17"),
+        value_name("VALUE")
+    )]
+    pub log_n: u8,
+
+    /// Set the work parameter r for encrypting files.
+    #[arg(
+        value_parser(value_parser!(u32).range(1..=32)),
+        short,
+        default_value("8"),
+        value_name("VALUE")
+    )]
+    pub r: u32,
+
+    /// Set the work parameter p for encrypting files.
+    #[arg(
+        value_parser(value_parser!(u32).range(1..=32)),
+        short,
+        default_value("1"),
+        value_name("VALUE")
+    )]
+    pub p: u32,
+
+    /// Make the filter read the passphrase from the environment variable.
+    #[arg(long, value_name("VAR"), group("passphrase"))]
+    pub passphrase_from_env: Option<String>,
+
+    /// Make the filter read the passphrase from the file.
+    ///
+    /// The path is made absolute.
+    #[arg(
+        long,
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath),
+        group("passphrase")
+    )]
+    pub passphrase_from_file: Option<PathBuf>,
+
+    /// Make the filter read the passphrase from the keyring.
+    #[arg(long, value_name("SERVICE/ACCOUNT"), group("passphrase"))]
+    pub passphrase_from_keyring: Option<passphrase::KeyringEntry>,
+
+    /// Make the filter read the passphrase from the output of the command.
+    #[arg(long, value_name("CMD"), group("passphrase"))]
+    pub passphrase_from_command: Option<String>,
+
+    /// Make the filter use the contents of the file as the passphrase.
+    ///
+    /// The path is made absolute.
+    #[arg(long, value_name("FILE"), value_hint(ValueHint::FilePath))]
+    pub keyfile: Option<PathBuf>,
+
+    /// Make the filter use the SHA-256 digest of the keyfile instead of its
+    /// contents.
+    #[arg(long, requires("keyfile"))]
+    pub keyfile_digest: bool,
+
+    /// Patterns of the files to encrypt.
+    #[arg(required(true), value_name("PATTERN"))]
+    pub patterns: Vec<String>,
+}
+
+#[cfg(unix)]
+#[derive(Args, Debug)]
+pub struct Agent {
+    /// Listen on the specified socket.
+    ///
+    /// If this is not specified, the socket specified by the
+    /// RSCRYPT_AGENT_SOCK environment variable or
+    /// $XDG_RUNTIME_DIR/rscrypt/agent.sock is used.
+    #[allow(clippy::doc_markdown)]
+    #[arg(
+        long,
+        env("RSCRYPT_AGENT_SOCK"),
+        value_name("PATH"),
+        value_hint(ValueHint::FilePath)
+    )]
+    pub socket: Option<PathBuf>,
+
+    /// Forget the derived keys after the specified duration.
+    #[arg(long, default_value("15m"), value_name("DURATION"))]
+    pub ttl: Time,
+
+    /// Make the running agent forget all derived keys instead of running the
+    /// agent.
+    #[arg(long, conflicts_with("ttl"))]
+    pub clear: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct GeneratePassphrase {
+    /// Generate a passphrase which consists of the specified number of words.
+    ///
+    /// This is the default behavior, and the default number of words is 7.
+    #[arg(
+        value_parser(value_parser!(u8).range(1..)),
+        long,
+        conflicts_with("chars"),
+        value_name("N")
+    )]
+    pub words: Option<u8>,
+
+    /// Generate a passphrase which consists of the specified number of
+    /// alphanumeric characters.
+    #[arg(value_parser(value_parser!(u8).range(1..)), long, value_name("N"))]
+    pub chars: Option<u8>,
+}
+
+#[cfg(feature = "x25519")]
+#[derive(Args, Debug)]
+pub struct Keygen {
+    /// Write the identity to the file instead of standard output.
+    ///
+    /// The file must not exist. On Unix, the permissions of the file are set
+    /// to 0600.
+    #[arg(short, long, value_name("FILE"), value_hint(ValueHint::FilePath))]
+    pub output: Option<PathBuf>,
+
+    /// Print the public keys of the identities in the file instead of
+    /// generating a new identity.
+    #[arg(
+        short('y'),
+        long,
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath),
+        conflicts_with("output")
+    )]
+    pub to_public: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct Bench {
+    #[command(flatten)]
+    pub limits: LimitArgs,
+
+    /// Measure with the work parameter N set to 2^<VALUE>.
+    ///
+    /// Multiple values can be specified separated by commas.
+    #[arg(
+        value_parser(value_parser!(u8).range(10..=40)),
+        long,
+        value_delimiter(','),
+        default_value("10,12,14,16,18"),
+        value_name("VALUE")
+    )]
+    pub log_n: Vec<u8>,
+
+    /// Measure with the specified work parameter r.
+    ///
+    /// Multiple values can be specified separated by commas.
+    #[arg(
+        value_parser(value_parser!(u32).range(1..=32)),
+        short,
+        value_delimiter(','),
+        default_value("8"),
+        value_name("VALUE")
+    )]
+    pub r: Vec<u32>,
+
+    /// Measure with the specified work parameter p.
+    ///
+    /// Multiple values can be specified separated by commas.
+    #[arg(
+        value_parser(value_parser!(u32).range(1..=32)),
+        short,
+        value_delimiter(','),
+        default_value("1"),
+        value_name("VALUE")
+    )]
+    pub p: Vec<u32>,
+
+    /// Do not write the result of measuring the performance of scrypt to the
+    /// cache.
+    #[arg(long)]
+    pub no_calibration_cache: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct Config {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Print the effective settings and where each one came from.
+    Show,
+}
+
 #[derive(Args, Debug)]
 pub struct Completion {
     /// Shell to generate completion for.
@@ -287,6 +1158,115 @@ pub struct Completion {
     pub shell: Shell,
 }
 
+impl PassphraseArgs {
+    /// Returns the source of the passphrase specified on the command line.
+    pub fn source(&self) -> Option<passphrase::Source> {
+        match (
+            self.passphrase_from_tty,
+            self.passphrase_from_stdin,
+            &self.passphrase_from_env,
+            &self.passphrase_from_file,
+            self.passphrase_from_fd,
+            &self.passphrase_from_keyring,
+            &self.passphrase_from_command,
+        ) {
+            (true, ..) => Some(passphrase::Source::Tty),
+            (_, true, ..) => Some(passphrase::Source::Stdin),
+            (.., Some(env), _, _, _, _) => Some(passphrase::Source::Env(env.clone())),
+            (.., Some(file), _, _, _) => Some(passphrase::Source::File(file.clone())),
+            (.., Some(fd), _, _) => Some(passphrase::Source::Fd(fd)),
+            (.., Some(entry), _) => Some(passphrase::Source::Keyring(entry.clone())),
+            (.., Some(command)) => Some(passphrase::Source::Command(command.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl LimitArgs {
+    /// Returns `true` if any resource limit is specified.
+    pub const fn is_specified(&self) -> bool {
+        self.max_memory.is_some() || self.max_memory_fraction.is_some() || self.max_time.is_some()
+    }
+}
+
+impl Encrypt {
+    /// Returns the output file.
+    ///
+    /// This returns [`None`] if the result is written to standard output.
+    pub fn output_file(&self) -> Option<&Path> {
+        self.output.as_deref().filter(|p| *p != Path::new("-"))
+    }
+
+    /// Returns the options specified by the environment variables.
+    pub fn from_env() -> clap::error::Result<Self> {
+        let matches =
+            Self::augment_args(clap::Command::new("enc")).try_get_matches_from(["enc", "-"])?;
+        Self::from_arg_matches(&matches)
+    }
+
+    /// Returns `true` if any recipient is specified.
+    #[cfg(feature = "x25519")]
+    pub fn has_recipients(&self) -> bool {
+        !self.recipient.is_empty()
+    }
+
+    /// Returns `true` if any recipient is specified.
+    #[cfg(not(feature = "x25519"))]
+    #[allow(clippy::unused_self)]
+    pub const fn has_recipients(&self) -> bool {
+        false
+    }
+
+    /// Returns the source of the passphrase specified on the command line.
+    pub fn passphrase_source(&self) -> Option<passphrase::Source> {
+        if self.passphrase_from_tty_once {
+            return Some(passphrase::Source::TtyOnce);
+        }
+        self.passphrase
+            .source()
+            .or_else(|| self.passphrase_source.clone())
+    }
+}
+
+impl Decrypt {
+    /// Returns the output file.
+    ///
+    /// This returns [`None`] if the result is written to standard output.
+    pub fn output_file(&self) -> Option<&Path> {
+        self.output.as_deref().filter(|p| *p != Path::new("-"))
+    }
+
+    /// Returns the options specified by the environment variables.
+    pub fn from_env() -> clap::error::Result<Self> {
+        let matches =
+            Self::augment_args(clap::Command::new("dec")).try_get_matches_from(["dec", "-"])?;
+        Self::from_arg_matches(&matches)
+    }
+
+    /// Returns the source of the passphrase specified on the command line.
+    pub fn passphrase_source(&self) -> Option<passphrase::Source> {
+        self.passphrase
+            .source()
+            .or_else(|| self.passphrase_source.clone())
+    }
+}
+
+impl AddPassphrase {
+    /// Returns the source of the new passphrase.
+    pub fn new_passphrase_source(&self) -> passphrase::Source {
+        match (
+            self.new_passphrase_from_stdin,
+            &self.new_passphrase_from_env,
+            &self.new_passphrase_from_file,
+        ) {
+            (true, ..) => passphrase::Source::Stdin,
+            (_, Some(env), _) => passphrase::Source::Env(env.clone()),
+            (.., Some(file)) => passphrase::Source::File(file.clone()),
+            _ => passphrase::Source::Tty,
+        }
+    }
+}
+
 impl Opt {
     /// Generates shell completion and print it.
     pub fn print_completion(generator: impl Generator) {
@@ -347,6 +1327,80 @@ impl Generator for Shell {
     }
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+#[value(rename_all = "lower")]
+pub enum Compression {
+    /// gzip.
+    Gzip,
+
+    /// Zstandard.
+    Zstd,
+}
+
+impl From<Compression> for compress::Compression {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::Gzip => Self::Gzip,
+            Compression::Zstd => Self::Zstd,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+#[value(rename_all = "lower")]
+pub enum GitFilterMode {
+    /// Encrypt the data.
+    Clean,
+
+    /// Decrypt the data.
+    Smudge,
+
+    /// Decrypt the data for showing the differences.
+    Diff,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+#[value(rename_all = "lower")]
+pub enum Overwrite {
+    /// Overwrite the output file.
+    Always,
+
+    /// Refuse to overwrite the output file.
+    Never,
+}
+
+impl fmt::Display for Overwrite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Always => write!(f, "always"),
+            Self::Never => write!(f, "never"),
+        }
+    }
+}
+
+impl FromStr for Overwrite {
+    type Err = anyhow::Error;
+
+    fn from_str(policy: &str) -> anyhow::Result<Self> {
+        <Self as ValueEnum>::from_str(policy, true)
+            .map_err(|_| anyhow!("overwrite policy is not one of always or never"))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+#[value(rename_all = "lower")]
+pub enum ProgressMode {
+    /// Show the progress if standard error is a terminal.
+    #[default]
+    Auto,
+
+    /// Always show the progress.
+    Always,
+
+    /// Never show the progress.
+    Never,
+}
+
 /// Amount of RAM.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Byte(byte_unit::Byte);
@@ -359,6 +1413,12 @@ impl Deref for Byte {
     }
 }
 
+impl fmt::Display for Byte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.0.get_appropriate_unit(UnitType::Binary))
+    }
+}
+
 impl FromStr for Byte {
     type Err = anyhow::Error;
 
@@ -385,6 +1445,12 @@ impl Deref for Rate {
     }
 }
 
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 impl FromStr for Rate {
     type Err = anyhow::Error;
 
@@ -416,6 +1482,12 @@ impl Deref for Time {
     }
 }
 
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
 impl FromStr for Time {
     type Err = anyhow::Error;
 
@@ -0,0 +1,669 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    env, fmt, fs, io,
+    ops::Deref,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{Context, anyhow, bail};
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::{
+    cli::{Byte, Decrypt, Encrypt, LimitArgs, Overwrite, Rate, Time},
+    passphrase,
+};
+
+/// The environment variable to specify the config file.
+pub const ENV_VAR: &str = "RSCRYPT_CONFIG";
+
+/// Where a setting came from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Origin {
+    /// The built-in default value.
+    Default,
+
+    /// The config file.
+    ConfigFile(PathBuf),
+
+    /// The environment variable.
+    Environment,
+
+    /// The command-line option.
+    CommandLine,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::ConfigFile(path) => write!(f, "config file {}", path.display()),
+            Self::Environment => write!(f, "environment variable"),
+            Self::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+/// A setting with where it came from.
+#[derive(Clone, Debug)]
+pub struct Setting<T> {
+    value: T,
+    origin: Origin,
+}
+
+impl<T> Setting<T> {
+    /// Creates a new `Setting` with the built-in default value.
+    const fn new(value: T) -> Self {
+        Self {
+            value,
+            origin: Origin::Default,
+        }
+    }
+
+    /// Replaces the value if `value` is [`Some`].
+    pub fn set(&mut self, value: Option<T>, origin: &Origin) {
+        if let Some(value) = value {
+            self.value = value;
+            self.origin = origin.clone();
+        }
+    }
+
+    /// Returns where this setting came from.
+    pub const fn origin(&self) -> &Origin {
+        &self.origin
+    }
+}
+
+impl<T> Deref for Setting<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+/// The resource limits to compute the derived key.
+#[derive(Clone, Debug)]
+#[allow(clippy::struct_field_names)]
+pub struct Limits {
+    pub max_memory: Setting<Option<Byte>>,
+    pub max_memory_fraction: Setting<Rate>,
+    pub max_time: Setting<Time>,
+}
+
+impl Limits {
+    /// Creates a new `Limits` with the built-in default values.
+    fn new(max_memory_fraction: &str, max_time: &str) -> Self {
+        Self {
+            max_memory: Setting::new(None),
+            max_memory_fraction: Setting::new(
+                Rate::from_str(max_memory_fraction).expect("default value should be valid"),
+            ),
+            max_time: Setting::new(
+                Time::from_str(max_time).expect("default value should be valid"),
+            ),
+        }
+    }
+
+    /// Replaces the resource limits with the specified options.
+    pub fn apply(&mut self, arg: &LimitArgs, origin: &Origin) {
+        self.max_memory.set(arg.max_memory.map(Some), origin);
+        self.max_memory_fraction
+            .set(arg.max_memory_fraction, origin);
+        self.max_time.set(arg.max_time, origin);
+    }
+}
+
+/// The effective settings for the `enc` command.
+#[derive(Clone, Debug)]
+pub struct EncryptSettings {
+    pub limits: Limits,
+    pub params: Setting<Option<(u8, u32, u32)>>,
+    pub passphrase: Setting<passphrase::Source>,
+    pub overwrite: Setting<Overwrite>,
+    pub armor: Setting<bool>,
+}
+
+impl Default for EncryptSettings {
+    fn default() -> Self {
+        Self {
+            limits: Limits::new("0.125", "5s"),
+            params: Setting::new(None),
+            passphrase: Setting::new(passphrase::Source::Tty),
+            overwrite: Setting::new(Overwrite::Always),
+            armor: Setting::new(false),
+        }
+    }
+}
+
+impl EncryptSettings {
+    /// Replaces the settings with the specified options.
+    ///
+    /// If any resource limit is specified, the encryption parameters in the
+    /// config file are not used.
+    pub fn apply(&mut self, arg: &Encrypt, origin: &Origin) {
+        self.limits.apply(&arg.limits, origin);
+        if let (Some(log_n), Some(r), Some(p)) = (arg.log_n, arg.r, arg.p) {
+            self.params.set(Some(Some((log_n, r, p))), origin);
+        } else if arg.limits.is_specified() {
+            self.params.set(Some(None), origin);
+        }
+        self.passphrase.set(arg.passphrase_source(), origin);
+        self.overwrite.set(arg.overwrite, origin);
+        self.armor
+            .set((arg.armor || arg.no_armor).then_some(arg.armor), origin);
+    }
+}
+
+/// The effective settings for the `dec` command.
+#[derive(Clone, Debug)]
+pub struct DecryptSettings {
+    pub limits: Limits,
+    pub passphrase: Setting<passphrase::Source>,
+    pub overwrite: Setting<Overwrite>,
+}
+
+impl Default for DecryptSettings {
+    fn default() -> Self {
+        Self {
+            limits: Limits::new("0.5", "300s"),
+            passphrase: Setting::new(passphrase::Source::Tty),
+            overwrite: Setting::new(Overwrite::Always),
+        }
+    }
+}
+
+impl DecryptSettings {
+    /// Replaces the settings with the specified options.
+    pub fn apply(&mut self, arg: &Decrypt, origin: &Origin) {
+        self.limits.apply(&arg.limits, origin);
+        self.passphrase.set(arg.passphrase_source(), origin);
+        self.overwrite.set(arg.overwrite, origin);
+    }
+}
+
+/// The effective settings.
+#[derive(Clone, Debug, Default)]
+pub struct Settings {
+    /// The config file, and whether it was found.
+    pub file: Option<(PathBuf, bool)>,
+
+    pub encrypt: EncryptSettings,
+    pub decrypt: DecryptSettings,
+}
+
+impl Settings {
+    /// Replaces the resource limits and the encryption parameters with the
+    /// environment variables.
+    ///
+    /// If any resource limit is specified, the encryption parameters in the
+    /// config file are not used. If both are specified, the encryption
+    /// parameters are used.
+    fn apply_env(&mut self) -> anyhow::Result<()> {
+        let origin = Origin::Environment;
+        let limits = LimitArgs {
+            max_memory: env_value("RSCRYPT_MAX_MEMORY")?,
+            max_memory_fraction: env_value("RSCRYPT_MAX_MEMORY_FRACTION")?,
+            max_time: env_value("RSCRYPT_MAX_TIME")?,
+        };
+        let params = match (
+            env_integer("RSCRYPT_LOG_N", 10, 40)?,
+            env_integer("RSCRYPT_R", 1, 32)?,
+            env_integer("RSCRYPT_P", 1, 32)?,
+        ) {
+            (Some(log_n), Some(r), Some(p)) => Some((log_n, r, p)),
+            (None, None, None) => None,
+            _ => bail!("RSCRYPT_LOG_N, RSCRYPT_R and RSCRYPT_P must be specified together"),
+        };
+
+        self.encrypt.limits.apply(&limits, &origin);
+        if params.is_some() || limits.is_specified() {
+            self.encrypt.params.set(Some(params), &origin);
+        }
+        self.decrypt.limits.apply(&limits, &origin);
+        Ok(())
+    }
+
+    /// Prints the effective settings and where each one came from.
+    pub fn display(&self) {
+        match &self.file {
+            Some((path, true)) => println!("Config file: {}", path.display()),
+            Some((path, false)) => println!("Config file: {} (not found)", path.display()),
+            None => println!("Config file: none"),
+        }
+
+        let display = |key: &str, value: &dyn fmt::Display, origin: &Origin| {
+            println!("{key} = {value} ({origin})");
+        };
+        let display_option = |key: &str, value: Option<&dyn fmt::Display>, origin: &Origin| {
+            display(key, value.unwrap_or(&"none"), origin);
+        };
+
+        let encrypt = &self.encrypt;
+        display_option(
+            "encrypt.max-memory",
+            encrypt.limits.max_memory.as_ref().map(|v| v as _),
+            encrypt.limits.max_memory.origin(),
+        );
+        display(
+            "encrypt.max-memory-fraction",
+            &*encrypt.limits.max_memory_fraction,
+            encrypt.limits.max_memory_fraction.origin(),
+        );
+        display(
+            "encrypt.max-time",
+            &*encrypt.limits.max_time,
+            encrypt.limits.max_time.origin(),
+        );
+        for (key, value) in [
+            (
+                "encrypt.log-n",
+                encrypt.params.map(|(log_n, ..)| log_n.into()),
+            ),
+            ("encrypt.r", encrypt.params.map(|(_, r, _)| r)),
+            ("encrypt.p", encrypt.params.map(|(.., p)| p)),
+        ] {
+            display_option(key, value.as_ref().map(|v| v as _), encrypt.params.origin());
+        }
+        display(
+            "encrypt.passphrase",
+            &*encrypt.passphrase,
+            encrypt.passphrase.origin(),
+        );
+        display(
+            "encrypt.overwrite",
+            &*encrypt.overwrite,
+            encrypt.overwrite.origin(),
+        );
+        display("encrypt.armor", &*encrypt.armor, encrypt.armor.origin());
+
+        let decrypt = &self.decrypt;
+        display_option(
+            "decrypt.max-memory",
+            decrypt.limits.max_memory.as_ref().map(|v| v as _),
+            decrypt.limits.max_memory.origin(),
+        );
+        display(
+            "decrypt.max-memory-fraction",
+            &*decrypt.limits.max_memory_fraction,
+            decrypt.limits.max_memory_fraction.origin(),
+        );
+        display(
+            "decrypt.max-time",
+            &*decrypt.limits.max_time,
+            decrypt.limits.max_time.origin(),
+        );
+        display(
+            "decrypt.passphrase",
+            &*decrypt.passphrase,
+            decrypt.passphrase.origin(),
+        );
+        display(
+            "decrypt.overwrite",
+            &*decrypt.overwrite,
+            decrypt.overwrite.origin(),
+        );
+    }
+}
+
+/// Returns the default path of the config file.
+///
+/// This is `$XDG_CONFIG_HOME/rscrypt/config.toml` or
+/// `$HOME/.config/rscrypt/config.toml` on Unix, and
+/// `%APPDATA%\rscrypt\config.toml` on Windows.
+pub fn default_path() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+    };
+    base.map(|dir| dir.join("rscrypt").join("config.toml"))
+}
+
+/// Loads the settings from the config file and the environment variables.
+///
+/// If `path` is [`None`], the file specified by the environment variable or
+/// the default path is used. It is not an error that the config file at the
+/// default path does not exist.
+///
+/// The environment variables take precedence over the config file.
+pub fn load(path: Option<&Path>) -> anyhow::Result<Settings> {
+    let mut settings = load_file(path)?;
+    settings.apply_env()?;
+    Ok(settings)
+}
+
+/// Loads the settings from the config file.
+fn load_file(path: Option<&Path>) -> anyhow::Result<Settings> {
+    let path = path
+        .map(Path::to_path_buf)
+        .or_else(|| env::var_os(ENV_VAR).map(PathBuf::from));
+    let required = path.is_some();
+    let Some(path) = path.or_else(default_path) else {
+        return Ok(Settings::default());
+    };
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound && !required => {
+            return Ok(Settings {
+                file: Some((path, false)),
+                ..Default::default()
+            });
+        }
+        Err(err) => {
+            return Err(err).with_context(|| format!("could not read {}", path.display()));
+        }
+    };
+    let mut settings = parse(&contents, &Origin::ConfigFile(path.clone()))
+        .with_context(|| format!("could not load config file {}", path.display()))?;
+    settings.file = Some((path, true));
+    Ok(settings)
+}
+
+/// Parses the value of the key.
+fn parse_value<T: FromStr<Err = anyhow::Error>>(item: &Item) -> anyhow::Result<T> {
+    let value = match item.as_value() {
+        Some(value) if value.is_str() => value.as_str().map(String::from),
+        Some(value) if value.is_integer() => value.as_integer().map(|v| v.to_string()),
+        Some(value) if value.is_float() => value.as_float().map(|v| v.to_string()),
+        _ => None,
+    };
+    value
+        .ok_or_else(|| anyhow!("value is not a string or a number"))
+        .and_then(|value| T::from_str(&value))
+}
+
+/// Parses the value of the key as an integer in the range.
+fn parse_integer<T: TryFrom<i64>>(item: &Item, min: i64, max: i64) -> anyhow::Result<T> {
+    item.as_integer()
+        .ok_or_else(|| anyhow!("value is not an integer"))
+        .and_then(|value| to_integer(value, min, max))
+}
+
+/// Converts the integer if it is in the range.
+fn to_integer<T: TryFrom<i64>>(value: i64, min: i64, max: i64) -> anyhow::Result<T> {
+    if !(min..=max).contains(&value) {
+        bail!("{value} is not in {min}..={max}");
+    }
+    T::try_from(value).map_err(|_| anyhow!("value is not in {min}..={max}"))
+}
+
+/// Returns the value of the environment variable.
+///
+/// An empty value is treated as if the environment variable is not set.
+fn env_value<T>(key: &str) -> anyhow::Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: Into<anyhow::Error>,
+{
+    let Some(value) = env::var_os(key).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    value
+        .to_str()
+        .ok_or_else(|| anyhow!("value is not valid UTF-8"))
+        .and_then(|value| T::from_str(value).map_err(Into::into))
+        .map(Some)
+        .with_context(|| format!("invalid value for {key}"))
+}
+
+/// Returns the value of the environment variable as an integer in the range.
+fn env_integer<T: TryFrom<i64>>(key: &str, min: i64, max: i64) -> anyhow::Result<Option<T>> {
+    env_value(key)?
+        .map(|value| {
+            to_integer(value, min, max).with_context(|| format!("invalid value for {key}"))
+        })
+        .transpose()
+}
+
+/// Parses the value of the key as a boolean.
+fn parse_bool(item: &Item) -> anyhow::Result<bool> {
+    item.as_bool()
+        .ok_or_else(|| anyhow!("value is not a boolean"))
+}
+
+/// Returns the table of the section.
+fn as_table<'a>(item: &'a Item, section: &str) -> anyhow::Result<&'a Table> {
+    item.as_table()
+        .ok_or_else(|| anyhow!("\"{section}\" is not a table"))
+}
+
+/// Parses the contents of the config file.
+fn parse(contents: &str, origin: &Origin) -> anyhow::Result<Settings> {
+    let document = DocumentMut::from_str(contents).context("invalid TOML")?;
+    let mut settings = Settings::default();
+
+    for (section, item) in document.iter() {
+        match section {
+            "encrypt" => {
+                let encrypt = &mut settings.encrypt;
+                let (mut log_n, mut r, mut p) = (None, None, None);
+                for (key, item) in as_table(item, section)? {
+                    let invalid = || format!("invalid value for \"{section}.{key}\"");
+                    match key {
+                        "max-memory" => encrypt
+                            .limits
+                            .max_memory
+                            .set(Some(Some(parse_value(item).with_context(invalid)?)), origin),
+                        "max-memory-fraction" => encrypt
+                            .limits
+                            .max_memory_fraction
+                            .set(Some(parse_value(item).with_context(invalid)?), origin),
+                        "max-time" => encrypt
+                            .limits
+                            .max_time
+                            .set(Some(parse_value(item).with_context(invalid)?), origin),
+                        "log-n" => log_n = Some(parse_integer(item, 10, 40).with_context(invalid)?),
+                        "r" => r = Some(parse_integer(item, 1, 32).with_context(invalid)?),
+                        "p" => p = Some(parse_integer(item, 1, 32).with_context(invalid)?),
+                        "passphrase" => encrypt
+                            .passphrase
+                            .set(Some(parse_value(item).with_context(invalid)?), origin),
+                        "overwrite" => encrypt
+                            .overwrite
+                            .set(Some(parse_value(item).with_context(invalid)?), origin),
+                        "armor" => encrypt
+                            .armor
+                            .set(Some(parse_bool(item).with_context(invalid)?), origin),
+                        _ => bail!("unknown key \"{section}.{key}\""),
+                    }
+                }
+                match (log_n, r, p) {
+                    (Some(log_n), Some(r), Some(p)) => {
+                        encrypt.params.set(Some(Some((log_n, r, p))), origin);
+                    }
+                    (None, None, None) => {}
+                    _ => bail!(
+                        "\"{section}.log-n\", \"{section}.r\" and \"{section}.p\" must be specified together"
+                    ),
+                }
+            }
+            "decrypt" => {
+                let decrypt = &mut settings.decrypt;
+                for (key, item) in as_table(item, section)? {
+                    let invalid = || format!("invalid value for \"{section}.{key}\"");
+                    match key {
+                        "max-memory" => decrypt
+                            .limits
+                            .max_memory
+                            .set(Some(Some(parse_value(item).with_context(invalid)?)), origin),
+                        "max-memory-fraction" => decrypt
+                            .limits
+                            .max_memory_fraction
+                            .set(Some(parse_value(item).with_context(invalid)?), origin),
+                        "max-time" => decrypt
+                            .limits
+                            .max_time
+                            .set(Some(parse_value(item).with_context(invalid)?), origin),
+                        "passphrase" => decrypt
+                            .passphrase
+                            .set(Some(parse_value(item).with_context(invalid)?), origin),
+                        "overwrite" => decrypt
+                            .overwrite
+                            .set(Some(parse_value(item).with_context(invalid)?), origin),
+                        _ => bail!("unknown key \"{section}.{key}\""),
+                    }
+                }
+            }
+            _ => bail!("unknown key \"{section}\""),
+        }
+    }
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn origin() -> Origin {
+        Origin::ConfigFile(PathBuf::from("config.toml"))
+    }
+
+    #[test]
+    fn parse_empty() {
+        let settings = parse("", &origin()).unwrap();
+        assert_eq!(*settings.encrypt.limits.max_memory, None);
+        assert_eq!(
+            *settings.encrypt.limits.max_memory_fraction,
+            Rate::from_str("0.125").unwrap()
+        );
+        assert_eq!(
+            *settings.encrypt.limits.max_time,
+            Time::from_str("5s").unwrap()
+        );
+        assert_eq!(*settings.encrypt.params, None);
+        assert_eq!(*settings.encrypt.passphrase, passphrase::Source::Tty);
+        assert_eq!(*settings.encrypt.overwrite, Overwrite::Always);
+        assert!(!*settings.encrypt.armor);
+        assert_eq!(settings.encrypt.armor.origin(), &Origin::Default);
+        assert_eq!(
+            *settings.decrypt.limits.max_memory_fraction,
+            Rate::from_str("0.5").unwrap()
+        );
+        assert_eq!(
+            *settings.decrypt.limits.max_time,
+            Time::from_str("300s").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_all() {
+        let settings = parse(
+            r#"
+[encrypt]
+max-memory = "1 GiB"
+max-memory-fraction = 0.25
+max-time = "10s"
+log-n = 17
+r = 8
+p = 1
+passphrase = "env:RSCRYPT_PASSPHRASE"
+overwrite = "never"
+armor = true
+
+[decrypt]
+max-memory = 2147483648
+max-memory-fraction = "1/4"
+max-time = "1m"
+passphrase = "stdin"
+overwrite = "never"
+"#,
+            &origin(),
+        )
+        .unwrap();
+        assert_eq!(
+            *settings.encrypt.limits.max_memory,
+            Some(Byte::from_str("1 GiB").unwrap())
+        );
+        assert_eq!(settings.encrypt.limits.max_memory.origin(), &origin());
+        assert_eq!(
+            *settings.encrypt.limits.max_memory_fraction,
+            Rate::from_str("0.25").unwrap()
+        );
+        assert_eq!(
+            *settings.encrypt.limits.max_time,
+            Time::from_str("10s").unwrap()
+        );
+        assert_eq!(*settings.encrypt.params, Some((17, 8, 1)));
+        assert_eq!(
+            *settings.encrypt.passphrase,
+            passphrase::Source::Env(String::from("RSCRYPT_PASSPHRASE"))
+        );
+        assert_eq!(*settings.encrypt.overwrite, Overwrite::Never);
+        assert!(*settings.encrypt.armor);
+        assert_eq!(
+            *settings.decrypt.limits.max_memory,
+            Some(Byte::from_str("2 GiB").unwrap())
+        );
+        assert_eq!(
+            *settings.decrypt.limits.max_memory_fraction,
+            Rate::from_str("0.25").unwrap()
+        );
+        assert_eq!(
+            *settings.decrypt.limits.max_time,
+            Time::from_str("60s").unwrap()
+        );
+        assert_eq!(*settings.decrypt.passphrase, passphrase::Source::Stdin);
+        assert_eq!(*settings.decrypt.overwrite, Overwrite::Never);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(
+            parse("[encrypt]\nmax-time = \"5 parsecs\"\n", &origin())
+                .unwrap_err()
+                .to_string()
+                .contains("invalid value for \"encrypt.max-time\"")
+        );
+        assert!(
+            parse("[encrypt]\nlog-n = 9\nr = 8\np = 1\n", &origin())
+                .unwrap_err()
+                .to_string()
+                .contains("invalid value for \"encrypt.log-n\"")
+        );
+        assert!(
+            parse("[encrypt]\nlog-n = 17\n", &origin())
+                .unwrap_err()
+                .to_string()
+                .contains("must be specified together")
+        );
+        assert!(
+            parse("[decrypt]\narmor = true\n", &origin())
+                .unwrap_err()
+                .to_string()
+                .contains("unknown key \"decrypt.armor\"")
+        );
+        assert!(
+            parse("encrypt = 1\n", &origin())
+                .unwrap_err()
+                .to_string()
+                .contains("\"encrypt\" is not a table")
+        );
+        assert!(
+            parse("[encrypt\n", &origin())
+                .unwrap_err()
+                .to_string()
+                .contains("invalid TOML")
+        );
+    }
+
+    #[test]
+    fn override_setting() {
+        let mut setting = Setting::new(1);
+        setting.set(None, &origin());
+        assert_eq!((*setting, setting.origin()), (1, &Origin::Default));
+        setting.set(Some(2), &origin());
+        assert_eq!((*setting, setting.origin()), (2, &origin()));
+        setting.set(None, &Origin::CommandLine);
+        assert_eq!((*setting, setting.origin()), (2, &origin()));
+        setting.set(Some(3), &Origin::CommandLine);
+        assert_eq!((*setting, setting.origin()), (3, &Origin::CommandLine));
+    }
+}
@@ -0,0 +1,232 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    env,
+    ffi::OsString,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    mem,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+use anyhow::{Context, bail};
+
+use crate::output;
+
+/// Returns the directory to create the temporary file in.
+///
+/// A tmpfs such as `$XDG_RUNTIME_DIR` and `/dev/shm` is preferred so that the
+/// plaintext is not written to a disk.
+fn temp_base() -> PathBuf {
+    #[cfg(unix)]
+    {
+        let candidates = env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .into_iter()
+            .chain([PathBuf::from("/dev/shm")]);
+        for dir in candidates {
+            if dir.is_absolute() && dir.is_dir() {
+                return dir;
+            }
+        }
+    }
+    env::temp_dir()
+}
+
+/// Returns the name of the temporary file for `path`.
+///
+/// The extensions of the encrypted file are removed so that the editor can
+/// detect the file type from the name.
+fn temp_name(path: &Path) -> OsString {
+    let mut name = path
+        .file_name()
+        .map_or_else(|| "data".into(), ToOwned::to_owned);
+    for ext in ["asc", "scrypt"] {
+        let path = Path::new(&name);
+        if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+        {
+            if let Some(stem) = path.file_stem() {
+                name = stem.to_owned();
+            }
+        }
+    }
+    name
+}
+
+/// A temporary file which only the owner can access.
+///
+/// The file is overwritten with random data and removed when this is
+/// dropped, unless [`TempFile::keep`] is called.
+#[derive(Debug)]
+pub struct TempFile {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Creates a temporary file for editing `original` with `data`.
+    ///
+    /// On Unix, the file is created with mode 0600 in a directory with mode
+    /// 0700.
+    pub fn new(original: &Path, data: &[u8]) -> anyhow::Result<Self> {
+        let base = temp_base();
+        let dir = (0..)
+            .map(|i| base.join(format!("rscrypt-edit-{}-{i}", process::id())))
+            .find_map(|dir| {
+                let mut builder = fs::DirBuilder::new();
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::DirBuilderExt;
+
+                    builder.mode(0o700);
+                }
+                match builder.create(&dir) {
+                    Ok(()) => Some(Ok(dir)),
+                    Err(err) if err.kind() == io::ErrorKind::AlreadyExists => None,
+                    Err(err) => Some(Err(err)),
+                }
+            })
+            .expect("temporary directory should be found")
+            .with_context(|| {
+                format!(
+                    "could not create a temporary directory in {}",
+                    base.display()
+                )
+            })?;
+        let file = Self {
+            path: dir.join(temp_name(original)),
+            dir,
+        };
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+
+            options.mode(0o600);
+        }
+        options
+            .open(&file.path)
+            .and_then(|mut f| f.write_all(data))
+            .with_context(|| format!("could not write data to {}", file.path.display()))?;
+        Ok(file)
+    }
+
+    /// Returns the path of the temporary file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the contents of the temporary file.
+    pub fn read(&self) -> anyhow::Result<Vec<u8>> {
+        fs::read(&self.path)
+            .with_context(|| format!("could not read data from {}", self.path.display()))
+    }
+
+    /// Keeps the temporary file instead of removing it, and returns its path.
+    pub fn keep(self) -> PathBuf {
+        let path = self.path.clone();
+        mem::forget(self);
+        path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = output::shred(&self.path);
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Returns the editor command.
+///
+/// This is `$VISUAL` or `$EDITOR` if they are set, and a platform default
+/// otherwise.
+fn editor() -> OsString {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(env::var_os)
+        .find(|e| !e.is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.into())
+}
+
+/// Launches the editor for `path` and waits for it to exit.
+pub fn launch_editor(path: &Path) -> anyhow::Result<()> {
+    let editor = editor();
+    // The editor command may contain arguments, such as "code --wait".
+    #[cfg(unix)]
+    let mut command = {
+        let mut command = Command::new("sh");
+        let mut script = editor.clone();
+        script.push(r#" "$@""#);
+        command.arg("-c").arg(script).arg(&editor).arg(path);
+        command
+    };
+    #[cfg(not(unix))]
+    let mut command = {
+        let editor = editor.to_string_lossy();
+        let mut args = editor.split_whitespace();
+        let mut command = Command::new(args.next().unwrap_or("notepad"));
+        command.args(args).arg(path);
+        command
+    };
+    let status = command
+        .status()
+        .with_context(|| format!("could not run the editor {}", editor.to_string_lossy()))?;
+    if !status.success() {
+        bail!(
+            "the editor {} exited with {status}",
+            editor.to_string_lossy()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_of_temp_file() {
+        assert_eq!(temp_name(Path::new("config.toml.scrypt")), "config.toml");
+        assert_eq!(
+            temp_name(Path::new("dir/config.toml.scrypt.asc")),
+            "config.toml"
+        );
+        assert_eq!(temp_name(Path::new("config.toml")), "config.toml");
+        assert_eq!(temp_name(Path::new("notes.SCRYPT")), "notes");
+    }
+
+    #[test]
+    fn temp_file() {
+        let file = TempFile::new(Path::new("data.txt.scrypt"), b"Hello, world!\n").unwrap();
+        let path = file.path().to_path_buf();
+        assert_eq!(path.file_name().unwrap(), "data.txt");
+        assert_eq!(file.read().unwrap(), b"Hello, world!\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = |p: &Path| fs::metadata(p).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&path), 0o600);
+            assert_eq!(mode(path.parent().unwrap()), 0o700);
+        }
+        drop(file);
+        assert!(!path.exists());
+        assert!(!path.parent().unwrap().exists());
+    }
+
+    #[test]
+    fn keep_temp_file() {
+        let file = TempFile::new(Path::new("data.txt.scrypt"), b"Hello, world!\n").unwrap();
+        let path = file.path().to_path_buf();
+        assert_eq!(file.keep(), path);
+        assert_eq!(fs::read(&path).unwrap(), b"Hello, world!\n");
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,428 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    cell::OnceCell,
+    fmt::Write as _,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{Context, bail};
+use rand::{RngCore, rngs::OsRng};
+use scryptenc::{
+    Decryptor, Encryptor, Error as ScryptencError,
+    hmac::{Hmac, Mac},
+    scrypt,
+};
+use sha2::Sha256;
+
+#[cfg(unix)]
+use crate::agent;
+use crate::passphrase::KeyringEntry;
+
+/// The name of the filter and the diff driver.
+pub const DRIVER_NAME: &str = "rscrypt";
+
+/// The salt to derive the key of the keyed hash which derives the salt of the
+/// encrypted data.
+///
+/// This is followed by the random value of the repository, if any.
+const KEY_SALT: &[u8] = b"rscrypt git-filter salt key";
+
+/// The name of the file in the top-level directory of the working tree which
+/// holds the random value of the repository.
+///
+/// This file is committed, so every clone derives the same salt.
+const SALT_FILE_NAME: &str = ".rscrypt-salt";
+
+/// The size of the random value of the repository in bytes.
+const REPOSITORY_SALT_SIZE: usize = 32;
+
+/// Returns the top-level directory of the working tree of the current
+/// repository, or [`None`] outside a repository.
+fn top_level() -> anyhow::Result<Option<PathBuf>> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .context("could not run git")?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let top_level = String::from_utf8(output.stdout).context("output of git is not valid UTF-8")?;
+    Ok(Some(PathBuf::from(top_level.trim_end())))
+}
+
+/// Returns the random value of the current repository, which is mixed into
+/// the salt of the key of the keyed hash.
+///
+/// Returns [`None`] if it does not exist, such as outside a repository or in a
+/// repository which was set up by an older version.
+pub fn repository_salt() -> anyhow::Result<Option<String>> {
+    let Some(top_level) = top_level()? else {
+        return Ok(None);
+    };
+    let path = top_level.join(SALT_FILE_NAME);
+    match fs::read_to_string(&path) {
+        Ok(salt) => Ok(Some(salt.trim().to_owned())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("could not read {}", path.display())),
+    }
+}
+
+/// Returns `true` if `data` is scrypt encrypted data.
+fn is_encrypted(data: &[u8]) -> bool {
+    !matches!(
+        scryptenc::Params::new(data),
+        Err(ScryptencError::InvalidLength | ScryptencError::InvalidMagicNumber)
+    )
+}
+
+/// Encrypts data deterministically.
+///
+/// The key of the keyed hash which derives the salt is derived from the
+/// passphrase with the encryption parameters, so the salt does not make it
+/// easier to guess the passphrase. This costs as much as encrypting the data
+/// itself, so the key is derived at most once.
+#[derive(Debug)]
+pub struct Cleaner<'a> {
+    passphrase: &'a [u8],
+    params: scrypt::Params,
+    repository_salt: Option<String>,
+    key: OnceCell<[u8; 32]>,
+}
+
+impl<'a> Cleaner<'a> {
+    /// Creates a new `Cleaner`.
+    pub const fn new(
+        passphrase: &'a [u8],
+        params: scrypt::Params,
+        repository_salt: Option<String>,
+    ) -> Self {
+        Self {
+            passphrase,
+            params,
+            repository_salt,
+            key: OnceCell::new(),
+        }
+    }
+
+    /// Returns the key of the keyed hash.
+    fn key(&self) -> &[u8; 32] {
+        self.key.get_or_init(|| {
+            let mut salt = KEY_SALT.to_vec();
+            if let Some(repository_salt) = &self.repository_salt {
+                salt.extend_from_slice(repository_salt.as_bytes());
+            }
+            let mut key = [u8::default(); 32];
+            scrypt::scrypt(self.passphrase, &salt, &self.params, &mut key)
+                .expect("size of the key should be 32 bytes");
+            key
+        })
+    }
+
+    /// Derives the salt from `path` and `plaintext`.
+    fn derive_salt(&self, path: &Path, plaintext: &[u8]) -> [u8; 32] {
+        let path = path.as_os_str().as_encoded_bytes();
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.key())
+            .expect("HMAC-SHA-256 key size should be 256 bits");
+        mac.update(&(path.len() as u64).to_be_bytes());
+        mac.update(path);
+        mac.update(plaintext);
+        mac.finalize().into_bytes().into()
+    }
+
+    /// Encrypts `plaintext` deterministically.
+    ///
+    /// Data which is already scrypt encrypted data is returned as is.
+    pub fn clean(&self, plaintext: Vec<u8>, path: &Path) -> Vec<u8> {
+        if is_encrypted(&plaintext) {
+            return plaintext;
+        }
+        let salt = self.derive_salt(path, &plaintext);
+        Encryptor::with_salt(&plaintext, self.passphrase, self.params, salt).encrypt_to_vec()
+    }
+}
+
+/// Decrypts `ciphertext`.
+///
+/// Data which is not scrypt encrypted data is returned as is. The passphrase is
+/// only read if the agent on the socket at `agent` does not hold the derived
+/// key.
+#[cfg_attr(not(unix), allow(unused_variables))]
+pub fn smudge(
+    ciphertext: Vec<u8>,
+    passphrase: impl FnOnce() -> anyhow::Result<Vec<u8>>,
+    agent: Option<&Path>,
+) -> anyhow::Result<Vec<u8>> {
+    if !is_encrypted(&ciphertext) {
+        return Ok(ciphertext);
+    }
+    #[cfg(unix)]
+    let cached = agent.and_then(|socket| agent::decryptor(socket, &ciphertext));
+    #[cfg(not(unix))]
+    let cached = None;
+    let cipher = if let Some(cipher) = cached {
+        cipher
+    } else {
+        let cipher = match Decryptor::new(&ciphertext, passphrase()?) {
+            c @ Err(ScryptencError::InvalidHeaderMac(_)) => c.context("passphrase is incorrect"),
+            c => c.context("the header in the encrypted data is invalid"),
+        }?;
+        #[cfg(unix)]
+        if let Some(socket) = agent {
+            agent::put(socket, &ciphertext, &cipher.derived_key());
+        }
+        cipher
+    };
+    cipher
+        .decrypt_to_vec()
+        .context("the encrypted data is corrupted")
+}
+
+/// Quotes `s` for the shell.
+fn shell_quote(s: &str) -> String {
+    if !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-_./=:,+@%".contains(&b))
+    {
+        return s.to_owned();
+    }
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Runs `git` with `args` and returns its standard output.
+fn git(args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .context("could not run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).context("output of git is not valid UTF-8")
+}
+
+/// Returns the absolute path of `path`.
+fn absolute(path: &Path) -> anyhow::Result<PathBuf> {
+    std::path::absolute(path)
+        .with_context(|| format!("could not get the absolute path of {}", path.display()))
+}
+
+/// The options passed to the filter.
+#[derive(Clone, Debug)]
+pub struct FilterOptions {
+    pub params: scrypt::Params,
+    pub passphrase_from_env: Option<String>,
+    pub passphrase_from_file: Option<PathBuf>,
+    pub passphrase_from_keyring: Option<KeyringEntry>,
+    pub passphrase_from_command: Option<String>,
+    pub keyfile: Option<PathBuf>,
+    pub keyfile_digest: bool,
+}
+
+impl FilterOptions {
+    /// Returns the options as the arguments of `rscrypt git-filter`.
+    fn to_args(&self) -> anyhow::Result<Vec<String>> {
+        let mut args = vec![
+            "--log-n".to_owned(),
+            self.params.log_n().to_string(),
+            "-r".to_owned(),
+            self.params.r().to_string(),
+            "-p".to_owned(),
+            self.params.p().to_string(),
+        ];
+        if let Some(env) = &self.passphrase_from_env {
+            args.extend(["--passphrase-from-env".to_owned(), env.clone()]);
+        }
+        if let Some(file) = &self.passphrase_from_file {
+            args.extend([
+                "--passphrase-from-file".to_owned(),
+                absolute(file)?.display().to_string(),
+            ]);
+        }
+        if let Some(entry) = &self.passphrase_from_keyring {
+            args.extend(["--passphrase-from-keyring".to_owned(), entry.to_string()]);
+        }
+        if let Some(command) = &self.passphrase_from_command {
+            args.extend(["--passphrase-from-command".to_owned(), command.clone()]);
+        }
+        if let Some(file) = &self.keyfile {
+            args.extend([
+                "--keyfile".to_owned(),
+                absolute(file)?.display().to_string(),
+            ]);
+        }
+        if self.keyfile_digest {
+            args.push("--keyfile-digest".to_owned());
+        }
+        Ok(args)
+    }
+}
+
+/// Sets up the filter for `patterns` in the current repository.
+pub fn init(patterns: &[String], options: &FilterOptions) -> anyhow::Result<()> {
+    let top_level = git(&["rev-parse", "--show-toplevel"])?;
+    let top_level = Path::new(top_level.trim_end());
+
+    let args = options
+        .to_args()?
+        .iter()
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let command = format!("rscrypt git-filter {args}");
+    let entries = [
+        (
+            format!("filter.{DRIVER_NAME}.clean"),
+            format!("{command} clean -- %f"),
+        ),
+        (
+            format!("filter.{DRIVER_NAME}.smudge"),
+            format!("{command} smudge -- %f"),
+        ),
+        (format!("filter.{DRIVER_NAME}.required"), "true".to_owned()),
+        (
+            format!("diff.{DRIVER_NAME}.textconv"),
+            format!("{command} diff"),
+        ),
+    ];
+    for (key, value) in &entries {
+        git(&["config", "--local", key, value])?;
+    }
+    let path = top_level.join(SALT_FILE_NAME);
+    if !path.exists() {
+        let mut salt = [u8::default(); REPOSITORY_SALT_SIZE];
+        OsRng.fill_bytes(&mut salt);
+        let mut salt = salt.iter().fold(String::new(), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        });
+        salt.push('\n');
+        fs::write(&path, salt).with_context(|| format!("could not write to {}", path.display()))?;
+    }
+
+    let path = top_level.join(".gitattributes");
+    let current = match fs::read_to_string(&path) {
+        Ok(s) => s,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => {
+            return Err(err).with_context(|| format!("could not read {}", path.display()));
+        }
+    };
+    let mut additions = String::new();
+    if !current.is_empty() && !current.ends_with('\n') {
+        additions.push('\n');
+    }
+    for pattern in patterns {
+        let line = format!("{pattern} filter={DRIVER_NAME} diff={DRIVER_NAME}");
+        if !current.lines().any(|l| l.trim() == line) {
+            additions.push_str(&line);
+            additions.push('\n');
+        }
+    }
+    if additions.trim().is_empty() {
+        return Ok(());
+    }
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut f| f.write_all(additions.as_bytes()))
+        .with_context(|| format!("could not write to {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &[u8] = b"passphrase";
+
+    fn params() -> scrypt::Params {
+        scrypt::Params::new(10, 8, 1, scrypt::Params::RECOMMENDED_LEN).unwrap()
+    }
+
+    #[test]
+    fn clean_is_deterministic() {
+        let cleaner = Cleaner::new(PASSPHRASE, params(), None);
+        let path = Path::new("secrets.env");
+        let first = cleaner.clean(b"Hello, world!\n".to_vec(), path);
+        let second = cleaner.clean(b"Hello, world!\n".to_vec(), path);
+        assert_eq!(first, second);
+        assert_ne!(
+            first,
+            cleaner.clean(b"Hello, world!\n".to_vec(), Path::new("other.env"))
+        );
+        assert_ne!(first, cleaner.clean(b"Hello, world!".to_vec(), path));
+        assert_eq!(
+            smudge(first, || Ok(PASSPHRASE.to_vec()), None).unwrap(),
+            b"Hello, world!\n"
+        );
+    }
+
+    #[test]
+    fn clean_with_repository_salt() {
+        let path = Path::new("secrets.env");
+        let without =
+            Cleaner::new(PASSPHRASE, params(), None).clean(b"Hello, world!\n".to_vec(), path);
+        let cleaner = Cleaner::new(PASSPHRASE, params(), Some("0123456789abcdef".to_owned()));
+        let with = cleaner.clean(b"Hello, world!\n".to_vec(), path);
+        assert_ne!(with, without);
+        assert_eq!(cleaner.clean(b"Hello, world!\n".to_vec(), path), with);
+        assert_ne!(
+            Cleaner::new(PASSPHRASE, params(), Some("fedcba9876543210".to_owned()))
+                .clean(b"Hello, world!\n".to_vec(), path),
+            with
+        );
+        assert_eq!(
+            smudge(with, || Ok(PASSPHRASE.to_vec()), None).unwrap(),
+            b"Hello, world!\n"
+        );
+    }
+
+    #[test]
+    fn pass_through() {
+        let ciphertext = include_bytes!("../tests/data/data.txt.scrypt").to_vec();
+        let cleaner = Cleaner::new(PASSPHRASE, params(), None);
+        assert_eq!(cleaner.clean(ciphertext.clone(), Path::new("")), ciphertext);
+        assert!(cleaner.key.get().is_none());
+        assert_eq!(
+            smudge(
+                b"Hello, world!\n".to_vec(),
+                || unreachable!("passphrase should not be read"),
+                None
+            )
+            .unwrap(),
+            b"Hello, world!\n"
+        );
+    }
+
+    #[test]
+    fn envelope_is_not_encrypted() {
+        let envelope =
+            scryptenc::envelope::encrypt(b"Hello, world!\n", &[PASSPHRASE], params()).unwrap();
+        assert!(!is_encrypted(&envelope));
+        let cleaner = Cleaner::new(PASSPHRASE, params(), None);
+        let ciphertext = cleaner.clean(envelope.clone(), Path::new("secrets.env"));
+        assert!(is_encrypted(&ciphertext));
+        assert_eq!(
+            smudge(ciphertext, || Ok(PASSPHRASE.to_vec()), None).unwrap(),
+            envelope
+        );
+    }
+
+    #[test]
+    fn quote() {
+        assert_eq!(shell_quote("RSCRYPT_PASSPHRASE"), "RSCRYPT_PASSPHRASE");
+        assert_eq!(shell_quote("/tmp/pass file"), "'/tmp/pass file'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+}
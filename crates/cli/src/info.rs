@@ -0,0 +1,202 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{fmt::Write, path::Path, time::Duration};
+
+use anyhow::bail;
+use byte_unit::UnitType;
+use scryptenc::{HEADER_SIZE, TAG_SIZE, envelope};
+use sha2::{Digest, Sha256};
+
+use crate::{config::DecryptSettings, params};
+
+/// Information about the encrypted data.
+#[derive(Clone, Debug)]
+pub struct Information {
+    pub path: String,
+    pub params: scryptenc::Params,
+    pub version: u8,
+    pub salt: [u8; 32],
+    pub checksum_valid: bool,
+    pub total_size: usize,
+    pub payload_size: usize,
+    pub estimated_time: Duration,
+    pub exceeds_default_limits: bool,
+}
+
+impl Information {
+    /// Reads the information from the header of `data`.
+    ///
+    /// This does not require the passphrase.
+    pub fn new(path: &Path, data: &[u8]) -> anyhow::Result<Self> {
+        if envelope::is_envelope(data) {
+            bail!("data is an envelope; use `rscrypt passphrase list` to show its slots");
+        }
+        let params = params::get(data)?;
+        let salt = data[16..48]
+            .try_into()
+            .expect("size of salt should be 32 bytes");
+        let checksum_valid = Sha256::digest(&data[..48])[..16] == data[48..64];
+        let limits = DecryptSettings::default().limits;
+        let exceeds_default_limits = params::check(
+            *limits.max_memory,
+            *limits.max_memory_fraction,
+            *limits.max_time,
+            params.log_n(),
+            params.r(),
+            params.p(),
+        )
+        .is_err();
+        Ok(Self {
+            path: path.display().to_string(),
+            params,
+            version: data[6],
+            salt,
+            checksum_valid,
+            total_size: data.len(),
+            payload_size: data.len() - (HEADER_SIZE + TAG_SIZE),
+            estimated_time: params::estimate_time(params.log_n(), params.r(), params.p()),
+            exceeds_default_limits,
+        })
+    }
+
+    /// Returns the salt as a hexadecimal string.
+    pub fn salt_hex(&self) -> String {
+        self.salt.iter().fold(String::new(), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        })
+    }
+
+    /// Returns the amount of memory required to compute the derived key.
+    pub fn memory(&self) -> u64 {
+        128 * self.params.n() * u64::from(self.params.r())
+    }
+}
+
+/// Formats `bytes` with the appropriate binary unit.
+pub fn format_bytes(bytes: u64) -> String {
+    format!(
+        "{:#.1}",
+        byte_unit::Byte::from(bytes).get_appropriate_unit(UnitType::Binary)
+    )
+}
+
+/// Returns "yes" if `b` is `true`, and "no" otherwise.
+const fn yes_or_no(b: bool) -> &'static str {
+    if b { "yes" } else { "no" }
+}
+
+/// Prints the information about a single file.
+pub fn display(info: &Information) {
+    params::displayln_without_resources(info.params.log_n(), info.params.r(), info.params.p());
+    eprintln!("Format version: {}", info.version);
+    eprintln!("Salt: {}", info.salt_hex());
+    eprintln!(
+        "Header checksum: {}",
+        if info.checksum_valid {
+            "valid"
+        } else {
+            "invalid"
+        }
+    );
+    eprintln!("Total size: {} bytes", info.total_size);
+    eprintln!("Payload size: {} bytes", info.payload_size);
+    eprintln!("Estimated decryption time: {:.1?}", info.estimated_time);
+    eprintln!(
+        "Exceeds default limits: {}",
+        yes_or_no(info.exceeds_default_limits)
+    );
+}
+
+/// Prints the information about multiple files as a table.
+pub fn display_table(infos: &[Information]) {
+    println!(
+        "{:>8} {:>3} {:>3} {:>7} {:>8} {:>12} {:>12} {:>12} {:>7} file",
+        "N", "r", "p", "version", "checksum", "memory", "payload", "time", "exceeds"
+    );
+    for info in infos {
+        println!(
+            "{:>8} {:>3} {:>3} {:>7} {:>8} {:>12} {:>12} {:>12} {:>7} {}",
+            info.params.n(),
+            info.params.r(),
+            info.params.p(),
+            info.version,
+            if info.checksum_valid { "ok" } else { "bad" },
+            format_bytes(info.memory()),
+            format_bytes(info.payload_size as u64),
+            format!("{:.1?}", info.estimated_time),
+            yes_or_no(info.exceeds_default_limits),
+            info.path
+        );
+    }
+}
+
+/// The information about the encrypted data.
+#[cfg(feature = "json")]
+#[derive(Debug, serde::Serialize)]
+pub struct Report {
+    #[serde(flatten)]
+    params: params::Params,
+    file: String,
+    version: u8,
+    salt: String,
+    checksum_valid: bool,
+    total_size: usize,
+    payload_size: usize,
+    estimated_time: f64,
+    exceeds_default_limits: bool,
+}
+
+#[cfg(feature = "json")]
+impl Report {
+    /// Creates a new `Report`.
+    pub fn new(info: &Information) -> Self {
+        Self {
+            params: params::Params::new(info.params),
+            file: info.path.clone(),
+            version: info.version,
+            salt: info.salt_hex(),
+            checksum_valid: info.checksum_valid,
+            total_size: info.total_size,
+            payload_size: info.payload_size,
+            estimated_time: info.estimated_time.as_secs_f64(),
+            exceeds_default_limits: info.exceeds_default_limits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = include_bytes!("../tests/data/data.txt.scrypt");
+
+    #[test]
+    fn new() {
+        let info = Information::new(Path::new("data.txt.scrypt"), DATA).unwrap();
+        assert_eq!(info.params.n(), 1024);
+        assert_eq!(info.version, 0);
+        assert_eq!(info.salt, DATA[16..48]);
+        assert!(info.checksum_valid);
+        assert_eq!(info.total_size, 142);
+        assert_eq!(info.payload_size, 14);
+        assert!(!info.exceeds_default_limits);
+    }
+
+    #[test]
+    fn new_with_invalid_checksum() {
+        let mut data = DATA.to_vec();
+        data[48] ^= 1;
+        let info = Information::new(Path::new("data.txt.scrypt"), &data).unwrap();
+        assert!(!info.checksum_valid);
+    }
+
+    #[test]
+    fn salt_hex() {
+        let info = Information::new(Path::new("data.txt.scrypt"), DATA).unwrap();
+        assert_eq!(info.salt_hex().len(), 64);
+        assert!(info.salt_hex().starts_with(&format!("{:02x}", DATA[16])));
+    }
+}
@@ -9,6 +9,7 @@ use std::{
 };
 
 use anyhow::Context;
+use scryptenc::armor;
 
 /// Reads the data to process.
 pub fn read(path: &Path) -> anyhow::Result<Vec<u8>> {
@@ -22,3 +23,15 @@ pub fn read(path: &Path) -> anyhow::Result<Vec<u8>> {
         fs::read(path).with_context(|| format!("could not read data from {}", path.display()))
     }
 }
+
+/// Reads the encrypted data to process.
+///
+/// If the data is ASCII-armored, this decodes it.
+pub fn read_encrypted(path: &Path) -> anyhow::Result<Vec<u8>> {
+    let data = read(path)?;
+    if armor::is_armored(&data) {
+        armor::decode(data).context("could not decode the ASCII-armored data")
+    } else {
+        Ok(data)
+    }
+}
@@ -0,0 +1,120 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::HashMap;
+
+use anyhow::{Context, bail};
+use zbus::{
+    blocking::{Connection, Proxy},
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value},
+};
+use zeroize::Zeroizing;
+
+/// The bus name of the Secret Service.
+const BUS_NAME: &str = "org.freedesktop.secrets";
+
+/// Returns the object path of the Secret Service.
+const fn service_path() -> ObjectPath<'static> {
+    ObjectPath::from_static_str_unchecked("/org/freedesktop/secrets")
+}
+
+/// The attribute which holds the service name of the item.
+pub const SERVICE_ATTRIBUTE: &str = "service";
+
+/// The attribute which holds the account name of the item.
+pub const ACCOUNT_ATTRIBUTE: &str = "username";
+
+/// The secret returned by the Secret Service.
+///
+/// This is the session, the parameters, the value and the content type.
+type Secret = (OwnedObjectPath, Vec<u8>, Vec<u8>, String);
+
+/// A session with the Secret Service which is closed when dropped.
+struct Session<'a>(Proxy<'a>);
+
+impl Session<'_> {
+    /// Opens a session with the Secret Service.
+    fn open(connection: &Connection) -> anyhow::Result<Self> {
+        let service = proxy(connection, service_path(), "org.freedesktop.Secret.Service")?;
+        let (_, path): (OwnedValue, OwnedObjectPath) = service
+            .call("OpenSession", &("plain", Value::from("")))
+            .context("could not open a session with the Secret Service")?;
+        let session = proxy(connection, path, "org.freedesktop.Secret.Session")?;
+        Ok(Self(session))
+    }
+
+    /// Returns the object path of the session.
+    fn path(&self) -> &ObjectPath<'_> {
+        self.0.path()
+    }
+}
+
+impl Drop for Session<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.call_method("Close", &());
+    }
+}
+
+/// Creates a proxy for the object of the Secret Service.
+fn proxy<'a>(
+    connection: &Connection,
+    path: impl Into<ObjectPath<'a>>,
+    interface: &'static str,
+) -> zbus::Result<Proxy<'a>> {
+    Proxy::new(connection, BUS_NAME, path.into(), interface)
+}
+
+/// Unlocks `item`, prompting the user if necessary.
+fn unlock(connection: &Connection, item: &OwnedObjectPath) -> anyhow::Result<()> {
+    let service = proxy(connection, service_path(), "org.freedesktop.Secret.Service")?;
+    let (_, prompt): (Vec<OwnedObjectPath>, OwnedObjectPath) =
+        service.call("Unlock", &(&[item],))?;
+    if prompt.as_str() == "/" {
+        return Ok(());
+    }
+    let prompt = proxy(connection, prompt, "org.freedesktop.Secret.Prompt")?;
+    let mut completed = prompt.receive_signal("Completed")?;
+    prompt.call_method("Prompt", &("",))?;
+    let dismissed = completed
+        .next()
+        .context("the prompt to unlock the keyring was closed")?
+        .body()
+        .deserialize::<(bool, OwnedValue)>()?
+        .0;
+    if dismissed {
+        bail!("unlocking the keyring was dismissed");
+    }
+    Ok(())
+}
+
+/// Reads the secret for `service` and `account` from the Secret Service.
+///
+/// The item is looked up by the "service" and "username" attributes.
+pub fn read(service: &str, account: &str) -> anyhow::Result<Zeroizing<Vec<u8>>> {
+    let connection = Connection::session().context("could not connect to the session bus")?;
+    let secrets = proxy(
+        &connection,
+        service_path(),
+        "org.freedesktop.Secret.Service",
+    )?;
+    let session = Session::open(&connection)?;
+
+    let attributes = HashMap::from([(SERVICE_ATTRIBUTE, service), (ACCOUNT_ATTRIBUTE, account)]);
+    let (unlocked, locked): (Vec<OwnedObjectPath>, Vec<OwnedObjectPath>) =
+        secrets.call("SearchItems", &(attributes,))?;
+    let item = if let Some(item) = unlocked.into_iter().next() {
+        item
+    } else if let Some(item) = locked.into_iter().next() {
+        unlock(&connection, &item)?;
+        item
+    } else {
+        bail!("no item for {service}/{account} was found in the keyring");
+    };
+
+    let item = proxy(&connection, item, "org.freedesktop.Secret.Item")?;
+    let (_, _, value, _): Secret = item
+        .call("GetSecret", &(session.path(),))
+        .context("could not get the secret from the keyring")?;
+    Ok(Zeroizing::new(value))
+}
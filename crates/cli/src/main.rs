@@ -5,12 +5,30 @@
 // Lint levels of Clippy.
 #![allow(clippy::multiple_crate_versions)]
 
+#[cfg(unix)]
+mod agent;
 mod app;
+mod audit;
+mod bench;
+mod calibration;
+mod check;
 mod cli;
+mod config;
+mod edit;
+mod git;
+mod info;
 mod input;
+#[cfg(feature = "keyring")]
+mod keyring;
 mod output;
 mod params;
 mod passphrase;
+mod progress;
+#[cfg(feature = "x25519")]
+mod recipient;
+#[cfg(feature = "json")]
+mod report;
+mod slot;
 mod utils;
 
 use std::{
@@ -18,7 +36,10 @@ use std::{
     process::{self, Termination},
 };
 
-use scryptenc::Error as ScryptencError;
+use clap::Parser;
+use scryptenc::{Error as ScryptencError, envelope, pem_rfc7468};
+
+use crate::cli::Opt;
 
 /// The system exit code of this package.
 #[derive(Debug)]
@@ -60,50 +81,94 @@ impl From<sysexits::ExitCode> for ExitCode {
     }
 }
 
-impl Termination for ExitCode {
-    fn report(self) -> process::ExitCode {
+impl ExitCode {
+    /// Returns the exit code as an integer.
+    fn code(&self) -> u8 {
         match self {
-            Self::Success => process::ExitCode::SUCCESS,
-            Self::Failure => process::ExitCode::FAILURE,
-            Self::InvalidFormat => 7.into(),
-            Self::UnknownVersion => 8.into(),
-            Self::LackOfMemory => 9.into(),
-            Self::LackOfCpuTime => 10.into(),
-            Self::InvalidPassphrase => 11.into(),
-            Self::InvalidParams => 14.into(),
-            Self::LackOfResources => 15.into(),
-            Self::Other(code) => code.into(),
+            Self::Success => 0,
+            Self::Failure => 1,
+            Self::InvalidFormat => 7,
+            Self::UnknownVersion => 8,
+            Self::LackOfMemory => 9,
+            Self::LackOfCpuTime => 10,
+            Self::InvalidPassphrase => 11,
+            Self::InvalidParams => 14,
+            Self::LackOfResources => 15,
+            Self::Other(code) => (*code).into(),
         }
     }
 }
 
+impl Termination for ExitCode {
+    fn report(self) -> process::ExitCode {
+        self.code().into()
+    }
+}
+
+/// Returns the exit code for the error of the scrypt encrypted data format.
+const fn scryptenc_exit_code(err: ScryptencError) -> ExitCode {
+    match err {
+        ScryptencError::InvalidLength
+        | ScryptencError::InvalidMagicNumber
+        | ScryptencError::InvalidChecksum
+        | ScryptencError::InvalidMac(_) => ExitCode::InvalidFormat,
+        ScryptencError::UnknownVersion(_) => ExitCode::UnknownVersion,
+        ScryptencError::InvalidParams(_) => ExitCode::InvalidParams,
+        ScryptencError::InvalidHeaderMac(_) => ExitCode::InvalidPassphrase,
+    }
+}
+
+/// Returns the exit code for the error.
+fn exit_code(err: &anyhow::Error) -> ExitCode {
+    if let Some(e) = err.downcast_ref::<io::Error>() {
+        return sysexits::ExitCode::from(e.kind()).into();
+    }
+    if let Some(e) = err.downcast_ref::<ScryptencError>() {
+        return scryptenc_exit_code(*e);
+    }
+    if let Some(e) = err.downcast_ref::<envelope::Error>() {
+        return match e {
+            envelope::Error::InvalidLength | envelope::Error::InvalidMagicNumber => {
+                ExitCode::InvalidFormat
+            }
+            envelope::Error::UnknownVersion(_) => ExitCode::UnknownVersion,
+            envelope::Error::NoMatchingSlot | envelope::Error::NoMatchingIdentity => {
+                ExitCode::InvalidPassphrase
+            }
+            envelope::Error::Slot(e) | envelope::Error::Body(e) => scryptenc_exit_code(*e),
+            envelope::Error::InvalidSlotCount(_)
+            | envelope::Error::InvalidSlotIndex(_)
+            | envelope::Error::InvalidRecipient => ExitCode::Failure,
+        };
+    }
+    if err.downcast_ref::<pem_rfc7468::Error>().is_some() {
+        return ExitCode::InvalidFormat;
+    }
+    if let Some(e) = err.downcast_ref::<params::Error>() {
+        return match e {
+            params::Error::Memory => ExitCode::LackOfMemory,
+            params::Error::CpuTime => ExitCode::LackOfCpuTime,
+            params::Error::Resources => ExitCode::LackOfResources,
+        };
+    }
+    ExitCode::Failure
+}
+
 fn main() -> ExitCode {
-    match app::run() {
+    let opt = Opt::parse();
+    #[cfg(feature = "json")]
+    let json = opt.json;
+    match app::run(opt) {
         Ok(()) => ExitCode::Success,
         Err(err) => {
-            eprintln!("Error: {err:?}");
-            if let Some(e) = err.downcast_ref::<io::Error>() {
-                return sysexits::ExitCode::from(e.kind()).into();
+            let code = exit_code(&err);
+            #[cfg(feature = "json")]
+            if json {
+                report::ErrorReport::new(&err, code.code()).print();
+                return code;
             }
-            if let Some(e) = err.downcast_ref::<ScryptencError>() {
-                return match e {
-                    ScryptencError::InvalidLength
-                    | ScryptencError::InvalidMagicNumber
-                    | ScryptencError::InvalidChecksum
-                    | ScryptencError::InvalidMac(_) => ExitCode::InvalidFormat,
-                    ScryptencError::UnknownVersion(_) => ExitCode::UnknownVersion,
-                    ScryptencError::InvalidParams(_) => ExitCode::InvalidParams,
-                    ScryptencError::InvalidHeaderMac(_) => ExitCode::InvalidPassphrase,
-                };
-            }
-            if let Some(e) = err.downcast_ref::<params::Error>() {
-                return match e {
-                    params::Error::Memory => ExitCode::LackOfMemory,
-                    params::Error::CpuTime => ExitCode::LackOfCpuTime,
-                    params::Error::Resources => ExitCode::LackOfResources,
-                };
-            }
-            ExitCode::Failure
+            eprintln!("Error: {err:?}");
+            code
         }
     }
 }
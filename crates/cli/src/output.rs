@@ -3,16 +3,204 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use std::{
-    fs,
-    io::{self, Write},
+    fs::{self, File, OpenOptions},
+    io::{self, IsTerminal, Write},
     path::Path,
+    process, str,
+    time::SystemTime,
 };
 
-use anyhow::Context;
+use anyhow::{Context, bail};
+use rand::{RngCore, rngs::OsRng};
+
+use crate::cli::Overwrite;
 
 /// Writes the result to a file.
-pub fn write_to_file(path: &Path, data: &[u8]) -> anyhow::Result<()> {
-    fs::write(path, data).with_context(|| format!("could not write data to {}", path.display()))
+///
+/// If `overwrite` is [`Overwrite::Never`], this fails if the file already
+/// exists. If `private` is `true`, the file is made accessible only by the
+/// owner on Unix, even if it already exists.
+pub fn write_to_file(
+    path: &Path,
+    data: &[u8],
+    overwrite: Overwrite,
+    private: bool,
+) -> anyhow::Result<()> {
+    let mut options = OpenOptions::new();
+    match overwrite {
+        Overwrite::Always => options.write(true).create(true).truncate(true),
+        Overwrite::Never => options.write(true).create_new(true),
+    };
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| {
+            #[cfg(unix)]
+            if private {
+                use std::os::unix::fs::PermissionsExt;
+
+                file.set_permissions(fs::Permissions::from_mode(0o600))?;
+            }
+            file.write_all(data)
+        })
+        .with_context(|| format!("could not write data to {}", path.display()))
+}
+
+/// The metadata of a file which is carried over to the output file.
+#[derive(Clone, Debug)]
+pub struct Metadata {
+    permissions: fs::Permissions,
+    modified: SystemTime,
+}
+
+impl Metadata {
+    /// Reads the metadata of the file.
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let inner = || -> io::Result<Self> {
+            let metadata = fs::metadata(path)?;
+            Ok(Self {
+                permissions: metadata.permissions(),
+                modified: metadata.modified()?,
+            })
+        };
+        inner().with_context(|| format!("could not read the metadata of {}", path.display()))
+    }
+
+    /// Sets the permissions and the modification time of the file to this
+    /// metadata.
+    pub fn apply(&self, path: &Path) -> anyhow::Result<()> {
+        let inner = || -> io::Result<()> {
+            let file = OpenOptions::new().write(true).open(path)?;
+            file.set_modified(self.modified)?;
+            file.set_permissions(self.permissions.clone())
+        };
+        inner().with_context(|| format!("could not set the metadata of {}", path.display()))
+    }
+}
+
+/// Replaces the contents of the file with `data` atomically.
+///
+/// The data is written to a temporary file in the same directory, which is
+/// then renamed to `path`. The permissions of the file are preserved.
+pub fn replace_file(path: &Path, data: &[u8]) -> anyhow::Result<()> {
+    let inner = || -> io::Result<()> {
+        let permissions = fs::metadata(path)?.permissions();
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+        let tmp = path.with_file_name(format!(
+            ".{}.{}.tmp",
+            file_name.to_string_lossy(),
+            process::id()
+        ));
+        let result = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp)
+            .and_then(|mut file| {
+                file.write_all(data)?;
+                file.set_permissions(permissions)?;
+                file.sync_all()
+            })
+            .and_then(|()| fs::rename(&tmp, path));
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        result
+    };
+    inner().with_context(|| format!("could not write data to {}", path.display()))
+}
+
+/// Flushes the file to the storage device.
+///
+/// On Unix, the directory which contains the file is also flushed so that the
+/// directory entry is durable.
+pub fn sync(path: &Path) -> anyhow::Result<()> {
+    let inner = || -> io::Result<()> {
+        File::open(path)?.sync_all()?;
+        #[cfg(unix)]
+        {
+            let parent = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            };
+            File::open(parent)?.sync_all()?;
+        }
+        Ok(())
+    };
+    inner().with_context(|| format!("could not flush {}", path.display()))
+}
+
+/// Overwrites the file with random data and removes it.
+///
+/// This does not reliably erase the data on SSDs, copy-on-write filesystems and
+/// journaling filesystems, because the new data may not be written to the
+/// same physical location as the old data.
+pub fn shred(path: &Path) -> io::Result<()> {
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    let mut remaining = file.metadata()?.len();
+    let mut buf = vec![u8::default(); 64 * 1024];
+    while remaining > 0 {
+        let n = usize::try_from(remaining).map_or(buf.len(), |n| n.min(buf.len()));
+        OsRng.fill_bytes(&mut buf[..n]);
+        file.write_all(&buf[..n])?;
+        remaining -= n as u64;
+    }
+    file.sync_all()?;
+    file.set_len(0)?;
+    drop(file);
+    fs::remove_file(path)
+}
+
+/// Returns `true` if `path` and `other` refer to the same file.
+///
+/// Symbolic links are followed, and hard links to the same file are also
+/// detected. Returns `false` if `other` does not exist.
+pub fn is_same_file(path: &Path, other: &Path) -> anyhow::Result<bool> {
+    let other_metadata = match fs::metadata(other) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => {
+            return Err(err).with_context(|| format!("could not access {}", other.display()));
+        }
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata =
+            fs::metadata(path).with_context(|| format!("could not access {}", path.display()))?;
+        Ok((metadata.dev(), metadata.ino()) == (other_metadata.dev(), other_metadata.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = other_metadata;
+        let canonicalize = |p: &Path| {
+            fs::canonicalize(p).with_context(|| format!("could not access {}", p.display()))
+        };
+        Ok(canonicalize(path)? == canonicalize(other)?)
+    }
+}
+
+/// Returns `true` if `data` looks like binary data.
+///
+/// Data which contains a NUL byte or is not valid UTF-8 is considered binary.
+pub fn is_binary(data: &[u8]) -> bool {
+    data.contains(&b'\0') || str::from_utf8(data).is_err()
+}
+
+/// Ensures that standard output is not a terminal before writing binary data
+/// to it.
+pub fn ensure_stdout_is_not_terminal() -> anyhow::Result<()> {
+    if io::stdout().is_terminal() {
+        bail!("refusing to write binary data to a terminal, use --force-tty to override");
+    }
+    Ok(())
 }
 
 /// Writes the result to standard output.
@@ -21,3 +209,18 @@ pub fn write_to_stdout(data: &[u8]) -> anyhow::Result<()> {
         .write_all(data)
         .context("could not write data to standard output")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_data() {
+        assert!(!is_binary(b"Hello, world!\n"));
+        assert!(!is_binary("こんにちは".as_bytes()));
+        assert!(!is_binary(b""));
+        assert!(is_binary(b"Hello,\0world!\n"));
+        assert!(is_binary(b"\xff\xfe"));
+        assert!(is_binary(include_bytes!("../tests/data/data.txt.scrypt")));
+    }
+}
@@ -14,14 +14,23 @@ use scryptenc::scrypt;
 use sysinfo::System;
 use thiserror::Error;
 
-use crate::cli::{Byte, Rate, Time};
+use crate::{
+    calibration,
+    cli::{Byte, Rate, Time},
+};
 
 type U128Fraction = GenericFraction<u128>;
 
 const SECOND: Duration = Duration::from_secs(1);
 
 static SYSTEM: LazyLock<System> = LazyLock::new(System::new_all);
-static OPERATIONS_PER_SECOND: LazyLock<u64> = LazyLock::new(get_scrypt_performance);
+static OPERATIONS_PER_SECOND: LazyLock<u64> = LazyLock::new(|| {
+    let cpu = SYSTEM
+        .cpus()
+        .first()
+        .map_or_else(String::new, |cpu| cpu.brand().to_owned());
+    calibration::operations_per_second(&cpu, get_scrypt_performance)
+});
 
 /// The error type for this module.
 #[derive(Debug, Error)]
@@ -77,12 +86,7 @@ fn display_with_resources(
     let n = 1 << log_n;
     let mem_limit = byte_unit::Byte::from(get_memory_to_use(max_memory, max_memory_fraction))
         .get_appropriate_unit(UnitType::Binary);
-    let expected_secs = Duration::from_secs_f64(
-        (U128Fraction::from(4 * u128::from(n) * u128::from(r) * u128::from(p))
-            / U128Fraction::from(*OPERATIONS_PER_SECOND))
-        .to_f64()
-        .unwrap_or_else(|| Duration::MAX.as_secs_f64()),
-    );
+    let expected_secs = estimate_time(log_n, r, p);
     display(n, r, p);
     eprintln!(" ({mem_limit:#.1} available),");
     eprint!("    and will take approximately {expected_secs:.1?} (limit: {max_time:.1?}).");
@@ -101,8 +105,29 @@ pub fn displayln_with_resources(
     eprintln!();
 }
 
+/// Returns the estimated time to compute the derived key.
+pub fn estimate_time(log_n: u8, r: u32, p: u32) -> Duration {
+    let n: u64 = 1 << log_n;
+    Duration::from_secs_f64(
+        (U128Fraction::from(4 * u128::from(n) * u128::from(r) * u128::from(p))
+            / U128Fraction::from(*OPERATIONS_PER_SECOND))
+        .to_f64()
+        .unwrap_or_else(|| Duration::MAX.as_secs_f64()),
+    )
+}
+
+/// Returns the number of times Salsa20/8 cores can be executed per second.
+pub fn operations_per_second() -> u64 {
+    *OPERATIONS_PER_SECOND
+}
+
+/// Returns the amount of the available memory.
+pub fn available_memory() -> u64 {
+    SYSTEM.available_memory()
+}
+
 /// Returns available memory.
-fn get_memory_to_use(max_memory: Option<Byte>, max_memory_fraction: Rate) -> u64 {
+pub fn get_memory_to_use(max_memory: Option<Byte>, max_memory_fraction: Rate) -> u64 {
     let available_mem = SYSTEM.available_memory();
     let mut mem_limit = (U128Fraction::from(available_mem)
         * U128Fraction::from_fraction(*max_memory_fraction))
@@ -245,3 +270,14 @@ impl Params {
         }
     }
 }
+
+#[cfg(feature = "json")]
+impl From<scrypt::Params> for Params {
+    fn from(params: scrypt::Params) -> Self {
+        Self {
+            n: 1 << params.log_n(),
+            r: params.r(),
+            p: params.p(),
+        }
+    }
+}
@@ -4,16 +4,176 @@
 
 use std::{
     env,
-    fs::File,
-    io::{self, BufRead, BufReader},
-    path::Path,
+    f64::consts::LOG2_10,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
 };
 
-use anyhow::Context;
+use anyhow::{Context, anyhow, bail};
 use dialoguer::{Password, theme::ColorfulTheme};
+use rand::{Rng, distributions::Alphanumeric, rngs::OsRng, seq::SliceRandom};
+use scryptenc::hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
 
 use crate::utils::StringExt;
 
+/// The minimum score of `zxcvbn` which is not considered weak.
+const MIN_SCORE: u8 = 3;
+
+/// The wordlist used to generate a passphrase.
+///
+/// This consists of 2048 common English words, so each word adds 11 bits of
+/// entropy.
+const WORDLIST: &str = include_str!("wordlist.txt");
+
+/// The separator between words of a generated passphrase.
+const WORD_SEPARATOR: &str = "-";
+
+/// The default number of words of a generated passphrase.
+const DEFAULT_WORDS: u8 = 7;
+
+/// The error type for this module.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The passphrase is empty.
+    #[error("passphrase is empty")]
+    Empty,
+
+    /// The passphrase is weaker than the required entropy.
+    #[error(
+        "passphrase is too weak (estimated entropy: {entropy:.1} bits, required: {min_entropy} bits)"
+    )]
+    Weak { entropy: f64, min_entropy: u32 },
+}
+
+/// The source to read the passphrase from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Source {
+    /// /dev/tty.
+    Tty,
+
+    /// /dev/tty, without confirmation.
+    TtyOnce,
+
+    /// Standard input.
+    Stdin,
+
+    /// The environment variable.
+    Env(String),
+
+    /// The file.
+    File(PathBuf),
+
+    /// The file descriptor.
+    Fd(u32),
+
+    /// The item in the keyring.
+    Keyring(KeyringEntry),
+
+    /// The output of the command.
+    Command(String),
+}
+
+impl Source {
+    /// Reads the passphrase from this source.
+    ///
+    /// If `confirm` is `false`, the passphrase read from /dev/tty is not
+    /// confirmed.
+    pub fn read(&self, confirm: bool) -> anyhow::Result<String> {
+        match self {
+            Self::Tty if confirm => read_passphrase_from_tty(),
+            Self::Tty | Self::TtyOnce => read_passphrase_from_tty_once(),
+            Self::Stdin => read_passphrase_from_stdin(),
+            Self::Env(key) => read_passphrase_from_env(key),
+            Self::File(path) => read_passphrase_from_file(path),
+            Self::Fd(fd) => read_passphrase_from_fd(*fd),
+            Self::Keyring(entry) => read_passphrase_from_keyring(entry),
+            Self::Command(command) => read_passphrase_from_command(command),
+        }
+    }
+
+    /// Returns `true` if this source reads from standard input.
+    pub const fn is_stdin(&self) -> bool {
+        matches!(self, Self::Stdin | Self::Fd(0))
+    }
+
+    /// Returns `true` if this source reads from /dev/tty.
+    pub const fn is_tty(&self) -> bool {
+        matches!(self, Self::Tty | Self::TtyOnce)
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tty => write!(f, "tty"),
+            Self::TtyOnce => write!(f, "tty-once"),
+            Self::Stdin => write!(f, "stdin"),
+            Self::Env(key) => write!(f, "env:{key}"),
+            Self::File(path) => write!(f, "file:{}", path.display()),
+            Self::Fd(fd) => write!(f, "fd:{fd}"),
+            Self::Keyring(entry) => write!(f, "keyring:{entry}"),
+            Self::Command(command) => write!(f, "command:{command}"),
+        }
+    }
+}
+
+impl FromStr for Source {
+    type Err = anyhow::Error;
+
+    fn from_str(source: &str) -> anyhow::Result<Self> {
+        match source.split_once(':') {
+            None if source == "tty" => Ok(Self::Tty),
+            None if source == "tty-once" => Ok(Self::TtyOnce),
+            None if source == "stdin" => Ok(Self::Stdin),
+            Some(("env", key)) if !key.is_empty() => Ok(Self::Env(key.into())),
+            Some(("file", path)) if !path.is_empty() => Ok(Self::File(path.into())),
+            Some(("fd", fd)) => fd
+                .parse()
+                .map(Self::Fd)
+                .map_err(|err| anyhow!("file descriptor is not a valid number: {err}")),
+            Some(("keyring", entry)) => entry.parse().map(Self::Keyring),
+            Some(("command", command)) if !command.is_empty() => Ok(Self::Command(command.into())),
+            _ => Err(anyhow!(
+                "passphrase source is not one of tty, tty-once, stdin, env:VAR, file:PATH, fd:FD, \
+                 keyring:SERVICE/ACCOUNT or command:CMD"
+            )),
+        }
+    }
+}
+
+/// The item in the keyring which holds the passphrase.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyringEntry {
+    pub service: String,
+    pub account: String,
+}
+
+impl fmt::Display for KeyringEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.service, self.account)
+    }
+}
+
+impl FromStr for KeyringEntry {
+    type Err = anyhow::Error;
+
+    fn from_str(entry: &str) -> anyhow::Result<Self> {
+        match entry.split_once('/') {
+            Some((service, account)) if !service.is_empty() && !account.is_empty() => Ok(Self {
+                service: service.into(),
+                account: account.into(),
+            }),
+            _ => Err(anyhow!("keyring entry is not in the form SERVICE/ACCOUNT")),
+        }
+    }
+}
+
 /// Reads the passphrase from /dev/tty.
 pub fn read_passphrase_from_tty() -> anyhow::Result<String> {
     Password::with_theme(&ColorfulTheme::default())
@@ -60,3 +220,369 @@ pub fn read_passphrase_from_file(path: &Path) -> anyhow::Result<String> {
     buf.remove_newline();
     Ok(buf)
 }
+
+/// Reads the passphrase from the file descriptor.
+pub fn read_passphrase_from_fd(fd: u32) -> anyhow::Result<String> {
+    if !cfg!(unix) {
+        bail!("reading the passphrase from a file descriptor is not supported on this platform");
+    }
+
+    let file = File::open(format!("/dev/fd/{fd}"))
+        .with_context(|| format!("could not open file descriptor {fd}"))?;
+    let mut reader = BufReader::new(file);
+
+    let mut buf = String::new();
+    reader
+        .read_line(&mut buf)
+        .with_context(|| format!("could not read passphrase from file descriptor {fd}"))?;
+    buf.remove_newline();
+    Ok(buf)
+}
+
+/// Reads the passphrase from the keyring.
+///
+/// This uses the Secret Service API, so this is only supported if the Secret
+/// Service is running on the session bus.
+pub fn read_passphrase_from_keyring(entry: &KeyringEntry) -> anyhow::Result<String> {
+    #[cfg(feature = "keyring")]
+    {
+        let secret = crate::keyring::read(&entry.service, &entry.account)
+            .with_context(|| format!("could not read passphrase from keyring ({entry})"))?;
+        std::str::from_utf8(&secret)
+            .map(String::from)
+            .context("passphrase in the keyring is not valid UTF-8")
+    }
+    #[cfg(not(feature = "keyring"))]
+    bail!("reading the passphrase from the keyring ({entry}) is not supported by this build");
+}
+
+/// Reads the passphrase from the first line of the output of the command.
+///
+/// The command is run by the shell, and its standard input is not inherited.
+pub fn read_passphrase_from_command(command: &str) -> anyhow::Result<String> {
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.arg("/C");
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.arg("-c");
+        process
+    };
+    let output = process
+        .arg(command)
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("could not run {command}"))?;
+    if !output.status.success() {
+        bail!("{command} exited with {}", output.status);
+    }
+    let mut buf = String::from_utf8(output.stdout)
+        .with_context(|| format!("output of {command} is not valid UTF-8"))?;
+    buf.truncate(buf.find('\n').unwrap_or(buf.len()));
+    buf.remove_newline();
+    Ok(buf)
+}
+
+/// Reads the keyfile.
+///
+/// If `digest` is `true`, this returns the SHA-256 digest of the keyfile
+/// instead of its contents.
+fn read_keyfile(path: &Path, digest: bool) -> anyhow::Result<Vec<u8>> {
+    let keyfile = fs::read(path)
+        .with_context(|| format!("could not read keyfile from {}", path.display()))?;
+    if digest {
+        Ok(Sha256::digest(keyfile).to_vec())
+    } else {
+        Ok(keyfile)
+    }
+}
+
+/// The label which is prepended to the passphrase combined with the keyfile.
+const KEYFILE_LABEL: &[u8] = b"rscrypt keyfile passphrase";
+
+/// Combines the passphrase with the keyfile.
+///
+/// The result is the HMAC-SHA-256 of [`KEYFILE_LABEL`] followed by the
+/// passphrase, keyed with the SHA-256 digest of the keyfile. Unlike the
+/// concatenation of the two, this differs from the digest of the keyfile used
+/// by `--keyfile-digest` even if the passphrase is empty.
+fn combine_with_keyfile(passphrase: &str, keyfile: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(&Sha256::digest(keyfile))
+        .expect("HMAC-SHA-256 key size should be 256 bits");
+    mac.update(KEYFILE_LABEL);
+    mac.update(passphrase.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Returns the password used to derive the key from the passphrase and the
+/// keyfile.
+pub fn to_password(
+    passphrase: Option<String>,
+    keyfile: Option<&Path>,
+    keyfile_digest: bool,
+) -> anyhow::Result<Vec<u8>> {
+    match (passphrase, keyfile) {
+        (Some(passphrase), Some(keyfile)) => {
+            let keyfile = read_keyfile(keyfile, false)?;
+            Ok(combine_with_keyfile(&passphrase, &keyfile))
+        }
+        (Some(passphrase), None) => Ok(passphrase.into_bytes()),
+        (None, Some(keyfile)) => read_keyfile(keyfile, keyfile_digest),
+        (None, None) => bail!("neither passphrase nor keyfile was specified"),
+    }
+}
+
+/// The passphrase which is read when it is first needed.
+///
+/// This allows not asking for the passphrase if the derived keys cached by the
+/// agent are used instead.
+pub struct LazyPassword<F> {
+    read: Option<F>,
+    password: Vec<u8>,
+}
+
+impl<F: FnOnce() -> anyhow::Result<Vec<u8>>> LazyPassword<F> {
+    /// Creates a new `LazyPassword` which is read by `read`.
+    pub const fn new(read: F) -> Self {
+        Self {
+            read: Some(read),
+            password: Vec::new(),
+        }
+    }
+
+    /// Returns the passphrase, reading it if it has not been read yet.
+    ///
+    /// If reading the passphrase fails, the error should be treated as fatal,
+    /// since the passphrase is not read again.
+    pub fn get(&mut self) -> anyhow::Result<&[u8]> {
+        if let Some(read) = self.read.take() {
+            self.password = read()?;
+        }
+        Ok(&self.password)
+    }
+}
+
+/// Checks the strength of the passphrase.
+///
+/// This prints a warning if the passphrase is weak.
+pub fn check_strength(
+    passphrase: &str,
+    min_entropy: Option<u32>,
+    allow_weak: bool,
+) -> Result<(), Error> {
+    if allow_weak {
+        return Ok(());
+    }
+    if passphrase.is_empty() {
+        return Err(Error::Empty);
+    }
+
+    let estimate = zxcvbn::zxcvbn(passphrase, &[]);
+    let entropy = estimate.guesses_log10() * LOG2_10;
+    if let Some(min_entropy) = min_entropy {
+        if entropy < f64::from(min_entropy) {
+            return Err(Error::Weak {
+                entropy,
+                min_entropy,
+            });
+        }
+    }
+    if u8::from(estimate.score()) < MIN_SCORE {
+        eprintln!("Warning: passphrase is weak (estimated entropy: {entropy:.1} bits)");
+    }
+    Ok(())
+}
+
+/// Generates a random passphrase.
+///
+/// If `chars` is specified, this generates a passphrase which consists of
+/// random alphanumeric characters. Otherwise, this generates a passphrase
+/// which consists of words randomly chosen from the wordlist.
+pub fn generate(words: Option<u8>, chars: Option<u8>) -> String {
+    chars.map_or_else(
+        || generate_words(words.unwrap_or(DEFAULT_WORDS)),
+        generate_chars,
+    )
+}
+
+/// Generates a passphrase which consists of the specified number of words
+/// randomly chosen from the wordlist.
+fn generate_words(count: u8) -> String {
+    let words = WORDLIST.lines().collect::<Vec<_>>();
+    (0..count)
+        .map(|_| {
+            *words
+                .choose(&mut OsRng)
+                .expect("wordlist should not be empty")
+        })
+        .collect::<Vec<_>>()
+        .join(WORD_SEPARATOR)
+}
+
+/// Generates a passphrase which consists of the specified number of random
+/// alphanumeric characters.
+fn generate_chars(count: u8) -> String {
+    (0..count)
+        .map(|_| char::from(OsRng.sample(Alphanumeric)))
+        .collect()
+}
+
+/// Writes the generated passphrase to the file.
+///
+/// On Unix, the file is created with mode 0600.
+pub fn write_passphrase_to_file(path: &Path, passphrase: &str) -> anyhow::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("could not open {}", path.display()))?;
+    // The mode is not changed if the file already exists.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        file.set_permissions(fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("could not set permissions of {}", path.display()))?;
+    }
+    writeln!(file, "{passphrase}")
+        .with_context(|| format!("could not write passphrase to {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combine_passphrase_with_keyfile() {
+        let combined = combine_with_keyfile("passphrase", b"keyfile");
+        assert_eq!(combined.len(), 32);
+        assert_eq!(combine_with_keyfile("passphrase", b"keyfile"), combined);
+        assert_ne!(combine_with_keyfile("password", b"keyfile"), combined);
+        assert_ne!(combine_with_keyfile("passphrase", b"keyfile2"), combined);
+    }
+
+    #[test]
+    fn check_strength_of_empty_passphrase() {
+        assert!(matches!(check_strength("", None, false), Err(Error::Empty)));
+        assert!(check_strength("", None, true).is_ok());
+    }
+
+    #[test]
+    fn check_strength_with_min_entropy() {
+        assert!(check_strength("passphrase", None, false).is_ok());
+        assert!(matches!(
+            check_strength("passphrase", Some(40), false),
+            Err(Error::Weak {
+                min_entropy: 40,
+                ..
+            })
+        ));
+        assert!(check_strength("correct horse battery staple", Some(40), false).is_ok());
+    }
+
+    #[test]
+    fn from_str_source() {
+        assert_eq!(Source::from_str("tty").unwrap(), Source::Tty);
+        assert_eq!(Source::from_str("tty-once").unwrap(), Source::TtyOnce);
+        assert_eq!(Source::from_str("stdin").unwrap(), Source::Stdin);
+        assert_eq!(
+            Source::from_str("env:PASSPHRASE").unwrap(),
+            Source::Env(String::from("PASSPHRASE"))
+        );
+        assert_eq!(
+            Source::from_str("file:/path/to/passphrase.txt").unwrap(),
+            Source::File(PathBuf::from("/path/to/passphrase.txt"))
+        );
+        assert_eq!(Source::from_str("fd:3").unwrap(), Source::Fd(3));
+        assert_eq!(
+            Source::from_str("keyring:rscrypt/alice").unwrap(),
+            Source::Keyring(KeyringEntry {
+                service: String::from("rscrypt"),
+                account: String::from("alice")
+            })
+        );
+        assert_eq!(
+            Source::from_str("command:pass show rscrypt").unwrap(),
+            Source::Command(String::from("pass show rscrypt"))
+        );
+    }
+
+    #[test]
+    fn from_str_source_with_invalid_value() {
+        assert!(Source::from_str("").is_err());
+        assert!(Source::from_str("keyboard").is_err());
+        assert!(Source::from_str("env:").is_err());
+        assert!(Source::from_str("file:").is_err());
+        assert!(Source::from_str("keyring:rscrypt").is_err());
+        assert!(Source::from_str("keyring:/alice").is_err());
+        assert!(Source::from_str("keyring:rscrypt/").is_err());
+        assert!(Source::from_str("command:").is_err());
+        assert!(
+            Source::from_str("fd:three")
+                .unwrap_err()
+                .to_string()
+                .contains("file descriptor is not a valid number")
+        );
+    }
+
+    #[test]
+    fn display_source() {
+        for source in [
+            "tty",
+            "tty-once",
+            "stdin",
+            "env:VAR",
+            "file:passphrase.txt",
+            "fd:3",
+            "keyring:rscrypt/alice",
+            "command:pass show rscrypt",
+        ] {
+            assert_eq!(Source::from_str(source).unwrap().to_string(), source);
+        }
+    }
+
+    #[test]
+    fn wordlist() {
+        let words = WORDLIST.lines().collect::<Vec<_>>();
+        assert_eq!(words.len(), 2048);
+        assert!(words.is_sorted());
+        assert!(words.windows(2).all(|w| w[0] != w[1]));
+        assert!(
+            words
+                .iter()
+                .all(|w| !w.is_empty() && w.bytes().all(|b| b.is_ascii_lowercase()))
+        );
+    }
+
+    #[test]
+    fn generate_passphrase_from_words() {
+        let passphrase = generate_words(7);
+        let words = passphrase.split(WORD_SEPARATOR).collect::<Vec<_>>();
+        assert_eq!(words.len(), 7);
+        assert!(words.iter().all(|w| WORDLIST.lines().any(|l| l == *w)));
+    }
+
+    #[test]
+    fn generate_passphrase_from_chars() {
+        let passphrase = generate_chars(24);
+        assert_eq!(passphrase.len(), 24);
+        assert!(passphrase.bytes().all(|b| b.is_ascii_alphanumeric()));
+        assert_ne!(passphrase, generate_chars(24));
+    }
+
+    #[test]
+    fn combine_empty_passphrase_with_keyfile() {
+        assert_ne!(
+            combine_with_keyfile("", b"keyfile"),
+            Sha256::digest(b"keyfile").to_vec()
+        );
+    }
+}
@@ -0,0 +1,114 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    io::{self, IsTerminal},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use byte_unit::UnitType;
+
+use crate::cli::ProgressMode;
+
+/// The characters of the spinner.
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// The interval between updates of the progress indicator.
+const INTERVAL: Duration = Duration::from_millis(100);
+
+/// The width of the progress bar.
+const BAR_WIDTH: usize = 30;
+
+/// The escape sequence to clear the current line.
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+/// Progress indicator which is output to standard error.
+#[derive(Clone, Copy, Debug)]
+pub struct Progress {
+    enabled: bool,
+}
+
+impl Progress {
+    /// Creates a new `Progress`.
+    ///
+    /// If `mode` is [`ProgressMode::Auto`], the progress indicator is shown
+    /// only if standard error is a terminal.
+    pub fn new(mode: ProgressMode) -> Self {
+        let enabled = match mode {
+            ProgressMode::Auto => io::stderr().is_terminal(),
+            ProgressMode::Always => true,
+            ProgressMode::Never => false,
+        };
+        Self { enabled }
+    }
+
+    /// Runs `f` while showing a spinner with the elapsed time against
+    /// `estimate`.
+    pub fn spin<T>(self, message: &str, estimate: Duration, f: impl FnOnce() -> T) -> T {
+        if !self.enabled {
+            return f();
+        }
+
+        let (tx, rx) = mpsc::channel::<()>();
+        thread::scope(|s| {
+            s.spawn(move || {
+                let start = Instant::now();
+                for c in SPINNER.iter().cycle() {
+                    eprint!(
+                        "{CLEAR_LINE}{c} {message} {:.1?} (estimated: {estimate:.1?})",
+                        start.elapsed()
+                    );
+                    if rx.recv_timeout(INTERVAL) != Err(RecvTimeoutError::Timeout) {
+                        break;
+                    }
+                }
+                eprint!("{CLEAR_LINE}");
+            });
+            let result = f();
+            drop(tx);
+            result
+        })
+    }
+
+    /// Returns a callback which shows a progress bar of `total` bytes.
+    ///
+    /// The callback takes the number of bytes processed so far.
+    pub fn bar(self, message: &str, total: usize) -> impl FnMut(usize) {
+        let mut last_update = None::<Instant>;
+        move |processed| {
+            if !self.enabled {
+                return;
+            }
+            if processed >= total {
+                eprint!("{CLEAR_LINE}");
+                return;
+            }
+
+            let now = Instant::now();
+            if last_update.is_some_and(|last| now - last < INTERVAL) {
+                return;
+            }
+            last_update = Some(now);
+
+            #[allow(clippy::cast_possible_truncation)]
+            let filled = (BAR_WIDTH as u128 * processed as u128 / total as u128) as usize;
+            let format_bytes = |bytes: usize| {
+                format!(
+                    "{:#.1}",
+                    byte_unit::Byte::from(bytes).get_appropriate_unit(UnitType::Binary)
+                )
+            };
+            eprint!(
+                "{CLEAR_LINE}{message} [{}{}] {} / {} ({}%)",
+                "=".repeat(filled),
+                " ".repeat(BAR_WIDTH - filled),
+                format_bytes(processed),
+                format_bytes(total),
+                100 * processed as u128 / total as u128
+            );
+        }
+    }
+}
@@ -0,0 +1,164 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{fmt, fs, path::Path, str::FromStr};
+
+use anyhow::{Context, anyhow, bail};
+use bech32::{Bech32, Hrp};
+use jiff::Timestamp;
+use rand::rngs::OsRng;
+use scryptenc::x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::{cli::Overwrite, output};
+
+/// The human-readable part of the encoded public key.
+const RECIPIENT_HRP: Hrp = Hrp::parse_unchecked("rscrypt");
+
+/// The human-readable part of the encoded secret key.
+const IDENTITY_HRP: Hrp = Hrp::parse_unchecked("rscrypt-secret-key-");
+
+/// The public key of a recipient.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Recipient(PublicKey);
+
+impl Recipient {
+    /// Returns the X25519 public key.
+    pub const fn public_key(&self) -> &PublicKey {
+        &self.0
+    }
+}
+
+impl From<&StaticSecret> for Recipient {
+    fn from(identity: &StaticSecret) -> Self {
+        Self(identity.into())
+    }
+}
+
+impl fmt::Display for Recipient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        bech32::encode_lower_to_fmt::<Bech32, _>(f, RECIPIENT_HRP, self.0.as_bytes())
+            .map_err(|_| fmt::Error)
+    }
+}
+
+impl FromStr for Recipient {
+    type Err = anyhow::Error;
+
+    fn from_str(recipient: &str) -> anyhow::Result<Self> {
+        decode(recipient, RECIPIENT_HRP)
+            .map(|key| Self(key.into()))
+            .ok_or_else(|| anyhow!("recipient is not a public key starting with \"rscrypt1\""))
+    }
+}
+
+/// Decodes the 32-byte key with the human-readable part `hrp`.
+fn decode(s: &str, hrp: Hrp) -> Option<[u8; 32]> {
+    bech32::decode(s)
+        .ok()
+        .filter(|(h, _)| *h == hrp)
+        .and_then(|(_, key)| key.try_into().ok())
+}
+
+/// Generates a new identity.
+pub fn generate() -> StaticSecret {
+    StaticSecret::random_from_rng(OsRng)
+}
+
+/// Returns the identity file which contains `identity`.
+///
+/// The file consists of the comments with the creation time and the public
+/// key, followed by the encoded secret key.
+pub fn to_identity_file(identity: &StaticSecret) -> String {
+    let key = bech32::encode_upper::<Bech32>(IDENTITY_HRP, identity.as_bytes())
+        .expect("secret key should be encodable");
+    format!(
+        "# created: {}\n# public key: {}\n{key}\n",
+        Timestamp::now()
+            .round(jiff::Unit::Second)
+            .expect("timestamp should be roundable"),
+        Recipient::from(identity)
+    )
+}
+
+/// Writes the identity file to `path`.
+///
+/// This fails if the file already exists. On Unix, the file is created with
+/// mode 0600.
+pub fn write_identity_file(path: &Path, identity: &StaticSecret) -> anyhow::Result<()> {
+    output::write_to_file(
+        path,
+        to_identity_file(identity).as_bytes(),
+        Overwrite::Never,
+        true,
+    )
+}
+
+/// Reads the identities from the identity file.
+///
+/// Empty lines and lines starting with "#" are ignored.
+pub fn read_identity_file(path: &Path) -> anyhow::Result<Vec<StaticSecret>> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("could not read identities from {}", path.display()))?;
+    let identities =
+        data.lines()
+            .map(str::trim)
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(i, line)| {
+                decode(line, IDENTITY_HRP).map(StaticSecret::from).with_context(|| {
+                format!(
+                    "line {} of {} is not a secret key starting with \"RSCRYPT-SECRET-KEY-1\"",
+                    i + 1,
+                    path.display()
+                )
+            })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+    if identities.is_empty() {
+        bail!("{} does not contain any identity", path.display());
+    }
+    Ok(identities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recipient_round_trip() {
+        let recipient = Recipient::from(&StaticSecret::from([u8::MAX; 32]));
+        let encoded = recipient.to_string();
+        assert!(encoded.starts_with("rscrypt1"));
+        assert_eq!(encoded.parse::<Recipient>().unwrap(), recipient);
+        assert_eq!(
+            encoded.to_uppercase().parse::<Recipient>().unwrap(),
+            recipient
+        );
+    }
+
+    #[test]
+    fn invalid_recipient() {
+        let identity = StaticSecret::from([u8::MAX; 32]);
+        let encoded = to_identity_file(&identity);
+        let secret_key = encoded.lines().last().unwrap();
+        assert!(secret_key.parse::<Recipient>().is_err());
+        assert!("rscrypt1".parse::<Recipient>().is_err());
+        assert!("".parse::<Recipient>().is_err());
+    }
+
+    #[test]
+    fn identity_file() {
+        let identity = StaticSecret::from([u8::MAX; 32]);
+        let file = to_identity_file(&identity);
+        let lines = file.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("# created: "));
+        assert_eq!(
+            lines[1],
+            format!("# public key: {}", Recipient::from(&identity))
+        );
+        assert!(lines[2].starts_with("RSCRYPT-SECRET-KEY-1"));
+        assert_eq!(decode(lines[2], IDENTITY_HRP).unwrap(), identity.to_bytes());
+    }
+}
@@ -0,0 +1,166 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{io, path::Path, time::Duration};
+
+use anyhow::Context;
+use scryptenc::{Error as ScryptencError, envelope, pem_rfc7468};
+use serde::Serialize;
+
+use crate::{
+    audit, check,
+    params::{self, Params},
+};
+
+/// The operation performed.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    /// Encryption.
+    Encrypt,
+
+    /// Decryption.
+    Decrypt,
+}
+
+/// The result of encryption or decryption.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    operation: Operation,
+    params: Option<Params>,
+    input_size: usize,
+    output_size: usize,
+    estimated_time: f64,
+    actual_time: f64,
+    output: Option<String>,
+}
+
+impl Report {
+    /// Creates a new `Report`.
+    ///
+    /// `params` is [`None`] if no derived key was computed with scrypt.
+    /// `output` is [`None`] if the result was written to standard output.
+    pub fn new(
+        operation: Operation,
+        params: Option<Params>,
+        input_size: usize,
+        output_size: usize,
+        estimated_time: Duration,
+        actual_time: Duration,
+        output: Option<&Path>,
+    ) -> Self {
+        Self {
+            operation,
+            params,
+            input_size,
+            output_size,
+            estimated_time: estimated_time.as_secs_f64(),
+            actual_time: actual_time.as_secs_f64(),
+            output: output.map(|path| path.display().to_string()),
+        }
+    }
+
+    /// Prints the report to standard error.
+    ///
+    /// This is the same stream as [`ErrorReport`], and never mixes with the
+    /// result written to standard output.
+    pub fn print(&self) -> anyhow::Result<()> {
+        let report = serde_json::to_string(self).context("could not serialize as JSON")?;
+        eprintln!("{report}");
+        Ok(())
+    }
+}
+
+/// The error which caused the program to exit.
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    error: ErrorDetail,
+}
+
+/// The details of the error.
+#[derive(Debug, Serialize)]
+struct ErrorDetail {
+    kind: &'static str,
+    message: String,
+    detail: String,
+    code: u8,
+}
+
+/// Returns the kind of the error of the scrypt encrypted data format.
+const fn scryptenc_kind(err: ScryptencError) -> &'static str {
+    match err {
+        ScryptencError::InvalidLength => "invalid_length",
+        ScryptencError::InvalidMagicNumber => "invalid_magic_number",
+        ScryptencError::UnknownVersion(_) => "unknown_version",
+        ScryptencError::InvalidParams(_) => "invalid_params",
+        ScryptencError::InvalidChecksum => "invalid_checksum",
+        ScryptencError::InvalidHeaderMac(_) => "invalid_header_mac",
+        ScryptencError::InvalidMac(_) => "invalid_mac",
+    }
+}
+
+/// Returns the kind and the message of the error.
+fn classify(err: &anyhow::Error) -> (&'static str, String) {
+    if let Some(e) = err.downcast_ref::<io::Error>() {
+        return ("io", e.to_string());
+    }
+    if let Some(e) = err.downcast_ref::<ScryptencError>() {
+        return (scryptenc_kind(*e), e.to_string());
+    }
+    if let Some(e) = err.downcast_ref::<envelope::Error>() {
+        let kind = match e {
+            envelope::Error::InvalidLength => "invalid_length",
+            envelope::Error::InvalidMagicNumber => "invalid_magic_number",
+            envelope::Error::UnknownVersion(_) => "unknown_version",
+            envelope::Error::InvalidSlotCount(_) => "invalid_slot_count",
+            envelope::Error::InvalidSlotIndex(_) => "invalid_slot_index",
+            envelope::Error::NoMatchingSlot => "no_matching_slot",
+            envelope::Error::InvalidRecipient => "invalid_recipient",
+            envelope::Error::NoMatchingIdentity => "no_matching_identity",
+            envelope::Error::Slot(e) | envelope::Error::Body(e) => scryptenc_kind(*e),
+        };
+        return (kind, e.to_string());
+    }
+    if let Some(e) = err.downcast_ref::<pem_rfc7468::Error>() {
+        return ("invalid_armor", e.to_string());
+    }
+    if let Some(e) = err.downcast_ref::<params::Error>() {
+        let kind = match e {
+            params::Error::Memory => "lack_of_memory",
+            params::Error::CpuTime => "lack_of_cpu_time",
+            params::Error::Resources => "lack_of_resources",
+        };
+        return (kind, e.to_string());
+    }
+    if let Some(e) = err.downcast_ref::<audit::Error>() {
+        return ("policy_violation", e.to_string());
+    }
+    if let Some(e) = err.downcast_ref::<check::Error>() {
+        return ("check_failed", e.to_string());
+    }
+    ("other", err.root_cause().to_string())
+}
+
+impl ErrorReport {
+    /// Creates a new `ErrorReport` from the error and the exit code.
+    pub fn new(err: &anyhow::Error, code: u8) -> Self {
+        let (kind, message) = classify(err);
+        Self {
+            error: ErrorDetail {
+                kind,
+                message,
+                detail: format!("{err:#}"),
+                code,
+            },
+        }
+    }
+
+    /// Prints the report to standard error.
+    pub fn print(&self) {
+        match serde_json::to_string(self) {
+            Ok(report) => eprintln!("{report}"),
+            Err(err) => eprintln!("Error: could not serialize as JSON: {err}"),
+        }
+    }
+}
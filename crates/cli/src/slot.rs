@@ -0,0 +1,63 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::time::Duration;
+
+use crate::{info, params};
+
+/// Returns the amount of memory required to compute the derived key.
+fn memory(params: &scryptenc::Params) -> u64 {
+    128 * params.n() * u64::from(params.r())
+}
+
+/// Returns the estimated time to compute the derived key.
+fn estimated_time(params: &scryptenc::Params) -> Duration {
+    params::estimate_time(params.log_n(), params.r(), params.p())
+}
+
+/// Returns the estimated time to try all slots of the envelope.
+pub fn total_estimated_time(slots: &[scryptenc::Params]) -> Duration {
+    slots.iter().map(estimated_time).sum()
+}
+
+/// Prints the slots of the envelope as a table.
+pub fn display(slots: &[scryptenc::Params]) {
+    println!(
+        "{:>4} {:>8} {:>3} {:>3} {:>12} {:>12}",
+        "slot", "N", "r", "p", "memory", "time"
+    );
+    for (index, params) in slots.iter().enumerate() {
+        println!(
+            "{:>4} {:>8} {:>3} {:>3} {:>12} {:>12}",
+            index,
+            params.n(),
+            params.r(),
+            params.p(),
+            info::format_bytes(memory(params)),
+            format!("{:.1?}", estimated_time(params))
+        );
+    }
+}
+
+/// The information about a slot of the envelope.
+#[cfg(feature = "json")]
+#[derive(Debug, serde::Serialize)]
+pub struct Report {
+    slot: usize,
+    #[serde(flatten)]
+    params: params::Params,
+    estimated_time: f64,
+}
+
+#[cfg(feature = "json")]
+impl Report {
+    /// Creates a new `Report`.
+    pub fn new(slot: usize, params: &scryptenc::Params) -> Self {
+        Self {
+            slot,
+            params: params::Params::new(*params),
+            estimated_time: estimated_time(params).as_secs_f64(),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+#![cfg(unix)]
+
+mod utils;
+
+use std::{
+    env, fs,
+    os::unix::fs::{MetadataExt, PermissionsExt},
+    path::PathBuf,
+    process::{self, Child, Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use predicates::prelude::predicate;
+
+/// A running agent.
+struct Agent {
+    process: Child,
+    socket: PathBuf,
+}
+
+impl Agent {
+    /// Starts the agent with `ttl` on a temporary socket.
+    fn start(name: &str, ttl: &str) -> Self {
+        let dir = env::temp_dir().join(format!("rscrypt-test-{}-{name}", process::id()));
+        let socket = dir.join("agent.sock");
+        let process = Command::new(assert_cmd::cargo::cargo_bin("rscrypt"))
+            .arg("agent")
+            .arg("--socket")
+            .arg(&socket)
+            .arg("--ttl")
+            .arg(ttl)
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        for _ in 0..100 {
+            if socket.exists() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        Self { process, socket }
+    }
+
+    /// Decrypts the test data without reading the passphrase.
+    fn decrypt(&self) -> assert_cmd::assert::Assert {
+        utils::command::command()
+            .arg("dec")
+            .arg("--passphrase-from-env")
+            .arg("RSCRYPT_TEST_UNSET_PASSPHRASE")
+            .arg("data/data.txt.scrypt")
+            .env("RSCRYPT_AGENT_SOCK", &self.socket)
+            .assert()
+    }
+}
+
+impl Drop for Agent {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        if let Some(dir) = self.socket.parent() {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+#[test]
+fn decrypt_with_cached_derived_key() {
+    let agent = Agent::start("agent-cache", "1m");
+    agent
+        .decrypt()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "could not read passphrase from environment variable",
+        ));
+
+    utils::command::command()
+        .arg("dec")
+        .arg("--no-agent")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_AGENT_SOCK", &agent.socket)
+        .write_stdin("passphrase")
+        .assert()
+        .success();
+    agent.decrypt().failure().code(1);
+
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_AGENT_SOCK", &agent.socket)
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    agent
+        .decrypt()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    utils::command::command()
+        .arg("dec")
+        .arg("--no-agent")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_UNSET_PASSPHRASE")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_AGENT_SOCK", &agent.socket)
+        .assert()
+        .failure()
+        .code(1);
+
+    utils::command::command()
+        .arg("agent")
+        .arg("--clear")
+        .arg("--socket")
+        .arg(&agent.socket)
+        .assert()
+        .success();
+    agent.decrypt().failure().code(1);
+}
+
+#[test]
+fn check_and_smudge_with_cached_derived_key() {
+    let agent = Agent::start("agent-other-commands", "1m");
+    let check = || {
+        utils::command::command()
+            .arg("check")
+            .arg("--passphrase-from-env")
+            .arg("RSCRYPT_TEST_UNSET_PASSPHRASE")
+            .arg("data/data.txt.scrypt")
+            .env("RSCRYPT_AGENT_SOCK", &agent.socket)
+            .assert()
+    };
+    let smudge = || {
+        utils::command::command()
+            .arg("git-filter")
+            .arg("--passphrase-from-env")
+            .arg("RSCRYPT_TEST_UNSET_PASSPHRASE")
+            .arg("smudge")
+            .env("RSCRYPT_AGENT_SOCK", &agent.socket)
+            .write_stdin(fs::read("tests/data/data.txt.scrypt").unwrap())
+            .assert()
+    };
+    check().failure().code(1);
+    smudge().failure().code(1);
+
+    utils::command::command()
+        .arg("check")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_AGENT_SOCK", &agent.socket)
+        .write_stdin("passphrase")
+        .assert()
+        .success();
+    check()
+        .success()
+        .stdout(predicate::eq("data/data.txt.scrypt: OK\n"));
+    smudge().success().stdout(predicate::eq("Hello, world!\n"));
+    utils::command::command()
+        .arg("check")
+        .arg("--no-agent")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_UNSET_PASSPHRASE")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_AGENT_SOCK", &agent.socket)
+        .assert()
+        .failure()
+        .code(1);
+}
+
+#[test]
+fn forget_expired_derived_key() {
+    let agent = Agent::start("agent-expire", "1s");
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_AGENT_SOCK", &agent.socket)
+        .write_stdin("passphrase")
+        .assert()
+        .success();
+    thread::sleep(Duration::from_secs(2));
+    agent.decrypt().failure().code(1);
+}
+
+#[test]
+fn clear_if_agent_is_not_running() {
+    utils::command::command()
+        .arg("agent")
+        .arg("--clear")
+        .arg("--socket")
+        .arg(env::temp_dir().join(format!(
+            "rscrypt-test-{}-agent-none/agent.sock",
+            process::id()
+        )))
+        .assert()
+        .failure()
+        .code(66)
+        .stderr(predicate::str::contains("could not access"));
+}
+
+#[test]
+fn refuse_default_dir_accessible_by_others() {
+    let tmp = env::temp_dir().join(format!("rscrypt-test-{}-agent-tmp", process::id()));
+    fs::create_dir_all(&tmp).unwrap();
+    let uid = fs::metadata(&tmp).unwrap().uid();
+    let dir = tmp.join(format!("rscrypt-{uid}"));
+    fs::create_dir(&dir).unwrap();
+    fs::set_permissions(&dir, fs::Permissions::from_mode(0o777)).unwrap();
+    utils::command::command()
+        .arg("agent")
+        .env_remove("RSCRYPT_AGENT_SOCK")
+        .env_remove("XDG_RUNTIME_DIR")
+        .env("TMPDIR", &tmp)
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("is accessible by other users"));
+    assert!(!dir.join("agent.sock").exists());
+    fs::remove_dir_all(tmp).unwrap();
+}
@@ -0,0 +1,187 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+mod utils;
+
+use std::fs;
+
+use predicates::prelude::predicate;
+
+use utils::temp::{self, TempPath};
+
+/// Creates the directory which contains the encrypted files in itself and in
+/// its subdirectory.
+fn create_data_dir(name: &str) -> TempPath {
+    let dir = temp::create_dir(name);
+    fs::create_dir(dir.join("sub")).unwrap();
+    fs::copy("tests/data/data.txt.scrypt", dir.join("data.txt.scrypt")).unwrap();
+    fs::copy(
+        "tests/data/data.txt.scrypt.asc",
+        dir.join("sub/data.txt.scrypt.asc"),
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn audit_compliant_file() {
+    utils::command::command()
+        .arg("audit")
+        .arg("--min-log-n")
+        .arg("10")
+        .arg("--min-memory")
+        .arg("1MiB")
+        .arg("data/data.txt.scrypt")
+        .arg("data/data.txt.scrypt.asc")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn audit_non_compliant_file() {
+    utils::command::command()
+        .arg("audit")
+        .arg("--min-log-n")
+        .arg("17")
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::eq(
+            "data/data.txt.scrypt: non-compliant (N = 1024; r = 8; p = 1;): N = 2^10 is less \
+             than 2^17\n",
+        ))
+        .stderr(predicate::str::contains(
+            "found 1 non-compliant files and 0 files which could not be checked",
+        ));
+}
+
+#[test]
+fn audit_with_min_memory() {
+    utils::command::command()
+        .arg("audit")
+        .arg("--min-memory")
+        .arg("64MiB")
+        .arg("data/data.txt.scrypt.asc")
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "1 MiB of memory is less than 64 MiB",
+        ));
+}
+
+#[test]
+fn audit_invalid_file() {
+    utils::command::command()
+        .arg("audit")
+        .arg("data/data.txt")
+        .arg("non_existent.txt.scrypt")
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "data/data.txt: error: data is not a valid scrypt encrypted file",
+        ))
+        .stdout(predicate::str::contains(
+            "non_existent.txt.scrypt: error: could not read data from non_existent.txt.scrypt",
+        ))
+        .stderr(predicate::str::contains(
+            "found 0 non-compliant files and 2 files which could not be checked",
+        ));
+}
+
+#[test]
+fn audit_directory_without_recursive() {
+    let dir = create_data_dir("audit-non-recursive");
+    utils::command::command()
+        .arg("audit")
+        .arg(&dir)
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "is a directory (use --recursive to scan it)",
+        ));
+}
+
+#[test]
+fn audit_directory_recursively() {
+    let dir = create_data_dir("audit-recursive");
+    utils::command::command()
+        .arg("audit")
+        .arg("-r")
+        .arg("--min-log-n")
+        .arg("10")
+        .arg(&dir)
+        .assert()
+        .success();
+    utils::command::command()
+        .arg("audit")
+        .arg("-r")
+        .arg("--min-log-n")
+        .arg("17")
+        .arg(&dir)
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains(format!(
+            "{}: non-compliant",
+            dir.join("data.txt.scrypt").display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{}: non-compliant",
+            dir.join("sub").join("data.txt.scrypt.asc").display()
+        )));
+}
+
+#[test]
+fn audit_with_invalid_min_log_n() {
+    utils::command::command()
+        .arg("audit")
+        .arg("--min-log-n")
+        .arg("64")
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value '64' for '--min-log-n <VALUE>'",
+        ));
+}
+
+#[test]
+fn audit_without_paths() {
+    utils::command::command()
+        .arg("audit")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn audit_as_json() {
+    utils::command::command()
+        .arg("audit")
+        .arg("-j")
+        .arg("--min-log-n")
+        .arg("17")
+        .arg("data/data.txt.scrypt")
+        .arg("data/data.txt")
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::eq(concat!(
+            r#"{"file":"data/data.txt.scrypt","status":"non_compliant","N":1024,"r":8,"p":1,"violations":["N = 2^10 is less than 2^17"]}"#,
+            '\n',
+            r#"{"file":"data/data.txt","status":"invalid","error":"data is not a valid scrypt encrypted file: encrypted data is shorter than 128 bytes"}"#,
+            '\n'
+        )))
+        .stderr(predicate::str::contains(r#""kind":"policy_violation""#));
+}
@@ -0,0 +1,96 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+mod utils;
+
+use predicates::prelude::predicate;
+
+#[test]
+fn bench() {
+    utils::command::command()
+        .arg("bench")
+        .arg("-M")
+        .arg("1MiB")
+        .arg("--log-n")
+        .arg("10,11")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Salsa20/8 cores per second: "))
+        .stdout(predicate::str::contains("Parameters selected: N = "))
+        .stdout(predicate::str::contains(
+            "for at most 1 MiB of memory and 5.0s",
+        ))
+        .stdout(predicate::str::is_match(r"\n +10 +8 +1 +1 MiB +[0-9.]+[mµn]?s\n").unwrap())
+        .stdout(predicate::str::is_match(r"\n +11 +8 +1 +2 MiB +skipped\n").unwrap());
+}
+
+#[cfg(not(feature = "json"))]
+#[test]
+fn bench_command_without_default_feature() {
+    utils::command::command()
+        .arg("bench")
+        .arg("-j")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("unexpected argument '-j' found"));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn bench_as_json() {
+    utils::command::command()
+        .arg("bench")
+        .arg("-M")
+        .arg("1MiB")
+        .arg("-t")
+        .arg("10s")
+        .arg("--log-n")
+        .arg("10,11")
+        .arg("-j")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(r#"{"operations_per_second":"#))
+        .stdout(predicate::str::contains(
+            r#""memory_limit":1048576,"time_limit":10.0,"selected":{"N":"#,
+        ))
+        .stdout(
+            predicate::str::is_match(
+                r#""measurements":\[\{"N":1024,"r":8,"p":1,"memory":1048576,"time":[0-9.e-]+\},\{"N":2048,"r":8,"p":1,"memory":2097152,"time":null\}\]\}\n$"#,
+            )
+            .unwrap(),
+        );
+}
+
+#[test]
+fn bench_with_multiple_parameters() {
+    utils::command::command()
+        .arg("bench")
+        .arg("-M")
+        .arg("1MiB")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("1,2")
+        .arg("-p")
+        .arg("1,2")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"\n +10 +1 +1 ").unwrap())
+        .stdout(predicate::str::is_match(r"\n +10 +1 +2 ").unwrap())
+        .stdout(predicate::str::is_match(r"\n +10 +2 +1 ").unwrap())
+        .stdout(predicate::str::is_match(r"\n +10 +2 +2 ").unwrap());
+}
+
+#[test]
+fn validate_log_n_is_out_of_range() {
+    utils::command::command()
+        .arg("bench")
+        .arg("--log-n")
+        .arg("10,9")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("9 is not in 10..=40"));
+}
@@ -0,0 +1,136 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+mod utils;
+
+use std::{env, fs, process};
+
+use predicates::prelude::predicate;
+
+#[test]
+fn check() {
+    utils::command::command()
+        .arg("check")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("data/data.txt.scrypt")
+        .arg("data/data.txt.scrypt.asc")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "data/data.txt.scrypt: OK\ndata/data.txt.scrypt.asc: OK\n",
+        ));
+}
+
+#[test]
+fn check_with_wrong_passphrase() {
+    utils::command::command()
+        .arg("check")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_TEST_PASSPHRASE", "password")
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::eq("data/data.txt.scrypt: wrong passphrase\n"))
+        .stderr(predicate::str::contains("1 of 1 files failed the check"));
+}
+
+#[test]
+fn check_corrupted_file() {
+    let path = env::temp_dir().join(format!("rscrypt-test-{}-check.scrypt", process::id()));
+    let mut data = fs::read("tests/data/data.txt.scrypt").unwrap();
+    data[scryptenc::HEADER_SIZE] ^= 1;
+    fs::write(&path, data).unwrap();
+    utils::command::command()
+        .arg("check")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains(format!(
+            "{}: corrupted (invalid MAC: MAC tag mismatch)",
+            path.display()
+        )))
+        .stdout(predicate::str::contains("data/data.txt.scrypt: OK\n"))
+        .stderr(predicate::str::contains("1 of 2 files failed the check"));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn check_invalid_file() {
+    utils::command::command()
+        .arg("check")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("data/data.txt")
+        .arg("non_existent.txt.scrypt")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "data/data.txt: not a scrypt file (encrypted data is shorter than 128 bytes)",
+        ))
+        .stdout(predicate::str::contains(
+            "non_existent.txt.scrypt: error (could not read data from non_existent.txt.scrypt",
+        ))
+        .stderr(predicate::str::contains("2 of 2 files failed the check"));
+}
+
+#[test]
+fn check_with_passphrase_from_stdin_and_stdin_input() {
+    utils::command::command()
+        .arg("check")
+        .arg("--passphrase-from-stdin")
+        .arg("-")
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "cannot read both passphrase and input data from standard input",
+        ));
+}
+
+#[test]
+fn check_without_files() {
+    utils::command::command()
+        .arg("check")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn check_as_json() {
+    utils::command::command()
+        .arg("check")
+        .arg("-j")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("data/data.txt.scrypt")
+        .arg("data/data.txt")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::eq(concat!(
+            r#"{"file":"data/data.txt.scrypt","status":"ok"}"#,
+            '\n',
+            r#"{"file":"data/data.txt","status":"not_scrypt","error":"encrypted data is shorter than 128 bytes"}"#,
+            '\n'
+        )))
+        .stderr(predicate::str::contains(r#""kind":"check_failed""#));
+}
@@ -0,0 +1,250 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+mod utils;
+
+use std::{env, fs, path::PathBuf, process};
+
+use predicates::prelude::predicate;
+
+fn write_config(name: &str, contents: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("rscrypt-test-{}-{name}.toml", process::id()));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn show_config() {
+    let config = write_config(
+        "show",
+        r#"
+[encrypt]
+max-time = "10s"
+log-n = 10
+r = 8
+p = 1
+armor = true
+
+[decrypt]
+max-memory = "1 GiB"
+"#,
+    );
+    utils::command::command()
+        .arg("config")
+        .arg("show")
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(format!(
+            "Config file: {}\n",
+            config.display()
+        )))
+        .stdout(predicate::str::contains(
+            "encrypt.max-memory-fraction = 1/8 (default)\n",
+        ))
+        .stdout(predicate::str::contains(format!(
+            "encrypt.max-time = 10s (config file {})\n",
+            config.display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "encrypt.log-n = 10 (config file {})\n",
+            config.display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "encrypt.armor = true (config file {})\n",
+            config.display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "decrypt.max-memory = 1 GiB (config file {})\n",
+            config.display()
+        )))
+        .stdout(predicate::str::contains(
+            "decrypt.max-time = 300s (default)\n",
+        ));
+    fs::remove_file(config).unwrap();
+}
+
+#[test]
+fn show_config_from_env() {
+    let config = write_config("show-from-env", "[decrypt]\npassphrase = \"stdin\"\n");
+    utils::command::command()
+        .arg("config")
+        .arg("show")
+        .env("RSCRYPT_CONFIG", &config)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "decrypt.passphrase = stdin (config file {})\n",
+            config.display()
+        )));
+    fs::remove_file(config).unwrap();
+}
+
+#[test]
+fn show_config_without_config_file() {
+    let config_dir = env::temp_dir().join(format!("rscrypt-test-{}-no-config", process::id()));
+    utils::command::command()
+        .arg("config")
+        .arg("show")
+        .env_remove("RSCRYPT_CONFIG")
+        .env("XDG_CONFIG_HOME", &config_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(format!(
+            "Config file: {} (not found)\n",
+            config_dir.join("rscrypt/config.toml").display()
+        )))
+        .stdout(predicate::str::contains(
+            "encrypt.armor = false (default)\n",
+        ));
+}
+
+#[test]
+fn show_config_if_config_file_does_not_exist() {
+    utils::command::command()
+        .arg("config")
+        .arg("show")
+        .arg("--config")
+        .arg("non_existent.toml")
+        .assert()
+        .failure()
+        .code(66)
+        .stderr(predicate::str::contains("could not read non_existent.toml"));
+}
+
+#[test]
+fn show_config_if_config_file_is_invalid() {
+    let config = write_config("invalid", "[encrypt]\nmax-tme = \"10s\"\n");
+    utils::command::command()
+        .arg("config")
+        .arg("show")
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("could not load config file"))
+        .stderr(predicate::str::contains("unknown key \"encrypt.max-tme\""));
+    fs::remove_file(config).unwrap();
+}
+
+#[test]
+fn show_config_with_env() {
+    let config = write_config("show-with-env", "[encrypt]\nmax-time = \"10s\"\n");
+    utils::command::command()
+        .arg("config")
+        .arg("show")
+        .arg("--config")
+        .arg(&config)
+        .env("RSCRYPT_MAX_TIME", "20s")
+        .env("RSCRYPT_PASSPHRASE_SOURCE", "stdin")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "encrypt.max-time = 20s (environment variable)\n",
+        ))
+        .stdout(predicate::str::contains(
+            "decrypt.max-time = 20s (environment variable)\n",
+        ))
+        .stdout(predicate::str::contains(
+            "encrypt.passphrase = stdin (environment variable)\n",
+        ));
+    fs::remove_file(config).unwrap();
+}
+
+#[test]
+fn encrypt_with_config() {
+    let config = write_config(
+        "encrypt",
+        r#"
+[encrypt]
+log-n = 10
+r = 8
+p = 1
+passphrase = "env:RSCRYPT_TEST_PASSPHRASE"
+armor = true
+"#,
+    );
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("--config")
+        .arg(&config)
+        .arg("data/data.txt")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(
+        output
+            .stdout
+            .starts_with(b"-----BEGIN SCRYPT ENCRYPTED DATA-----\n")
+    );
+    utils::command::command()
+        .arg("info")
+        .arg("-")
+        .write_stdin(output.stdout.clone())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("N = 1024; r = 8; p = 1;"));
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("-")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .write_stdin(output.stdout)
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("--config")
+        .arg(&config)
+        .arg("--no-armor")
+        .arg("data/data.txt")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.starts_with(b"scrypt"));
+    fs::remove_file(config).unwrap();
+}
+
+#[test]
+fn decrypt_with_overwrite_policy() {
+    let config = write_config(
+        "overwrite",
+        "[decrypt]\npassphrase = \"env:RSCRYPT_TEST_PASSPHRASE\"\noverwrite = \"never\"\n",
+    );
+    let output = env::temp_dir().join(format!("rscrypt-test-{}-overwrite.txt", process::id()));
+    fs::write(&output, "existing data").unwrap();
+    utils::command::command()
+        .arg("dec")
+        .arg("--config")
+        .arg(&config)
+        .arg("data/data.txt.scrypt")
+        .arg(&output)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .failure()
+        .code(73)
+        .stderr(predicate::str::contains("could not write data to"));
+    assert_eq!(fs::read_to_string(&output).unwrap(), "existing data");
+    utils::command::command()
+        .arg("dec")
+        .arg("--config")
+        .arg(&config)
+        .arg("--overwrite")
+        .arg("always")
+        .arg("data/data.txt.scrypt")
+        .arg(&output)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&output).unwrap(), "Hello, world!\n");
+    fs::remove_file(output).unwrap();
+    fs::remove_file(config).unwrap();
+}
@@ -4,7 +4,9 @@
 
 mod utils;
 
-use predicates::prelude::predicate;
+use std::{env, fs, process};
+
+use predicates::prelude::{PredicateBooleanExt, predicate};
 
 #[test]
 fn basic_decrypt() {
@@ -18,6 +20,33 @@ fn basic_decrypt() {
         .stdout(predicate::eq("Hello, world!\n"));
 }
 
+#[test]
+fn decrypt_armored_data() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt.asc")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
+
+#[test]
+fn decrypt_armored_with_preamble() {
+    let armored = fs::read_to_string("tests/data/data.txt.scrypt.asc").unwrap();
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_PASSPHRASE")
+        .arg("-")
+        .env("RSCRYPT_PASSPHRASE", "passphrase")
+        .write_stdin(format!("Attached below.\n\n{armored}"))
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
+
 #[test]
 fn infer_subcommand_name_for_decrypt_command() {
     utils::command::command()
@@ -213,6 +242,113 @@ fn decrypt_with_max_time() {
         .stderr(predicate::str::contains("limit: 3600.0s"));
 }
 
+#[test]
+fn decrypt_with_progress() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--progress")
+        .arg("always")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"))
+        .stderr(predicate::str::contains("Deriving key"));
+    utils::command::command()
+        .arg("dec")
+        .arg("--progress")
+        .arg("never")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"))
+        .stderr(predicate::str::is_empty());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn decrypt_as_json() {
+    utils::command::command()
+        .arg("dec")
+        .arg("-j")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"))
+        .stderr(
+            predicate::str::is_match(
+                r#"^\{"operation":"decrypt","params":\{"N":1024,"r":8,"p":1\},"input_size":142,"output_size":14,"estimated_time":[0-9.e-]+,"actual_time":[0-9.e-]+,"output":null\}\n$"#,
+            )
+            .unwrap(),
+        );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn decrypt_with_invalid_passphrase_as_json() {
+    utils::command::command()
+        .arg("--json")
+        .arg("dec")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .write_stdin("password")
+        .assert()
+        .failure()
+        .code(11)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::eq(concat!(
+            r#"{"error":{"kind":"invalid_header_mac","message":"invalid header MAC","detail":"passphrase is incorrect: invalid header MAC: MAC tag mismatch","code":11}}"#,
+            '\n'
+        )));
+}
+
+#[test]
+fn decrypt_with_env() {
+    utils::command::command()
+        .arg("dec")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_MAX_TIME", "3600s")
+        .env("RSCRYPT_PASSPHRASE_SOURCE", "stdin")
+        .env("RSCRYPT_VERBOSE", "1")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"))
+        .stderr(predicate::str::contains("limit: 3600.0s"));
+}
+
+#[test]
+fn decrypt_with_env_overridden_by_option() {
+    utils::command::command()
+        .arg("dec")
+        .arg("-t")
+        .arg("1800s")
+        .arg("--passphrase-from-stdin")
+        .arg("-v")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_MAX_TIME", "3600s")
+        .env("RSCRYPT_PASSPHRASE_SOURCE", "env:RSCRYPT_TEST_PASSPHRASE")
+        .env("RSCRYPT_VERBOSE", "false")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("limit: 1800.0s"));
+    utils::command::command()
+        .arg("dec")
+        .arg("-f")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_MAX_TIME", "3600s")
+        .write_stdin("passphrase")
+        .assert()
+        .success();
+}
+
 #[test]
 fn invalid_time_for_decrypt_command() {
     utils::command::command()
@@ -331,3 +467,328 @@ fn decrypt_verbose() {
             "Parameters used: N = 1024; r = 8; p = 1;",
         ));
 }
+
+#[test]
+fn decrypt_if_armored_data_is_invalid() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-stdin")
+        .arg("-")
+        .write_stdin("-----BEGIN SCRYPT ENCRYPTED DATA-----\n!!!!\n")
+        .assert()
+        .failure()
+        .code(7)
+        .stderr(predicate::str::contains(
+            "could not decode the ASCII-armored data",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn decrypt_with_passphrase_from_fd() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-fd")
+        .arg("0")
+        .arg("data/data.txt.scrypt")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn decrypt_if_fd_is_invalid() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-fd")
+        .arg("1000")
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "could not open file descriptor 1000",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn decrypt_with_passphrase_from_command() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-command")
+        .arg("printf 'passphrase\\nsecond line\\n'")
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-command")
+        .arg("exit 3")
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "exit 3 exited with exit status: 3",
+        ));
+}
+
+#[test]
+fn decrypt_with_passphrase_from_keyring_with_invalid_entry() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-keyring")
+        .arg("rscrypt")
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "keyring entry is not in the form SERVICE/ACCOUNT",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn decrypt_to_private_file() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let output = std::env::temp_dir().join(format!(
+        "rscrypt-test-{}-decrypt-private.txt",
+        std::process::id()
+    ));
+    std::fs::write(&output, "").unwrap();
+    std::fs::set_permissions(&output, std::fs::Permissions::from_mode(0o644)).unwrap();
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .arg(&output)
+        .write_stdin("passphrase")
+        .assert()
+        .success();
+    let metadata = std::fs::metadata(&output).unwrap();
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "Hello, world!\n");
+    std::fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn decrypt_with_preserve() {
+    let input = std::env::temp_dir().join(format!(
+        "rscrypt-test-{}-decrypt-preserve.txt.scrypt",
+        std::process::id()
+    ));
+    let output = input.with_extension("");
+    std::fs::copy("tests/data/data.txt.scrypt", &input).unwrap();
+    let modified =
+        std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+    {
+        let file = std::fs::File::options().write(true).open(&input).unwrap();
+        file.set_modified(modified).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            file.set_permissions(std::fs::Permissions::from_mode(0o640))
+                .unwrap();
+        }
+    }
+    utils::command::command()
+        .arg("dec")
+        .arg("--preserve")
+        .arg("--passphrase-from-stdin")
+        .arg(&input)
+        .arg(&output)
+        .write_stdin("passphrase")
+        .assert()
+        .success();
+    let metadata = std::fs::metadata(&output).unwrap();
+    assert_eq!(metadata.modified().unwrap(), modified);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o640);
+    }
+    std::fs::remove_file(input).unwrap();
+    std::fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn validate_preserve_for_decrypt_command() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--preserve")
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+    utils::command::command()
+        .arg("dec")
+        .arg("--preserve")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("-")
+        .arg("data.txt")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "cannot preserve the metadata of standard input",
+        ));
+}
+
+#[test]
+fn decrypt_to_explicit_stdout() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .arg("-")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    utils::command::command()
+        .arg("dec")
+        .arg("--preserve")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .arg("-")
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "cannot preserve the metadata in standard output",
+        ));
+}
+
+#[test]
+fn decrypt_with_tries_from_non_interactive_source() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--tries")
+        .arg("3")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_TEST_PASSPHRASE", "password")
+        .assert()
+        .failure()
+        .code(11)
+        .stderr(predicate::str::contains("passphrase is incorrect"))
+        .stderr(predicate::str::contains("try again").not());
+}
+
+#[test]
+fn validate_tries_for_decrypt_command() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--tries")
+        .arg("0")
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value '0' for '--tries <N>'",
+        ));
+}
+
+fn encrypt_compressed() -> Vec<u8> {
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--compress")
+        .arg("zstd")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("passphrase")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    output.stdout
+}
+
+#[test]
+fn decrypt_with_no_decompress() {
+    let output = utils::command::command()
+        .arg("dec")
+        .arg("--no-decompress")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_PASSPHRASE")
+        .arg("--force-tty")
+        .arg("-")
+        .env("RSCRYPT_PASSPHRASE", "passphrase")
+        .write_stdin(encrypt_compressed())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.starts_with(&[0x50, 0x2a, 0x4d, 0x18]));
+}
+
+#[test]
+fn decrypt_with_max_decompressed_size() {
+    let path = env::temp_dir().join(format!("rscrypt-test-{}-large.txt", process::id()));
+    fs::write(&path, vec![b'a'; 2 * 1024 * 1024]).unwrap();
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--compress")
+        .arg("gzip")
+        .arg("--passphrase-from-stdin")
+        .arg(&path)
+        .write_stdin("passphrase")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    fs::remove_file(path).unwrap();
+    utils::command::command()
+        .arg("dec")
+        .arg("--max-decompressed-size")
+        .arg("2MiB")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_PASSPHRASE")
+        .arg("-")
+        .env("RSCRYPT_PASSPHRASE", "passphrase")
+        .write_stdin(output.stdout.clone())
+        .assert()
+        .success()
+        .stdout(predicate::function(|out: &[u8]| {
+            out.len() == 2 * 1024 * 1024
+        }));
+    utils::command::command()
+        .arg("dec")
+        .arg("--max-decompressed-size")
+        .arg("1MiB")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_PASSPHRASE")
+        .arg("-")
+        .env("RSCRYPT_PASSPHRASE", "passphrase")
+        .write_stdin(output.stdout)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("could not decompress data"))
+        .stderr(predicate::str::contains(
+            "decompressed data is larger than the limit",
+        ));
+}
@@ -0,0 +1,226 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+#![cfg(unix)]
+
+mod utils;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use predicates::prelude::{PredicateBooleanExt, predicate};
+use utils::temp::{TempPath, copy_data, temp_path};
+
+/// Creates an editor which runs `script` with the path of the file as `$1`.
+///
+/// Returns the script and the command to run it.
+fn create_editor(name: &str, script: &str) -> (TempPath, String) {
+    let path = TempPath::new(name);
+    fs::write(&path, script).unwrap();
+    let editor = format!("sh {}", path.display());
+    (path, editor)
+}
+
+fn decrypt(path: &Path) -> String {
+    let output = utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn edit() {
+    let path = copy_data("tests/data/data.txt.scrypt", "edit.scrypt");
+    let (_script, editor) = create_editor(
+        "edit-editor.sh",
+        "printf 'Goodbye, world!\\n' >> \"$1\"\necho \"$1\" > \"$0.log\"\n",
+    );
+    utils::command::command()
+        .arg("edit")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .env_remove("VISUAL")
+        .env("EDITOR", &editor)
+        .assert()
+        .success();
+    assert_eq!(decrypt(&path), "Hello, world!\nGoodbye, world!\n");
+    assert_eq!(
+        fs::read(&path).unwrap()[..16],
+        fs::read("tests/data/data.txt.scrypt").unwrap()[..16]
+    );
+
+    let log = TempPath::new("edit-editor.sh.log");
+    let temp_file = PathBuf::from(fs::read_to_string(&log).unwrap().trim_end());
+    assert_eq!(
+        temp_file.file_name().unwrap(),
+        temp_path("edit").file_name().unwrap()
+    );
+    assert!(!temp_file.exists());
+}
+
+#[test]
+fn edit_armored_data() {
+    let path = copy_data("tests/data/data.txt.scrypt.asc", "edit-armored.scrypt.asc");
+    let (_script, editor) = create_editor(
+        "edit-armored-editor.sh",
+        "printf 'Goodbye, world!\\n' > \"$1\"\n",
+    );
+    utils::command::command()
+        .arg("edit")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .env("VISUAL", &editor)
+        .assert()
+        .success();
+    assert!(
+        fs::read(&path)
+            .unwrap()
+            .starts_with(b"-----BEGIN SCRYPT ENCRYPTED DATA-----\n")
+    );
+    assert_eq!(decrypt(&path), "Goodbye, world!\n");
+}
+
+#[test]
+fn edit_without_changes() {
+    let path = copy_data("tests/data/data.txt.scrypt", "edit-unchanged.scrypt");
+    utils::command::command()
+        .arg("edit")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .env("VISUAL", "true")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "was not changed, skipping re-encryption",
+        ));
+    assert_eq!(
+        fs::read(&path).unwrap(),
+        fs::read("tests/data/data.txt.scrypt").unwrap()
+    );
+}
+
+#[test]
+fn edit_with_upgrade() {
+    let path = copy_data("tests/data/data.txt.scrypt", "edit-upgrade.scrypt");
+    let (_script, editor) = create_editor(
+        "edit-upgrade-editor.sh",
+        "printf 'Goodbye, world!\\n' > \"$1\"\n",
+    );
+    utils::command::command()
+        .arg("edit")
+        .arg("-u")
+        .arg("-t")
+        .arg("1s")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .env("VISUAL", &editor)
+        .assert()
+        .success();
+    utils::command::command()
+        .arg("info")
+        .arg(&path)
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with("Parameters used: N = 1024;").not());
+    assert_eq!(decrypt(&path), "Goodbye, world!\n");
+}
+
+#[test]
+fn edit_if_editor_failed() {
+    let path = copy_data("tests/data/data.txt.scrypt", "edit-failed.scrypt");
+    let (_script, editor) = create_editor(
+        "edit-failed-editor.sh",
+        "printf 'Goodbye, world!\\n' > \"$1\"\necho \"$1\" > \"$0.log\"\nexit 1\n",
+    );
+    utils::command::command()
+        .arg("edit")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .env("VISUAL", &editor)
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(
+            predicate::str::contains("exited with exit status: 1")
+                .and(predicate::str::contains("the edited data was kept in").not()),
+        );
+    assert_eq!(
+        fs::read(&path).unwrap(),
+        fs::read("tests/data/data.txt.scrypt").unwrap()
+    );
+
+    let log = TempPath::new("edit-failed-editor.sh.log");
+    let temp_file = PathBuf::from(fs::read_to_string(&log).unwrap().trim_end());
+    assert!(!temp_file.exists());
+    assert!(!temp_file.parent().unwrap().exists());
+}
+
+#[test]
+fn edit_if_replacing_failed() {
+    let path = copy_data("tests/data/data.txt.scrypt", "edit-replace-failed.scrypt");
+    // Replace the file with a directory while editing, so that renaming the
+    // new file to it fails.
+    let (_script, editor) = create_editor(
+        "edit-replace-failed-editor.sh",
+        &format!(
+            "printf 'Goodbye, world!\\n' > \"$1\"\necho \"$1\" > \"$0.log\"\n\
+             rm '{path}' && mkdir '{path}'\n",
+            path = path.display()
+        ),
+    );
+    utils::command::command()
+        .arg("edit")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .env("VISUAL", &editor)
+        .assert()
+        .failure()
+        .code(74)
+        .stderr(
+            predicate::str::contains("could not write data to")
+                .and(predicate::str::contains("the edited data was kept in")),
+        );
+
+    let log = TempPath::new("edit-replace-failed-editor.sh.log");
+    let temp_file = PathBuf::from(fs::read_to_string(&log).unwrap().trim_end());
+    assert_eq!(fs::read_to_string(&temp_file).unwrap(), "Goodbye, world!\n");
+    fs::remove_dir_all(temp_file.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn edit_with_incorrect_passphrase() {
+    utils::command::command()
+        .arg("edit")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_TEST_PASSPHRASE", "password")
+        .env("VISUAL", "false")
+        .assert()
+        .failure()
+        .code(11)
+        .stderr(predicate::str::contains("passphrase is incorrect"));
+}
@@ -226,6 +226,212 @@ fn encrypt_with_max_time() {
         .stderr(predicate::str::contains("limit: 10.0s"));
 }
 
+#[test]
+fn encrypt_with_progress() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--progress")
+        .arg("always")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Deriving key"));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn encrypt_as_json() {
+    let output = std::env::temp_dir().join(format!(
+        "rscrypt-test-{}-encrypt-as-json.scrypt",
+        std::process::id()
+    ));
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--json")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .arg(&output)
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(
+            r#"{"operation":"encrypt","params":{"N":1024,"r":8,"p":1},"input_size":14,"output_size":142,"estimated_time":"#,
+        ))
+        .stderr(predicate::str::ends_with(format!(
+            "\"output\":\"{}\"}}\n",
+            output.display()
+        )));
+    std::fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn encrypt_with_env() {
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("data/data.txt")
+        .env("RSCRYPT_LOG_N", "10")
+        .env("RSCRYPT_R", "8")
+        .env("RSCRYPT_P", "1")
+        .env("RSCRYPT_PASSPHRASE_SOURCE", "stdin")
+        .write_stdin("passphrase")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    utils::command::command()
+        .arg("info")
+        .arg("-")
+        .write_stdin(output.stdout)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("N = 1024; r = 8; p = 1;"));
+}
+
+#[test]
+fn encrypt_with_env_overridden_by_option() {
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .env("RSCRYPT_MAX_TIME", "10s")
+        .write_stdin("passphrase")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    utils::command::command()
+        .arg("info")
+        .arg("-")
+        .write_stdin(output.stdout)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("N = 1024; r = 8; p = 1;"));
+    utils::command::command()
+        .arg("enc")
+        .arg("-t")
+        .arg("10s")
+        .arg("--passphrase-from-stdin")
+        .arg("-v")
+        .arg("data/data.txt")
+        .env("RSCRYPT_LOG_N", "10")
+        .env("RSCRYPT_R", "8")
+        .env("RSCRYPT_P", "1")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("limit: 10.0s"));
+}
+
+#[test]
+fn encrypt_with_conflicting_env() {
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .env("RSCRYPT_MAX_TIME", "10s")
+        .env("RSCRYPT_LOG_N", "10")
+        .env("RSCRYPT_R", "8")
+        .env("RSCRYPT_P", "1")
+        .write_stdin("passphrase")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    utils::command::command()
+        .arg("info")
+        .arg("-")
+        .write_stdin(output.stdout)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("N = 1024; r = 8; p = 1;"));
+}
+
+#[test]
+fn encrypt_with_invalid_env() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .env("RSCRYPT_MAX_TIME", "NaN")
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "invalid value for RSCRYPT_MAX_TIME",
+        ));
+    utils::command::command()
+        .arg("enc")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .env("RSCRYPT_LOG_N", "9")
+        .env("RSCRYPT_R", "8")
+        .env("RSCRYPT_P", "1")
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("invalid value for RSCRYPT_LOG_N"));
+    utils::command::command()
+        .arg("enc")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .env("RSCRYPT_LOG_N", "10")
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "RSCRYPT_LOG_N, RSCRYPT_R and RSCRYPT_P must be specified together",
+        ));
+}
+
+#[test]
+fn encrypt_with_force_without_parameters() {
+    utils::command::command()
+        .arg("enc")
+        .arg("-f")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "`--force` requires the encryption parameters",
+        ));
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("-f")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .env("RSCRYPT_LOG_N", "10")
+        .env("RSCRYPT_R", "8")
+        .env("RSCRYPT_P", "1")
+        .write_stdin("passphrase")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+}
+
 #[test]
 fn invalid_time_for_encrypt_command() {
     utils::command::command()
@@ -471,3 +677,795 @@ fn encrypt_verbose() {
             "Parameters used: N = 1024; r = 8; p = 1;",
         ));
 }
+
+#[test]
+fn encrypt_with_compress() {
+    for format in ["gzip", "zstd"] {
+        let output = utils::command::command()
+            .arg("enc")
+            .arg("--log-n")
+            .arg("10")
+            .arg("-r")
+            .arg("8")
+            .arg("-p")
+            .arg("1")
+            .arg("--compress")
+            .arg(format)
+            .arg("--passphrase-from-stdin")
+            .arg("data/data.txt")
+            .write_stdin("passphrase")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        utils::command::command()
+            .arg("dec")
+            .arg("--passphrase-from-env")
+            .arg("RSCRYPT_PASSPHRASE")
+            .arg("-")
+            .env("RSCRYPT_PASSPHRASE", "passphrase")
+            .write_stdin(output.stdout)
+            .assert()
+            .success()
+            .stdout(predicate::eq("Hello, world!\n"));
+    }
+}
+
+#[test]
+fn invalid_compression_format_for_encrypt_command() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--compress")
+        .arg("bzip2")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'bzip2' for '--compress <FORMAT>'",
+        ));
+}
+
+#[test]
+fn encrypt_with_armor() {
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--armor")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("passphrase")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let armored = String::from_utf8(output.stdout).unwrap();
+    assert!(armored.starts_with("-----BEGIN SCRYPT ENCRYPTED DATA-----\n"));
+    assert!(armored.ends_with("-----END SCRYPT ENCRYPTED DATA-----\n"));
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_PASSPHRASE")
+        .arg("-")
+        .env("RSCRYPT_PASSPHRASE", "passphrase")
+        .write_stdin(armored)
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
+
+#[test]
+fn encrypt_with_keyfile() {
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--keyfile")
+        .arg("data/data.txt")
+        .arg("data/data.txt")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    utils::command::command()
+        .arg("dec")
+        .arg("--keyfile")
+        .arg("data/data.txt")
+        .arg("-")
+        .write_stdin(output.stdout.clone())
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    utils::command::command()
+        .arg("dec")
+        .arg("--keyfile")
+        .arg("data/data.txt")
+        .arg("--keyfile-digest")
+        .arg("-")
+        .write_stdin(output.stdout)
+        .assert()
+        .failure()
+        .code(11)
+        .stderr(predicate::str::contains("passphrase is incorrect"));
+}
+
+#[test]
+fn encrypt_with_passphrase_and_keyfile() {
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--passphrase-from-stdin")
+        .arg("--keyfile")
+        .arg("data/data.txt.scrypt")
+        .arg("data/data.txt")
+        .write_stdin("passphrase")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_PASSPHRASE")
+        .arg("--keyfile")
+        .arg("data/data.txt.scrypt")
+        .arg("-")
+        .env("RSCRYPT_PASSPHRASE", "passphrase")
+        .write_stdin(output.stdout.clone())
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_PASSPHRASE")
+        .arg("-")
+        .env("RSCRYPT_PASSPHRASE", "passphrase")
+        .write_stdin(output.stdout)
+        .assert()
+        .failure()
+        .code(11)
+        .stderr(predicate::str::contains("passphrase is incorrect"));
+}
+
+#[test]
+fn encrypt_if_non_existent_keyfile() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--keyfile")
+        .arg("non_existent.key")
+        .arg("data/data.txt")
+        .assert()
+        .failure()
+        .code(66)
+        .stderr(predicate::str::contains(
+            "could not read keyfile from non_existent.key",
+        ));
+}
+
+#[test]
+fn validate_keyfile_digest_requires_keyfile_for_encrypt_command() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--keyfile-digest")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("--keyfile <FILE>"));
+}
+
+#[cfg(unix)]
+#[test]
+fn encrypt_with_passphrase_from_fd() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--passphrase-from-fd")
+        .arg("0")
+        .arg("data/data.txt")
+        .write_stdin("passphrase")
+        .assert()
+        .success();
+}
+
+#[cfg(unix)]
+#[test]
+fn validate_conflicts_if_reading_from_fd_0_for_encrypt_command() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--passphrase-from-fd")
+        .arg("0")
+        .arg("-")
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "cannot read both passphrase and input data from standard input",
+        ));
+}
+
+#[test]
+fn encrypt_with_weak_passphrase() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Warning: passphrase is weak"));
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--allow-weak-passphrase")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn encrypt_with_strong_passphrase() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--min-entropy")
+        .arg("40")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("correct horse battery staple")
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn encrypt_if_passphrase_is_empty() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("passphrase is empty"));
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--allow-weak-passphrase")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("")
+        .assert()
+        .success();
+}
+
+#[test]
+fn encrypt_if_passphrase_is_less_than_min_entropy() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--min-entropy")
+        .arg("40")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("passphrase is too weak"))
+        .stderr(predicate::str::contains("required: 40 bits"));
+}
+
+#[test]
+fn validate_conflicts_between_min_entropy_and_allow_weak_passphrase() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--min-entropy")
+        .arg("40")
+        .arg("--allow-weak-passphrase")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn encrypt_with_generated_passphrase() {
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--generate-passphrase")
+        .arg("data/data.txt")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let passphrase = stderr
+        .lines()
+        .find_map(|line| line.strip_prefix("Generated passphrase: "))
+        .unwrap();
+    assert_eq!(passphrase.split('-').count(), 7);
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("-")
+        .env("RSCRYPT_TEST_PASSPHRASE", passphrase)
+        .write_stdin(output.stdout)
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
+
+#[test]
+fn encrypt_with_generated_passphrase_to_file() {
+    let passphrase_file = std::env::temp_dir().join(format!(
+        "rscrypt-test-{}-passphrase-out.txt",
+        std::process::id()
+    ));
+    let output = utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--generate-passphrase")
+        .arg("--chars")
+        .arg("32")
+        .arg("--passphrase-out")
+        .arg(&passphrase_file)
+        .arg("data/data.txt")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    let passphrase = std::fs::read_to_string(&passphrase_file).unwrap();
+    assert_eq!(passphrase.trim_end().len(), 32);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = std::fs::metadata(&passphrase_file)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-file")
+        .arg(&passphrase_file)
+        .arg("-")
+        .write_stdin(output.stdout)
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    std::fs::remove_file(passphrase_file).unwrap();
+}
+
+#[test]
+fn validate_words_without_generate_passphrase() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--words")
+        .arg("5")
+        .arg("data/data.txt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+}
+
+#[test]
+fn validate_conflicts_between_generate_passphrase_and_passphrase_from_stdin() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--generate-passphrase")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn encrypt_with_calibration_cache() {
+    let cache_dir = std::env::temp_dir().join(format!(
+        "rscrypt-test-{}-calibration-cache",
+        std::process::id()
+    ));
+    let cache_file = cache_dir.join("rscrypt/calibration");
+    let encrypt = |args: &[&str]| {
+        let mut command = utils::command::command();
+        command
+            .arg("enc")
+            .arg("-M")
+            .arg("1MiB")
+            .arg("-t")
+            .arg("1s")
+            .args(args)
+            .arg("--passphrase-from-stdin")
+            .arg("data/data.txt")
+            .env("XDG_CACHE_HOME", &cache_dir)
+            .write_stdin("passphrase");
+        command
+    };
+
+    encrypt(&[]).assert().success();
+    let cache = std::fs::read_to_string(&cache_file).unwrap();
+    assert!(cache.contains(concat!("version = ", env!("CARGO_PKG_VERSION"), '\n')));
+    assert!(cache.contains("operations_per_second = "));
+
+    // Make the cached result too slow to encrypt within the time limit.
+    let slow = cache
+        .lines()
+        .map(|line| {
+            if line.starts_with("operations_per_second") {
+                "operations_per_second = 1"
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    std::fs::write(&cache_file, &slow).unwrap();
+    encrypt(&[])
+        .assert()
+        .failure()
+        .code(10)
+        .stderr(predicate::str::contains(
+            "decrypting files takes too much CPU time",
+        ));
+    encrypt(&["--no-calibration-cache"]).assert().success();
+    assert_eq!(std::fs::read_to_string(&cache_file).unwrap(), slow);
+    encrypt(&["--recalibrate"]).assert().success();
+    assert_ne!(std::fs::read_to_string(&cache_file).unwrap(), slow);
+    encrypt(&[]).assert().success();
+
+    std::fs::remove_dir_all(cache_dir).unwrap();
+}
+
+#[test]
+fn validate_conflicts_between_recalibrate_and_no_calibration_cache() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--recalibrate")
+        .arg("--no-calibration-cache")
+        .arg("data/data.txt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+fn remove_input(option: &str) {
+    let input = std::env::temp_dir().join(format!(
+        "rscrypt-test-{}-encrypt{option}.txt",
+        std::process::id()
+    ));
+    let output = input.with_extension("txt.scrypt");
+    std::fs::write(&input, "Hello, world!\n").unwrap();
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg(option)
+        .arg("--passphrase-from-stdin")
+        .arg(&input)
+        .arg(&output)
+        .write_stdin("passphrase")
+        .assert()
+        .success();
+    assert!(!input.exists());
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-stdin")
+        .arg(&output)
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    std::fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn encrypt_with_remove_input() {
+    remove_input("--remove-input");
+}
+
+#[test]
+fn encrypt_with_shred_input() {
+    remove_input("--shred-input");
+}
+
+#[test]
+fn encrypt_with_remove_input_keeps_input_on_failure() {
+    let output = std::env::temp_dir().join(format!(
+        "rscrypt-test-{}-encrypt-remove-input-failure",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&output).unwrap();
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--remove-input")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt")
+        .arg(&output)
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .code(74)
+        .stderr(predicate::str::contains("could not write data to"));
+    assert!(std::path::Path::new("tests/data/data.txt").exists());
+    std::fs::remove_dir(output).unwrap();
+}
+
+fn remove_input_with_same_output(
+    name: &str,
+    option: &str,
+    link: impl FnOnce(&std::path::Path, &std::path::Path),
+) {
+    let input = std::env::temp_dir().join(format!(
+        "rscrypt-test-{}-encrypt-{name}{option}.txt",
+        std::process::id()
+    ));
+    let output = input.with_extension("txt.scrypt");
+    std::fs::write(&input, "Hello, world!\n").unwrap();
+    link(&input, &output);
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg(option)
+        .arg("--passphrase-from-stdin")
+        .arg(&input)
+        .arg(&output)
+        .write_stdin("passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "cannot remove the input file which is also the output file",
+        ));
+    assert_eq!(std::fs::read_to_string(&input).unwrap(), "Hello, world!\n");
+    let _ = std::fs::remove_file(output);
+    std::fs::remove_file(input).unwrap();
+}
+
+#[test]
+fn encrypt_with_remove_input_to_same_file() {
+    for option in ["--remove-input", "--shred-input"] {
+        utils::command::command()
+            .arg("enc")
+            .arg(option)
+            .arg("data/data.txt")
+            .arg("data/data.txt")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "cannot remove the input file which is also the output file",
+            ));
+        remove_input_with_same_output("hard-link", option, |input, output| {
+            std::fs::hard_link(input, output).unwrap();
+        });
+        #[cfg(unix)]
+        remove_input_with_same_output("symlink", option, |input, output| {
+            std::os::unix::fs::symlink(input, output).unwrap();
+        });
+    }
+    assert_eq!(
+        std::fs::read_to_string("tests/data/data.txt").unwrap(),
+        "Hello, world!\n"
+    );
+}
+
+#[test]
+fn validate_remove_input_options_for_encrypt_command() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--remove-input")
+        .arg("--shred-input")
+        .arg("data/data.txt")
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+    utils::command::command()
+        .arg("enc")
+        .arg("--shred-input")
+        .arg("data/data.txt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+    utils::command::command()
+        .arg("enc")
+        .arg("--remove-input")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("-")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("cannot remove standard input"));
+}
+
+#[test]
+fn encrypt_with_preserve() {
+    let input = std::env::temp_dir().join(format!(
+        "rscrypt-test-{}-encrypt-preserve.txt",
+        std::process::id()
+    ));
+    let output = input.with_extension("txt.scrypt");
+    std::fs::write(&input, "Hello, world!\n").unwrap();
+    let modified =
+        std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+    {
+        let file = std::fs::File::options().write(true).open(&input).unwrap();
+        file.set_modified(modified).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))
+                .unwrap();
+        }
+    }
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--preserve")
+        .arg("--passphrase-from-stdin")
+        .arg(&input)
+        .arg(&output)
+        .write_stdin("passphrase")
+        .assert()
+        .success();
+    let metadata = std::fs::metadata(&output).unwrap();
+    assert_eq!(metadata.modified().unwrap(), modified);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+    }
+    std::fs::remove_file(input).unwrap();
+    std::fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn encrypt_to_explicit_stdout() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--passphrase-from-fd")
+        .arg("0")
+        .arg("data/data.txt")
+        .arg("-")
+        .write_stdin("passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::function(|output: &[u8]| {
+            output.starts_with(b"scrypt")
+        }));
+    assert!(!std::path::Path::new("tests/-").exists());
+    utils::command::command()
+        .arg("enc")
+        .arg("--remove-input")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("data/data.txt")
+        .arg("-")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "cannot verify the encrypted data written to standard output",
+        ));
+}
@@ -0,0 +1,73 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+mod utils;
+
+use predicates::prelude::predicate;
+
+#[test]
+fn generate_passphrase() {
+    utils::command::command()
+        .arg("genpass")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("^[a-z]+(-[a-z]+){6}\n$").unwrap());
+}
+
+#[test]
+fn infer_subcommand_name_for_generate_passphrase_command() {
+    utils::command::command()
+        .arg("g")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("^[a-z]+(-[a-z]+){6}\n$").unwrap());
+}
+
+#[test]
+fn generate_passphrase_with_words() {
+    utils::command::command()
+        .arg("genpass")
+        .arg("--words")
+        .arg("10")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("^[a-z]+(-[a-z]+){9}\n$").unwrap());
+}
+
+#[test]
+fn generate_passphrase_with_chars() {
+    utils::command::command()
+        .arg("genpass")
+        .arg("--chars")
+        .arg("32")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("^[0-9A-Za-z]{32}\n$").unwrap());
+}
+
+#[test]
+fn validate_conflicts_between_words_and_chars() {
+    utils::command::command()
+        .arg("genpass")
+        .arg("--words")
+        .arg("5")
+        .arg("--chars")
+        .arg("32")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn validate_words_is_zero() {
+    utils::command::command()
+        .arg("genpass")
+        .arg("--words")
+        .arg("0")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("0 is not in 1..=255"));
+}
@@ -0,0 +1,260 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+mod utils;
+
+use std::{env, fs, iter, path::Path, process::Command};
+
+use predicates::prelude::predicate;
+use utils::temp::{self, TempPath};
+
+fn git(dir: &Path, args: &[&str]) -> Vec<u8> {
+    let bin_dir = assert_cmd::cargo::cargo_bin("rscrypt")
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let path =
+        env::join_paths(iter::once(bin_dir).chain(env::split_paths(&env::var_os("PATH").unwrap())))
+            .unwrap();
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("PATH", path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    output.stdout
+}
+
+fn create_repo(name: &str) -> TempPath {
+    let dir = temp::create_dir(name);
+    git(&dir, &["init", "-q"]);
+    dir
+}
+
+fn clean(path: &str, input: &str) -> Vec<u8> {
+    utils::command::command()
+        .arg("git-filter")
+        .arg("--log-n")
+        .arg("10")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("clean")
+        .arg(path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone()
+}
+
+#[test]
+fn git_filter_clean_is_deterministic() {
+    let ciphertext = clean("secrets.env", "Hello, world!\n");
+    assert!(ciphertext.starts_with(b"scrypt"));
+    assert_eq!(clean("secrets.env", "Hello, world!\n"), ciphertext);
+    assert_ne!(clean("other.env", "Hello, world!\n"), ciphertext);
+    assert_ne!(clean("secrets.env", "Hello, world!"), ciphertext);
+}
+
+#[test]
+fn git_filter_smudge() {
+    let ciphertext = clean("secrets.env", "Hello, world!\n");
+    utils::command::command()
+        .arg("git-filter")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("smudge")
+        .arg("secrets.env")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .write_stdin(ciphertext)
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
+
+#[test]
+fn git_filter_smudge_with_incorrect_passphrase() {
+    utils::command::command()
+        .arg("git-filter")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("smudge")
+        .env("RSCRYPT_TEST_PASSPHRASE", "password")
+        .write_stdin(fs::read("tests/data/data.txt.scrypt").unwrap())
+        .assert()
+        .failure()
+        .code(11)
+        .stderr(predicate::str::contains("passphrase is incorrect"));
+}
+
+#[test]
+fn git_filter_pass_through() {
+    utils::command::command()
+        .arg("git-filter")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("smudge")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .write_stdin("Hello, world!\n")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    let ciphertext = fs::read("tests/data/data.txt.scrypt").unwrap();
+    utils::command::command()
+        .arg("git-filter")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("clean")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .write_stdin(ciphertext.clone())
+        .assert()
+        .success()
+        .stdout(predicate::eq(ciphertext));
+}
+
+#[test]
+fn git_filter_diff() {
+    utils::command::command()
+        .arg("git-filter")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("diff")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
+
+#[test]
+fn git_filter_with_invalid_mode() {
+    utils::command::command()
+        .arg("git-filter")
+        .arg("encrypt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'encrypt' for '<MODE>'",
+        ));
+}
+
+#[test]
+fn git_init() {
+    let dir = create_repo("git-init");
+    fs::write(dir.join(".gitattributes"), "*.txt text").unwrap();
+    let mut salt = None;
+    for _ in 0..2 {
+        utils::command::command()
+            .current_dir(&dir)
+            .arg("git-init")
+            .arg("--log-n")
+            .arg("10")
+            .arg("--passphrase-from-env")
+            .arg("RSCRYPT_TEST_PASSPHRASE")
+            .arg("*.env")
+            .arg("secrets/**")
+            .assert()
+            .success();
+        let current = fs::read(dir.join(".rscrypt-salt")).unwrap();
+        assert_eq!(current.len(), 65);
+        assert!(current[..64].iter().all(u8::is_ascii_hexdigit));
+        assert_eq!(*salt.get_or_insert_with(|| current.clone()), current);
+    }
+    assert_eq!(
+        fs::read_to_string(dir.join(".gitattributes")).unwrap(),
+        "*.txt text\n*.env filter=rscrypt diff=rscrypt\nsecrets/** filter=rscrypt diff=rscrypt\n"
+    );
+    assert_eq!(
+        git(&dir, &["config", "filter.rscrypt.clean"]),
+        b"rscrypt git-filter --log-n 10 -r 8 -p 1 --passphrase-from-env RSCRYPT_TEST_PASSPHRASE \
+          clean -- %f\n"
+    );
+    assert_eq!(git(&dir, &["config", "filter.rscrypt.required"]), b"true\n");
+
+    fs::write(dir.join("secrets.env"), "Hello, world!\n").unwrap();
+    git(&dir, &["add", "secrets.env"]);
+    let blob = git(&dir, &["cat-file", "blob", ":secrets.env"]);
+    assert!(blob.starts_with(b"scrypt"));
+    assert_ne!(blob, clean("secrets.env", "Hello, world!\n"));
+    assert_eq!(
+        git(&dir, &["status", "--porcelain", "secrets.env"]),
+        b"A  secrets.env\n"
+    );
+
+    fs::remove_file(dir.join("secrets.env")).unwrap();
+    git(&dir, &["checkout", "--", "secrets.env"]);
+    assert_eq!(
+        fs::read_to_string(dir.join("secrets.env")).unwrap(),
+        "Hello, world!\n"
+    );
+}
+
+#[test]
+fn git_init_in_clone() {
+    let dir = create_repo("git-init-origin");
+    let init = |dir: &Path| {
+        utils::command::command()
+            .current_dir(dir)
+            .arg("git-init")
+            .arg("--log-n")
+            .arg("10")
+            .arg("--passphrase-from-env")
+            .arg("RSCRYPT_TEST_PASSPHRASE")
+            .arg("*.env")
+            .assert()
+            .success();
+    };
+    init(&dir);
+    fs::write(dir.join("secrets.env"), "Hello, world!\n").unwrap();
+    git(
+        &dir,
+        &["add", ".gitattributes", ".rscrypt-salt", "secrets.env"],
+    );
+    git(
+        &dir,
+        &[
+            "-c",
+            "user.name=rscrypt",
+            "-c",
+            "user.email=rscrypt@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "Add secrets.env",
+        ],
+    );
+
+    let clone = TempPath::new("git-init-clone");
+    git(&dir, &["clone", "-q", ".", clone.to_str().unwrap()]);
+    init(&clone);
+    fs::remove_file(clone.join("secrets.env")).unwrap();
+    git(&clone, &["checkout", "--", "secrets.env"]);
+    assert_eq!(
+        fs::read_to_string(clone.join("secrets.env")).unwrap(),
+        "Hello, world!\n"
+    );
+    git(&clone, &["add", "--renormalize", "secrets.env"]);
+    assert!(git(&clone, &["status", "--porcelain"]).is_empty());
+}
+
+#[test]
+fn git_init_outside_repository() {
+    let dir = temp::create_dir("git-init-outside");
+    utils::command::command()
+        .current_dir(&dir)
+        .env("GIT_CEILING_DIRECTORIES", env::temp_dir())
+        .arg("git-init")
+        .arg("*.env")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "git rev-parse --show-toplevel failed",
+        ));
+}
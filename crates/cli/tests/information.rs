@@ -18,6 +18,65 @@ fn basic_information() {
         ));
 }
 
+#[test]
+fn detailed_information() {
+    utils::command::command()
+        .arg("info")
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Format version: 0\n"))
+        .stderr(predicate::str::contains(
+            "Salt: 64cb0cea31c8657c857b8f09d169759a26d58ff5a12d4aabad9cfb303763d5f9\n",
+        ))
+        .stderr(predicate::str::contains("Header checksum: valid\n"))
+        .stderr(predicate::str::contains("Total size: 142 bytes\n"))
+        .stderr(predicate::str::contains("Payload size: 14 bytes\n"))
+        .stderr(predicate::str::contains("Estimated decryption time: "))
+        .stderr(predicate::str::contains("Exceeds default limits: no\n"));
+}
+
+#[test]
+fn information_of_multiple_files() {
+    utils::command::command()
+        .arg("info")
+        .arg("data/data.txt.scrypt")
+        .arg("data/data.txt.scrypt.asc")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "       N   r   p version checksum",
+        ))
+        .stdout(predicate::str::contains("data/data.txt.scrypt\n"))
+        .stdout(predicate::str::contains("data/data.txt.scrypt.asc\n"));
+}
+
+#[test]
+fn information_if_one_of_multiple_files_is_invalid() {
+    utils::command::command()
+        .arg("info")
+        .arg("data/data.txt.scrypt")
+        .arg("data/data.txt")
+        .assert()
+        .failure()
+        .code(7)
+        .stderr(predicate::str::contains(
+            "could not read information from data/data.txt",
+        ));
+}
+
+#[test]
+fn information_of_armored_data() {
+    utils::command::command()
+        .arg("info")
+        .arg("data/data.txt.scrypt.asc")
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with(
+            "Parameters used: N = 1024; r = 8; p = 1;",
+        ));
+}
+
 #[test]
 fn infer_subcommand_name_for_information_command() {
     utils::command::command()
@@ -72,7 +131,37 @@ fn information_as_json() {
         .arg("data/data.txt.scrypt")
         .assert()
         .success()
-        .stdout(predicate::eq(concat!(r#"{"N":1024,"r":8,"p":1}"#, '\n')));
+        .stdout(predicate::str::starts_with(r#"{"N":1024,"r":8,"p":1,"#))
+        .stdout(predicate::str::contains(r#""file":"data/data.txt.scrypt""#))
+        .stdout(predicate::str::contains(r#""version":0"#))
+        .stdout(predicate::str::contains(
+            r#""salt":"64cb0cea31c8657c857b8f09d169759a26d58ff5a12d4aabad9cfb303763d5f9""#,
+        ))
+        .stdout(predicate::str::contains(r#""checksum_valid":true"#))
+        .stdout(predicate::str::contains(r#""total_size":142"#))
+        .stdout(predicate::str::contains(r#""payload_size":14"#))
+        .stdout(predicate::str::contains(
+            r#""exceeds_default_limits":false"#,
+        ))
+        .stdout(predicate::str::ends_with("}\n"));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn information_of_multiple_files_as_json_lines() {
+    let output = utils::command::command()
+        .arg("info")
+        .arg("-j")
+        .arg("data/data.txt.scrypt")
+        .arg("data/data.txt.scrypt.asc")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains(r#""file":"data/data.txt.scrypt""#));
+    assert!(lines[1].contains(r#""file":"data/data.txt.scrypt.asc""#));
 }
 
 #[test]
@@ -0,0 +1,248 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+#![cfg(feature = "x25519")]
+
+mod utils;
+
+use std::{fs, path::Path};
+
+use predicates::prelude::predicate;
+use utils::temp::TempPath;
+
+fn keygen(name: &str) -> (TempPath, String) {
+    let path = TempPath::new(name);
+    utils::command::command()
+        .arg("keygen")
+        .arg("-o")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::starts_with("Public key: rscrypt1"));
+    let output = utils::command::command()
+        .arg("keygen")
+        .arg("-y")
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let recipient = String::from_utf8(output.stdout)
+        .unwrap()
+        .trim_end()
+        .to_owned();
+    (path, recipient)
+}
+
+fn encrypt(recipient: &str, output: &Path) {
+    utils::command::command()
+        .arg("enc")
+        .arg("--recipient")
+        .arg(recipient)
+        .arg("data/data.txt")
+        .arg(output)
+        .assert()
+        .success();
+}
+
+#[test]
+fn generate_identity() {
+    utils::command::command()
+        .arg("keygen")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                "^# created: .+\n# public key: rscrypt1[a-z0-9]+\nRSCRYPT-SECRET-KEY-1[A-Z0-9]+\n$",
+            )
+            .unwrap(),
+        )
+        .stderr(predicate::str::starts_with("Public key: rscrypt1"));
+}
+
+#[test]
+fn generate_identity_to_file() {
+    let (path, recipient) = keygen("identity.txt");
+    let identity = fs::read_to_string(&path).unwrap();
+    assert!(identity.contains(&format!("# public key: {recipient}\n")));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+    }
+    utils::command::command()
+        .arg("keygen")
+        .arg("-o")
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("could not write data to"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), identity);
+}
+
+#[test]
+fn decrypt_with_identity() {
+    let (identity, recipient) = keygen("identity-decrypt.txt");
+    let path = TempPath::new("recipient.scrypt");
+    encrypt(&recipient, &path);
+    assert!(
+        fs::read(&path)
+            .unwrap()
+            .starts_with(b"screnvlp\x01\x00\x01")
+    );
+    utils::command::command()
+        .arg("dec")
+        .arg("--identity")
+        .arg(&identity)
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    utils::command::command()
+        .arg("dec")
+        .arg("--no-agent")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("has no passphrase"));
+}
+
+#[test]
+fn decrypt_with_identity_or_passphrase() {
+    let (identity, recipient) = keygen("identity-hybrid.txt");
+    let path = TempPath::new("hybrid.scrypt");
+    utils::command::command()
+        .arg("enc")
+        .arg("--recipient")
+        .arg(&recipient)
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("--allow-weak-passphrase")
+        .arg("data/data.txt")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .success();
+    utils::command::command()
+        .arg("dec")
+        .arg("--identity")
+        .arg(&identity)
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    utils::command::command()
+        .arg("dec")
+        .arg("--no-agent")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
+
+#[test]
+fn decrypt_with_incorrect_identity() {
+    let (identity, recipient) = keygen("identity-correct.txt");
+    let (other, _) = keygen("identity-incorrect.txt");
+    let path = TempPath::new("incorrect-identity.scrypt");
+    encrypt(&recipient, &path);
+    utils::command::command()
+        .arg("dec")
+        .arg("--identity")
+        .arg(&other)
+        .arg(&path)
+        .assert()
+        .failure()
+        .code(11)
+        .stderr(predicate::str::contains("identity is incorrect"));
+    utils::command::command()
+        .arg("dec")
+        .arg("--identity")
+        .arg(&other)
+        .arg("--identity")
+        .arg(&identity)
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
+
+#[test]
+fn decrypt_non_envelope_with_identity() {
+    let (identity, _) = keygen("identity-non-envelope.txt");
+    utils::command::command()
+        .arg("dec")
+        .arg("--identity")
+        .arg(&identity)
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "data/data.txt.scrypt is not an envelope",
+        ));
+}
+
+#[test]
+fn invalid_identity_file() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--identity")
+        .arg("data/data.txt")
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "line 1 of data/data.txt is not a secret key",
+        ));
+}
+
+#[test]
+fn invalid_recipient() {
+    utils::command::command()
+        .arg("enc")
+        .arg("--recipient")
+        .arg("rscrypt1invalid")
+        .arg("data/data.txt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "recipient is not a public key starting with \"rscrypt1\"",
+        ));
+}
+
+#[test]
+fn identity_conflicts_with_passphrase() {
+    utils::command::command()
+        .arg("dec")
+        .arg("--identity")
+        .arg("identity.txt")
+        .arg("--passphrase-from-stdin")
+        .arg("data/data.txt.scrypt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the argument '--identity <FILE>' cannot be used with",
+        ));
+}
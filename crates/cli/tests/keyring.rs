@@ -0,0 +1,204 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+#![cfg(all(unix, feature = "keyring"))]
+// The signatures of the methods of the stand-in are determined by the D-Bus
+// interfaces.
+#![allow(
+    clippy::missing_const_for_fn,
+    clippy::needless_pass_by_value,
+    clippy::unused_self
+)]
+
+mod utils;
+
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{self, BufRead, BufReader},
+    process::{self, Child, Command, Stdio},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use predicates::prelude::predicate;
+use zbus::{
+    blocking::connection,
+    interface,
+    zvariant::{OwnedObjectPath, OwnedValue, Value},
+};
+
+const ITEM_PATH: &str = "/org/freedesktop/secrets/collection/login/1";
+const SESSION_PATH: &str = "/org/freedesktop/secrets/session/1";
+
+fn path(path: &str) -> OwnedObjectPath {
+    OwnedObjectPath::try_from(path).unwrap()
+}
+
+/// A stand-in for the Secret Service which holds a single item.
+struct Service;
+
+#[interface(name = "org.freedesktop.Secret.Service")]
+impl Service {
+    fn open_session(&self, algorithm: &str, input: OwnedValue) -> (OwnedValue, OwnedObjectPath) {
+        assert_eq!(algorithm, "plain");
+        assert_eq!(input, OwnedValue::try_from(Value::from("")).unwrap());
+        (
+            OwnedValue::try_from(Value::from("")).unwrap(),
+            path(SESSION_PATH),
+        )
+    }
+
+    fn search_items(
+        &self,
+        attributes: HashMap<String, String>,
+    ) -> (Vec<OwnedObjectPath>, Vec<OwnedObjectPath>) {
+        let matches = attributes.get("service").map(String::as_str) == Some("rscrypt-test")
+            && attributes.get("username").map(String::as_str) == Some("alice");
+        (
+            if matches {
+                vec![path(ITEM_PATH)]
+            } else {
+                Vec::new()
+            },
+            Vec::new(),
+        )
+    }
+}
+
+struct Item;
+
+#[interface(name = "org.freedesktop.Secret.Item")]
+impl Item {
+    fn get_secret(
+        &self,
+        session: OwnedObjectPath,
+    ) -> ((OwnedObjectPath, Vec<u8>, Vec<u8>, String),) {
+        ((
+            session,
+            Vec::new(),
+            b"passphrase".to_vec(),
+            String::from("text/plain"),
+        ),)
+    }
+}
+
+/// A stand-in for the session which counts how many times it was closed.
+struct Session(Arc<AtomicUsize>);
+
+#[interface(name = "org.freedesktop.Secret.Session")]
+impl Session {
+    fn close(&self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// A private session bus.
+struct Bus {
+    daemon: Child,
+    address: String,
+}
+
+impl Bus {
+    /// Starts `dbus-daemon`, or returns [`None`] if it is not installed.
+    fn start() -> Option<Self> {
+        let dir = env::temp_dir().join(format!("rscrypt-test-{}-keyring", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("bus.conf");
+        fs::write(
+            &config,
+            format!(
+                r#"<busconfig>
+  <type>session</type>
+  <listen>unix:path={}</listen>
+  <policy context="default">
+    <allow send_destination="*" eavesdrop="true"/>
+    <allow eavesdrop="true"/>
+    <allow own="*"/>
+  </policy>
+</busconfig>
+"#,
+                dir.join("bus").display()
+            ),
+        )
+        .unwrap();
+        let mut daemon = match Command::new("dbus-daemon")
+            .arg(format!("--config-file={}", config.display()))
+            .arg("--nofork")
+            .arg("--print-address")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(daemon) => daemon,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+            Err(err) => panic!("{err}"),
+        };
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap())
+            .read_line(&mut address)
+            .unwrap();
+        Some(Self {
+            daemon,
+            address: address.trim_end().to_owned(),
+        })
+    }
+}
+
+impl Drop for Bus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+        let _ = fs::remove_dir_all(
+            env::temp_dir().join(format!("rscrypt-test-{}-keyring", process::id())),
+        );
+    }
+}
+
+#[test]
+fn decrypt_with_passphrase_from_keyring() {
+    let Some(bus) = Bus::start() else {
+        eprintln!("dbus-daemon is not installed, skipping");
+        return;
+    };
+    let closed = Arc::default();
+    let _connection = connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .name("org.freedesktop.secrets")
+        .unwrap()
+        .serve_at("/org/freedesktop/secrets", Service)
+        .unwrap()
+        .serve_at(ITEM_PATH, Item)
+        .unwrap()
+        .serve_at(SESSION_PATH, Session(Arc::clone(&closed)))
+        .unwrap()
+        .build()
+        .unwrap();
+
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-keyring")
+        .arg("rscrypt-test/alice")
+        .arg("data/data.txt.scrypt")
+        .env("DBUS_SESSION_BUS_ADDRESS", &bus.address)
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    assert_eq!(closed.load(Ordering::SeqCst), 1);
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-keyring")
+        .arg("rscrypt-test/bob")
+        .arg("data/data.txt.scrypt")
+        .env("DBUS_SESSION_BUS_ADDRESS", &bus.address)
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "no item for rscrypt-test/bob was found in the keyring",
+        ));
+    assert_eq!(closed.load(Ordering::SeqCst), 2);
+}
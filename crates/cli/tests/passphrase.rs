@@ -0,0 +1,315 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+mod utils;
+
+use std::{fs, path::Path};
+
+use predicates::prelude::{PredicateBooleanExt, predicate};
+use utils::temp::TempPath;
+
+fn encrypt_envelope(name: &str, armor: bool) -> TempPath {
+    let path = TempPath::new(name);
+    let mut command = utils::command::command();
+    command
+        .arg("enc")
+        .arg("--envelope")
+        .arg("--log-n")
+        .arg("10")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("--allow-weak-passphrase");
+    if armor {
+        command.arg("--armor");
+    }
+    command
+        .arg("data/data.txt")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "alice")
+        .assert()
+        .success();
+    path
+}
+
+fn decrypt(path: &Path, passphrase: &str) -> assert_cmd::assert::Assert {
+    utils::command::command()
+        .arg("dec")
+        .arg("--no-agent")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(path)
+        .env("RSCRYPT_TEST_PASSPHRASE", passphrase)
+        .assert()
+}
+
+fn add_passphrase(path: &Path, passphrase: &str, new_passphrase: &str) {
+    utils::command::command()
+        .arg("passphrase")
+        .arg("add")
+        .arg("-t")
+        .arg("1s")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("--new-passphrase-from-env")
+        .arg("RSCRYPT_TEST_NEW_PASSPHRASE")
+        .arg("--allow-weak-passphrase")
+        .arg(path)
+        .env("RSCRYPT_TEST_PASSPHRASE", passphrase)
+        .env("RSCRYPT_TEST_NEW_PASSPHRASE", new_passphrase)
+        .assert()
+        .success();
+}
+
+#[test]
+fn decrypt_envelope() {
+    let path = encrypt_envelope("envelope.scrypt", false);
+    assert!(fs::read(&path).unwrap().starts_with(b"screnvlp"));
+    decrypt(&path, "alice")
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    decrypt(&path, "bob")
+        .failure()
+        .code(11)
+        .stderr(predicate::str::contains("passphrase is incorrect"));
+}
+
+#[test]
+fn add_and_remove_passphrase() {
+    let path = encrypt_envelope("envelope-add-remove.scrypt", false);
+    let body = fs::read(&path).unwrap()[202..].to_vec();
+    add_passphrase(&path, "alice", "bob");
+    assert_eq!(fs::read(&path).unwrap()[394..], body);
+    utils::command::command()
+        .arg("passphrase")
+        .arg("list")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("slot").and(predicate::str::contains("\n   1 ")));
+    decrypt(&path, "bob")
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+
+    utils::command::command()
+        .arg("passphrase")
+        .arg("remove")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "alice")
+        .assert()
+        .success();
+    decrypt(&path, "alice").failure().code(11);
+    decrypt(&path, "bob")
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+
+    utils::command::command()
+        .arg("passphrase")
+        .arg("remove")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "bob")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "number of slots must be between 1 and 255, but it is 0",
+        ));
+}
+
+#[test]
+fn remove_slot() {
+    let path = encrypt_envelope("envelope-remove-slot.scrypt", false);
+    add_passphrase(&path, "alice", "bob");
+    utils::command::command()
+        .arg("passphrase")
+        .arg("remove")
+        .arg("--slot")
+        .arg("0")
+        .arg(&path)
+        .assert()
+        .success();
+    decrypt(&path, "alice").failure().code(11);
+    decrypt(&path, "bob")
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+    utils::command::command()
+        .arg("passphrase")
+        .arg("remove")
+        .arg("--slot")
+        .arg("1")
+        .arg(&path)
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("slot 1 does not exist"));
+}
+
+#[test]
+fn add_passphrase_with_incorrect_passphrase() {
+    let path = encrypt_envelope("envelope-incorrect.scrypt", false);
+    let before = fs::read(&path).unwrap();
+    utils::command::command()
+        .arg("passphrase")
+        .arg("add")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("--new-passphrase-from-env")
+        .arg("RSCRYPT_TEST_NEW_PASSPHRASE")
+        .arg("--allow-weak-passphrase")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "carol")
+        .env("RSCRYPT_TEST_NEW_PASSPHRASE", "bob")
+        .assert()
+        .failure()
+        .code(11)
+        .stderr(predicate::str::contains("passphrase is incorrect"));
+    assert_eq!(fs::read(&path).unwrap(), before);
+}
+
+#[test]
+fn add_passphrase_to_armored_envelope() {
+    let path = encrypt_envelope("envelope-armored.scrypt.asc", true);
+    add_passphrase(&path, "alice", "bob");
+    assert!(
+        fs::read(&path)
+            .unwrap()
+            .starts_with(b"-----BEGIN SCRYPT ENCRYPTED DATA-----\n")
+    );
+    decrypt(&path, "bob")
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
+
+#[test]
+fn add_passphrase_to_non_envelope() {
+    utils::command::command()
+        .arg("passphrase")
+        .arg("add")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("--new-passphrase-from-env")
+        .arg("RSCRYPT_TEST_NEW_PASSPHRASE")
+        .arg("data/data.txt.scrypt")
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .env("RSCRYPT_TEST_NEW_PASSPHRASE", "bob")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "data/data.txt.scrypt is not an envelope",
+        ));
+}
+
+#[test]
+fn add_passphrase_from_stdin_twice() {
+    let path = encrypt_envelope("envelope-stdin.scrypt", false);
+    utils::command::command()
+        .arg("passphrase")
+        .arg("add")
+        .arg("--passphrase-from-stdin")
+        .arg("--new-passphrase-from-stdin")
+        .arg(&path)
+        .write_stdin("alice\nbob\n")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "cannot read both passphrase and new passphrase from standard input",
+        ));
+}
+
+#[test]
+fn check_envelope() {
+    let path = encrypt_envelope("envelope-check.scrypt", false);
+    let armored = encrypt_envelope("envelope-check.scrypt.asc", true);
+    let check = |passphrase: &str| {
+        utils::command::command()
+            .arg("check")
+            .arg("--no-agent")
+            .arg("--passphrase-from-env")
+            .arg("RSCRYPT_TEST_PASSPHRASE")
+            .arg(&path)
+            .arg(&armored)
+            .env("RSCRYPT_TEST_PASSPHRASE", passphrase)
+            .assert()
+    };
+    check("alice").success().stdout(predicate::eq(format!(
+        "{}: OK\n{}: OK\n",
+        path.display(),
+        armored.display()
+    )));
+    check("bob")
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains(format!(
+            "{}: wrong passphrase\n",
+            path.display()
+        )));
+}
+
+#[test]
+fn audit_envelope() {
+    let path = encrypt_envelope("envelope-audit.scrypt", false);
+    let armored = encrypt_envelope("envelope-audit.scrypt.asc", true);
+    add_passphrase(&path, "alice", "bob");
+    let audit = |min_log_n: &str| {
+        utils::command::command()
+            .arg("audit")
+            .arg("--min-log-n")
+            .arg(min_log_n)
+            .arg(&path)
+            .arg(&armored)
+            .assert()
+    };
+    audit("10").success().stdout(predicate::str::is_empty());
+    audit("11").failure().code(1).stdout(predicate::eq(format!(
+        "{}: non-compliant (N = 1024; r = 8; p = 1;): N = 2^10 is less than 2^11\n{}: \
+         non-compliant (N = 1024; r = 8; p = 1;): N = 2^10 is less than 2^11\n",
+        path.display(),
+        armored.display()
+    )));
+}
+
+#[test]
+fn reject_envelope() {
+    let path = encrypt_envelope("envelope-reject.scrypt", false);
+    for (args, message) in [
+        (
+            &["info"][..],
+            "data is an envelope; use `rscrypt passphrase list` to show its slots",
+        ),
+        (
+            &[
+                "upgrade",
+                "--passphrase-from-env",
+                "RSCRYPT_TEST_PASSPHRASE",
+            ],
+            "cannot upgrade an envelope; add a passphrase with `rscrypt passphrase add` and \
+             remove the old one instead",
+        ),
+        (
+            &["edit", "--passphrase-from-env", "RSCRYPT_TEST_PASSPHRASE"],
+            "cannot edit an envelope; decrypt it and encrypt it again instead",
+        ),
+    ] {
+        utils::command::command()
+            .args(args)
+            .arg(&path)
+            .env("RSCRYPT_TEST_PASSPHRASE", "alice")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(predicate::str::contains(message));
+    }
+    decrypt(&path, "alice")
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
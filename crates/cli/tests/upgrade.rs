@@ -0,0 +1,208 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+mod utils;
+
+use std::fs;
+
+use predicates::prelude::{PredicateBooleanExt, predicate};
+
+use utils::temp::{TempPath, copy_data};
+
+#[test]
+fn upgrade() {
+    let path = copy_data("tests/data/data.txt.scrypt", "upgrade.scrypt");
+    utils::command::command()
+        .arg("upgrade")
+        .arg("-t")
+        .arg("1s")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .success();
+    let data = fs::read(&path).unwrap();
+    assert_eq!(data.len(), 142);
+    assert_ne!(
+        data[16..48],
+        fs::read("tests/data/data.txt.scrypt").unwrap()[16..48]
+    );
+    utils::command::command()
+        .arg("info")
+        .arg(&path)
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with("Parameters used: N = 1024;").not());
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
+
+#[test]
+fn upgrade_armored_data() {
+    let path = copy_data(
+        "tests/data/data.txt.scrypt.asc",
+        "upgrade-armored.scrypt.asc",
+    );
+    utils::command::command()
+        .arg("upgrade")
+        .arg("-t")
+        .arg("1s")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .success();
+    assert!(
+        fs::read(&path)
+            .unwrap()
+            .starts_with(b"-----BEGIN SCRYPT ENCRYPTED DATA-----\n")
+    );
+    utils::command::command()
+        .arg("dec")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .success()
+        .stdout(predicate::eq("Hello, world!\n"));
+}
+
+#[test]
+fn upgrade_if_params_meet_target() {
+    let path = copy_data("tests/data/data.txt.scrypt", "upgrade-skip.scrypt");
+    utils::command::command()
+        .arg("upgrade")
+        .arg("-t")
+        .arg("1ms")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "password")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "already meet the target, skipping",
+        ));
+    assert_eq!(
+        fs::read(&path).unwrap(),
+        fs::read("tests/data/data.txt.scrypt").unwrap()
+    );
+}
+
+#[test]
+fn upgrade_with_incorrect_passphrase() {
+    let path = copy_data("tests/data/data.txt.scrypt", "upgrade-incorrect.scrypt");
+    utils::command::command()
+        .arg("upgrade")
+        .arg("-t")
+        .arg("1s")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "password")
+        .assert()
+        .failure()
+        .code(11)
+        .stderr(predicate::str::contains("passphrase is incorrect"));
+    assert_eq!(
+        fs::read(&path).unwrap(),
+        fs::read("tests/data/data.txt.scrypt").unwrap()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn upgrade_preserves_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = copy_data("tests/data/data.txt.scrypt", "upgrade-permissions.scrypt");
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+    utils::command::command()
+        .arg("upgrade")
+        .arg("-t")
+        .arg("1s")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .success();
+    assert_eq!(
+        fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+        0o640
+    );
+}
+
+#[test]
+fn upgrade_if_input_file_is_invalid() {
+    utils::command::command()
+        .arg("upgrade")
+        .arg("data/data.txt")
+        .assert()
+        .failure()
+        .code(7)
+        .stderr(predicate::str::contains(
+            "data is not a valid scrypt encrypted file",
+        ));
+}
+
+#[test]
+fn upgrade_if_decryption_requires_too_much_memory() {
+    let path = TempPath::new("upgrade-too-much-memory.scrypt");
+    let config = TempPath::new("upgrade-too-much-memory.toml");
+    fs::write(&config, "[decrypt]\nmax-memory = \"1 MiB\"\n").unwrap();
+    utils::command::command()
+        .arg("enc")
+        .arg("--log-n")
+        .arg("11")
+        .arg("-r")
+        .arg("8")
+        .arg("-p")
+        .arg("1")
+        .arg("--passphrase-from-env")
+        .arg("RSCRYPT_TEST_PASSPHRASE")
+        .arg("data/data.txt")
+        .arg(&path)
+        .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+        .assert()
+        .success();
+    let original = fs::read(&path).unwrap();
+    let upgrade = |force: bool| {
+        let mut command = utils::command::command();
+        command
+            .arg("upgrade")
+            .arg("--config")
+            .arg(&config)
+            .arg("-t")
+            .arg("5s")
+            .arg("--passphrase-from-env")
+            .arg("RSCRYPT_TEST_PASSPHRASE");
+        if force {
+            command.arg("--force");
+        }
+        command
+            .arg(&path)
+            .env("RSCRYPT_TEST_PASSPHRASE", "passphrase")
+            .assert()
+    };
+    upgrade(false)
+        .failure()
+        .code(9)
+        .stderr(predicate::str::contains(
+            "decrypting files takes too much memory",
+        ));
+    assert_eq!(fs::read(&path).unwrap(), original);
+    upgrade(true).success();
+    assert_ne!(fs::read(&path).unwrap(), original);
+}
@@ -2,10 +2,26 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::env;
+
 use assert_cmd::Command;
 
+use super::temp::temp_path;
+
+/// Returns the command which is not affected by the configuration of the
+/// user.
 pub fn command() -> Command {
     let mut command = Command::cargo_bin("rscrypt").unwrap();
-    command.current_dir("tests");
+    command
+        .current_dir("tests")
+        .env("XDG_CONFIG_HOME", temp_path("config"))
+        .env("XDG_CACHE_HOME", temp_path("cache"));
+    for (key, _) in env::vars_os() {
+        if key.to_string_lossy().starts_with("RSCRYPT_") {
+            command.env_remove(key);
+        }
+    }
+    // Do not use the agent of the user.
+    command.env("RSCRYPT_AGENT_SOCK", temp_path("agent/agent.sock"));
     command
 }
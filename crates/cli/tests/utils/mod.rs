@@ -3,3 +3,4 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 pub mod command;
+pub mod temp;
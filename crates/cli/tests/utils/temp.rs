@@ -0,0 +1,80 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Each test binary only uses some of the helpers.
+#![allow(dead_code)]
+
+use std::{
+    env,
+    ffi::OsStr,
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// Returns the path of `name` in the temporary directory for this test
+/// process.
+pub fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("rscrypt-test-{}-{name}", process::id()))
+}
+
+/// The path in the temporary directory which is removed when it is dropped.
+///
+/// This also removes the file or the directory if the test panics.
+#[derive(Debug)]
+pub struct TempPath(PathBuf);
+
+impl TempPath {
+    /// Creates the path of `name` in the temporary directory for this test
+    /// process.
+    pub fn new(name: &str) -> Self {
+        Self(temp_path(name))
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        // The file may not have been created if the test failed.
+        let _ = if self.0.is_dir() {
+            fs::remove_dir_all(&self.0)
+        } else {
+            fs::remove_file(&self.0)
+        };
+    }
+}
+
+impl Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<OsStr> for TempPath {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_os_str()
+    }
+}
+
+/// Copies `source` to `name` in the temporary directory.
+pub fn copy_data(source: &str, name: &str) -> TempPath {
+    let path = TempPath::new(name);
+    fs::copy(source, &path).unwrap();
+    path
+}
+
+/// Creates the directory `name` in the temporary directory.
+pub fn create_dir(name: &str) -> TempPath {
+    let dir = TempPath::new(name);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
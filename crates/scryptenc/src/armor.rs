@@ -0,0 +1,149 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! ASCII armor for the scrypt encrypted data format.
+//!
+//! The ASCII-armored data is the Base64-encoded encrypted data surrounded by
+//! the `-----BEGIN SCRYPT ENCRYPTED DATA-----` and
+//! `-----END SCRYPT ENCRYPTED DATA-----` lines, according to the textual
+//! encoding defined in [RFC 7468].
+//!
+//! # Examples
+//!
+//! ```
+//! use scryptenc::{Decryptor, Encryptor, armor, scrypt::Params};
+//!
+//! let data = b"Hello, world!\n";
+//! let passphrase = "passphrase";
+//!
+//! // Encrypt `data` using `passphrase` and armor it.
+//! let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+//! let ciphertext = Encryptor::with_params(data, passphrase, params).encrypt_to_vec();
+//! let armored = armor::encode(ciphertext);
+//! assert!(armored.starts_with("-----BEGIN SCRYPT ENCRYPTED DATA-----\n"));
+//!
+//! // And dearmor and decrypt it back.
+//! let ciphertext = armor::decode(armored).unwrap();
+//! let plaintext = Decryptor::new(&ciphertext, passphrase)
+//!     .and_then(|c| c.decrypt_to_vec())
+//!     .unwrap();
+//! assert_eq!(plaintext, data);
+//! ```
+//!
+//! [RFC 7468]: https://datatracker.ietf.org/doc/html/rfc7468
+
+use alloc::{string::String, vec::Vec};
+
+use pem_rfc7468::{Error, LineEnding};
+
+/// The type label of the ASCII-armored data.
+pub const LABEL: &str = "SCRYPT ENCRYPTED DATA";
+
+/// Returns the position of the `-----BEGIN SCRYPT ENCRYPTED DATA-----` line in
+/// `data`.
+fn find_boundary(data: &[u8]) -> Option<usize> {
+    let boundary = format!("-----BEGIN {LABEL}-----");
+    data.windows(boundary.len())
+        .position(|w| w == boundary.as_bytes())
+}
+
+/// Encodes `data` into the ASCII-armored data.
+///
+/// # Panics
+///
+/// Panics if the length of the ASCII-armored data overflows [`usize`].
+///
+/// # Examples
+///
+/// ```
+/// use scryptenc::armor;
+///
+/// let ciphertext = include_bytes!("../tests/data/data.txt.scrypt");
+///
+/// let armored = armor::encode(ciphertext);
+/// assert!(armored.starts_with("-----BEGIN SCRYPT ENCRYPTED DATA-----\n"));
+/// assert!(armored.ends_with("-----END SCRYPT ENCRYPTED DATA-----\n"));
+/// ```
+#[must_use]
+#[inline]
+pub fn encode(data: impl AsRef<[u8]>) -> String {
+    pem_rfc7468::encode_string(LABEL, LineEnding::LF, data.as_ref())
+        .expect("length of the ASCII-armored data should be valid")
+}
+
+/// Decodes the ASCII-armored data.
+///
+/// Any text before the `-----BEGIN SCRYPT ENCRYPTED DATA-----` line and any
+/// trailing whitespace are ignored.
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - `data` is not a valid ASCII-armored data.
+/// - The type label is not [`LABEL`].
+///
+/// # Examples
+///
+/// ```
+/// use scryptenc::armor;
+///
+/// let ciphertext = include_bytes!("../tests/data/data.txt.scrypt");
+/// let armored = armor::encode(ciphertext);
+///
+/// let decoded = armor::decode(armored).unwrap();
+/// assert_eq!(decoded, ciphertext);
+/// ```
+pub fn decode(data: impl AsRef<[u8]>) -> Result<Vec<u8>, Error> {
+    let inner = |data: &[u8]| -> Result<Vec<u8>, Error> {
+        let data = find_boundary(data)
+            .map_or(data, |pos| &data[pos..])
+            .trim_ascii_end();
+        let (label, decoded) = pem_rfc7468::decode_vec(data)?;
+        if label == LABEL {
+            Ok(decoded)
+        } else {
+            Err(Error::UnexpectedTypeLabel { expected: LABEL })
+        }
+    };
+    inner(data.as_ref())
+}
+
+/// Returns [`true`] if `data` looks like the ASCII-armored data.
+///
+/// This only checks whether `data` contains the
+/// `-----BEGIN SCRYPT ENCRYPTED DATA-----` line. As with [`decode`], any text
+/// before the line is allowed. Use [`decode`] to validate it.
+///
+/// # Examples
+///
+/// ```
+/// use scryptenc::armor;
+///
+/// let ciphertext = include_bytes!("../tests/data/data.txt.scrypt");
+/// assert!(!armor::is_armored(ciphertext));
+///
+/// let armored = armor::encode(ciphertext);
+/// assert!(armor::is_armored(armored));
+/// ```
+#[must_use]
+pub fn is_armored(data: impl AsRef<[u8]>) -> bool {
+    find_boundary(data.as_ref()).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label() {
+        assert_eq!(LABEL, "SCRYPT ENCRYPTED DATA");
+    }
+
+    #[test]
+    fn line_width() {
+        let armored = encode([u8::MAX; 128]);
+        assert!(armored.lines().all(|line| line.len() <= 64));
+    }
+}
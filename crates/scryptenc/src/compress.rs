@@ -0,0 +1,312 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Compression of the plaintext before encryption.
+//!
+//! The scrypt encrypted data format does not have a field to indicate that the
+//! plaintext is compressed, so the compressed data produced by this module
+//! carries a marker instead. For gzip, the marker is an extra field
+//! (`FEXTRA`) with the subfield ID `RS`. For Zstandard, the marker is a
+//! skippable frame placed before the compressed frame. Both are valid
+//! according to [RFC 1952] and [RFC 8878], so the decrypted data can still be
+//! decompressed with the standard tools. On the other hand, data which was
+//! compressed by other tools before encryption is not detected as compressed.
+//!
+//! # Security considerations
+//!
+//! Compression makes the length of the encrypted data depend on the content of
+//! the plaintext. If an attacker can control part of the plaintext and observe
+//! the length of the encrypted data, they may be able to recover the secret
+//! part of the plaintext, as in the [CRIME] and [BREACH] attacks. Do not
+//! compress data that mixes secrets with attacker-controlled data.
+//!
+//! # Examples
+//!
+//! ```
+//! use scryptenc::{
+//!     Decryptor, Encryptor,
+//!     compress::{self, Compression},
+//!     scrypt::Params,
+//! };
+//!
+//! let data = b"Hello, world!\n";
+//! let passphrase = "passphrase";
+//!
+//! // Compress `data` and encrypt it using `passphrase`.
+//! let compressed = compress::compress(data, Compression::Zstd).unwrap();
+//! let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+//! let ciphertext = Encryptor::with_params(&compressed, passphrase, params).encrypt_to_vec();
+//!
+//! // And decrypt and decompress it back.
+//! let plaintext = Decryptor::new(&ciphertext, passphrase)
+//!     .and_then(|c| c.decrypt_to_vec())
+//!     .unwrap();
+//! assert_eq!(compress::detect(&plaintext), Some(Compression::Zstd));
+//! let plaintext = compress::decompress(plaintext).unwrap();
+//! assert_eq!(plaintext, data);
+//! ```
+//!
+//! [RFC 1952]: https://datatracker.ietf.org/doc/html/rfc1952
+//! [RFC 8878]: https://datatracker.ietf.org/doc/html/rfc8878
+//! [CRIME]: https://en.wikipedia.org/wiki/CRIME
+//! [BREACH]: https://en.wikipedia.org/wiki/BREACH
+
+use std::{
+    boxed::Box,
+    io::{self, Read, Write},
+    vec::Vec,
+};
+
+use flate2::{GzBuilder, read::GzDecoder};
+
+/// The identifier of the marker which indicates that the data was compressed
+/// by this module.
+const MARKER_ID: [u8; 2] = *b"RS";
+
+/// The gzip extra field which contains the marker.
+///
+/// This consists of the subfield ID and the subfield length (zero).
+const GZIP_EXTRA_FIELD: [u8; 4] = [MARKER_ID[0], MARKER_ID[1], 0, 0];
+
+/// The magic number of gzip, followed by the compression method (deflate).
+const GZIP_MAGIC_NUMBER: [u8; 3] = [0x1f, 0x8b, 0x08];
+
+/// The flag which indicates that the gzip header has an extra field.
+const GZIP_FEXTRA: u8 = 0x04;
+
+/// The skippable frame of Zstandard which contains the marker.
+///
+/// This consists of the magic number of the skippable frame, the frame size
+/// and the marker.
+const ZSTD_SKIPPABLE_FRAME: [u8; 10] = [
+    0x50,
+    0x2a,
+    0x4d,
+    0x18,
+    0x02,
+    0x00,
+    0x00,
+    0x00,
+    MARKER_ID[0],
+    MARKER_ID[1],
+];
+
+/// The magic number of Zstandard.
+const ZSTD_MAGIC_NUMBER: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The compression format used before encryption.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Compression {
+    /// gzip.
+    Gzip,
+
+    /// Zstandard.
+    Zstd,
+}
+
+/// Compresses `data` with the specified [`Compression`] and into a newly
+/// allocated [`Vec`].
+///
+/// The result contains a marker so that [`detect`] can recognize it.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the compression fails.
+///
+/// # Examples
+///
+/// ```
+/// use scryptenc::compress::{self, Compression};
+///
+/// let data = b"Hello, world!\n";
+///
+/// let compressed = compress::compress(data, Compression::Gzip).unwrap();
+/// assert_ne!(compressed, data);
+/// ```
+pub fn compress(data: impl AsRef<[u8]>, compression: Compression) -> io::Result<Vec<u8>> {
+    let inner = |data: &[u8], compression: Compression| -> io::Result<Vec<u8>> {
+        match compression {
+            Compression::Gzip => {
+                let mut encoder = GzBuilder::new()
+                    .extra(GZIP_EXTRA_FIELD)
+                    .write(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            Compression::Zstd => {
+                let mut buf = Vec::from(ZSTD_SKIPPABLE_FRAME);
+                zstd::stream::copy_encode(data, &mut buf, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+                Ok(buf)
+            }
+        }
+    };
+    inner(data.as_ref(), compression)
+}
+
+/// Detects the [`Compression`] of `data` compressed by [`compress`].
+///
+/// Returns [`None`] if `data` does not have the marker, even if it is a valid
+/// gzip or Zstandard data.
+///
+/// # Examples
+///
+/// ```
+/// use scryptenc::compress::{self, Compression};
+///
+/// let data = b"Hello, world!\n";
+/// assert_eq!(compress::detect(data), None);
+///
+/// let compressed = compress::compress(data, Compression::Gzip).unwrap();
+/// assert_eq!(compress::detect(compressed), Some(Compression::Gzip));
+/// ```
+#[must_use]
+pub fn detect(data: impl AsRef<[u8]>) -> Option<Compression> {
+    let inner = |data: &[u8]| -> Option<Compression> {
+        if data.starts_with(&ZSTD_SKIPPABLE_FRAME)
+            && data[ZSTD_SKIPPABLE_FRAME.len()..].starts_with(&ZSTD_MAGIC_NUMBER)
+        {
+            return Some(Compression::Zstd);
+        }
+
+        if !data.starts_with(&GZIP_MAGIC_NUMBER) || data.get(3)? & GZIP_FEXTRA == 0 {
+            return None;
+        }
+        let xlen = usize::from(u16::from_le_bytes([*data.get(10)?, *data.get(11)?]));
+        let mut extra = data.get(12..12 + xlen)?;
+        while let [si1, si2, len_lo, len_hi, rest @ ..] = extra {
+            if [*si1, *si2] == MARKER_ID {
+                return Some(Compression::Gzip);
+            }
+            extra = rest.get(usize::from(u16::from_le_bytes([*len_lo, *len_hi]))..)?;
+        }
+        None
+    };
+    inner(data.as_ref())
+}
+
+/// Decompresses `data` compressed by [`compress`] and into a newly allocated
+/// [`Vec`].
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - `data` was not compressed by [`compress`].
+/// - The decompression fails.
+///
+/// # Examples
+///
+/// ```
+/// use scryptenc::compress::{self, Compression};
+///
+/// let data = b"Hello, world!\n";
+///
+/// let compressed = compress::compress(data, Compression::Zstd).unwrap();
+/// let decompressed = compress::decompress(compressed).unwrap();
+/// assert_eq!(decompressed, data);
+/// ```
+pub fn decompress(data: impl AsRef<[u8]>) -> io::Result<Vec<u8>> {
+    decompress_with_limit(data, u64::MAX)
+}
+
+/// Decompresses `data` compressed by [`compress`] and into a newly allocated
+/// [`Vec`], up to `limit` bytes.
+///
+/// This is useful for protecting against the data which expands to a huge size
+/// when decompressed.
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - `data` was not compressed by [`compress`].
+/// - The decompression fails.
+/// - The decompressed data is larger than `limit` bytes.
+///
+/// # Examples
+///
+/// ```
+/// use scryptenc::compress::{self, Compression};
+///
+/// let data = b"Hello, world!\n";
+///
+/// let compressed = compress::compress(data, Compression::Zstd).unwrap();
+/// let decompressed = compress::decompress_with_limit(&compressed, 14).unwrap();
+/// assert_eq!(decompressed, data);
+/// assert!(compress::decompress_with_limit(&compressed, 13).is_err());
+/// ```
+pub fn decompress_with_limit(data: impl AsRef<[u8]>, limit: u64) -> io::Result<Vec<u8>> {
+    let inner = |data: &[u8], limit: u64| -> io::Result<Vec<u8>> {
+        let decoder: Box<dyn Read> = match detect(data) {
+            Some(Compression::Gzip) => Box::new(GzDecoder::new(data)),
+            Some(Compression::Zstd) => Box::new(zstd::stream::Decoder::new(data)?),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "data was not compressed before encryption",
+                ));
+            }
+        };
+        let mut buf = Vec::new();
+        decoder
+            .take(limit.saturating_add(1))
+            .read_to_end(&mut buf)?;
+        if u64::try_from(buf.len()).map_or(true, |len| len > limit) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "decompressed data is larger than the limit",
+            ));
+        }
+        Ok(buf)
+    };
+    inner(data.as_ref(), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gzip_header_has_marker() {
+        let compressed = compress([], Compression::Gzip).unwrap();
+        assert_eq!(&compressed[..3], GZIP_MAGIC_NUMBER);
+        assert_eq!(compressed[3] & GZIP_FEXTRA, GZIP_FEXTRA);
+        assert_eq!(&compressed[10..12], [4, 0]);
+        assert_eq!(&compressed[12..16], GZIP_EXTRA_FIELD);
+    }
+
+    #[test]
+    fn zstd_has_skippable_frame() {
+        let compressed = compress([], Compression::Zstd).unwrap();
+        assert_eq!(&compressed[..10], ZSTD_SKIPPABLE_FRAME);
+        assert_eq!(&compressed[10..14], ZSTD_MAGIC_NUMBER);
+    }
+
+    #[test]
+    fn detect_gzip_with_other_subfields() {
+        let mut encoder = GzBuilder::new()
+            .extra([
+                b'A',
+                b'B',
+                2,
+                0,
+                0xff,
+                0xff,
+                MARKER_ID[0],
+                MARKER_ID[1],
+                0,
+                0,
+            ])
+            .write(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"Hello, world!\n").unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert_eq!(detect(compressed), Some(Compression::Gzip));
+    }
+
+    #[test]
+    fn detect_truncated_gzip() {
+        let compressed = compress(b"Hello, world!\n", Compression::Gzip).unwrap();
+        assert_eq!(detect(&compressed[..14]), None);
+    }
+}
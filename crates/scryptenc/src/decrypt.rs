@@ -8,7 +8,7 @@ use aes::cipher::{KeyIvInit, StreamCipher, generic_array::GenericArray};
 use hmac::Mac;
 
 use crate::{
-    Aes256Ctr128BE, Error, HEADER_SIZE, HmacSha256, HmacSha256Key, HmacSha256Output, Result,
+    Aes256Ctr128BE, CHUNK_SIZE, DERIVED_KEY_SIZE, Error, HEADER_SIZE, HmacSha256Output, Result,
     TAG_SIZE,
     format::{DerivedKey, Header},
 };
@@ -57,22 +57,72 @@ impl<'c> Decryptor<'c> {
             let mut dk = [u8::default(); DerivedKey::SIZE];
             scrypt::scrypt(passphrase, &header.salt(), &header.params().into(), &mut dk)
                 .expect("derived key size should be 64 bytes");
-            let dk = DerivedKey::new(dk);
-
-            header.verify_mac(&dk.mac(), ciphertext[64..HEADER_SIZE].into())?;
-            let (ciphertext, mac) =
-                ciphertext[HEADER_SIZE..].split_at(ciphertext.len() - HEADER_SIZE - TAG_SIZE);
-            let mac = *HmacSha256Output::from_slice(mac);
-            Ok(Self {
-                header,
-                dk,
-                ciphertext,
-                mac,
-            })
+            Self::with_header(ciphertext, header, DerivedKey::new(dk))
         };
         inner(ciphertext.as_ref(), passphrase.as_ref())
     }
 
+    /// Creates a new `Decryptor` with the derived key instead of the
+    /// passphrase.
+    ///
+    /// This skips computing the derived key, which is the expensive part of
+    /// the decryption. The derived key can be obtained by
+    /// [`Decryptor::derived_key`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `ciphertext` is shorter than 128 bytes.
+    /// - The magic number is invalid.
+    /// - The version number is the unrecognized scrypt version number.
+    /// - The scrypt parameters are invalid.
+    /// - The checksum of the header mismatch.
+    /// - The MAC (authentication tag) of the header is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scryptenc::Decryptor;
+    /// #
+    /// let data = b"Hello, world!\n";
+    /// let ciphertext = include_bytes!("../tests/data/data.txt.scrypt");
+    /// let passphrase = "passphrase";
+    ///
+    /// let dk = Decryptor::new(&ciphertext, passphrase)
+    ///     .unwrap()
+    ///     .derived_key();
+    /// let cipher = Decryptor::with_derived_key(&ciphertext, dk).unwrap();
+    /// let mut buf = [u8::default(); 14];
+    /// cipher.decrypt(&mut buf).unwrap();
+    /// # assert_eq!(buf, *data);
+    /// ```
+    pub fn with_derived_key(
+        ciphertext: &'c impl AsRef<[u8]>,
+        derived_key: [u8; DERIVED_KEY_SIZE],
+    ) -> Result<Self> {
+        let inner = |ciphertext: &'c [u8]| -> Result<Self> {
+            let mut header = Header::parse(ciphertext)?;
+            header.verify_checksum(&ciphertext[48..64])?;
+            Self::with_header(ciphertext, header, DerivedKey::new(derived_key))
+        };
+        inner(ciphertext.as_ref())
+    }
+
+    /// Creates a new `Decryptor` from the header and the derived key.
+    fn with_header(ciphertext: &'c [u8], mut header: Header, dk: DerivedKey) -> Result<Self> {
+        header.verify_mac(&dk.mac(), ciphertext[64..HEADER_SIZE].into())?;
+        let (ciphertext, mac) =
+            ciphertext[HEADER_SIZE..].split_at(ciphertext.len() - HEADER_SIZE - TAG_SIZE);
+        let mac = *HmacSha256Output::from_slice(mac);
+        Ok(Self {
+            header,
+            dk,
+            ciphertext,
+            mac,
+        })
+    }
+
     /// Decrypts the ciphertext into `buf`.
     ///
     /// # Errors
@@ -101,23 +151,135 @@ impl<'c> Decryptor<'c> {
     /// cipher.decrypt(&mut buf).unwrap();
     /// # assert_eq!(buf, *data);
     /// ```
+    #[inline]
     pub fn decrypt(&self, buf: &mut (impl AsMut<[u8]> + ?Sized)) -> Result<()> {
-        let inner = |decryptor: &Self, buf: &mut [u8]| -> Result<()> {
-            fn verify_mac(data: &[u8], key: &HmacSha256Key, tag: &HmacSha256Output) -> Result<()> {
-                let mut mac = HmacSha256::new_from_slice(key)
-                    .expect("HMAC-SHA-256 key size should be 256 bits");
-                mac.update(data);
-                mac.verify(tag).map_err(Error::InvalidMac)
-            }
+        self.decrypt_with_progress(buf, |_| ())
+    }
 
-            buf.copy_from_slice(decryptor.ciphertext);
+    /// Decrypts the ciphertext into `buf` while reporting the progress.
+    ///
+    /// `progress` is called each time a chunk of the ciphertext is decrypted,
+    /// with the total number of bytes of the ciphertext decrypted so far.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the MAC (authentication tag) of the scrypt encrypted
+    /// data format is invalid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following are true:
+    ///
+    /// - `buf` and the decrypted data have different lengths.
+    /// - The end of the keystream will be reached with the given data length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scryptenc::Decryptor;
+    /// #
+    /// let data = b"Hello, world!\n";
+    /// let ciphertext = include_bytes!("../tests/data/data.txt.scrypt");
+    /// let passphrase = "passphrase";
+    ///
+    /// let cipher = Decryptor::new(&ciphertext, passphrase).unwrap();
+    /// let mut buf = [u8::default(); 14];
+    /// let mut processed = 0;
+    /// cipher
+    ///     .decrypt_with_progress(&mut buf, |n| processed = n)
+    ///     .unwrap();
+    /// assert_eq!(processed, data.len());
+    /// # assert_eq!(buf, *data);
+    /// ```
+    pub fn decrypt_with_progress(
+        &self,
+        buf: &mut (impl AsMut<[u8]> + ?Sized),
+        mut progress: impl FnMut(usize),
+    ) -> Result<()> {
+        let inner =
+            |decryptor: &Self, buf: &mut [u8], progress: &mut dyn FnMut(usize)| -> Result<()> {
+                buf.copy_from_slice(decryptor.ciphertext);
+
+                let mut cipher =
+                    Aes256Ctr128BE::new(&decryptor.dk.encrypt(), &GenericArray::default());
+                let mut mac = decryptor.dk.hmac();
+                mac.update(&decryptor.header.as_bytes());
+                let mut processed = 0;
+                for chunk in buf.chunks_mut(CHUNK_SIZE) {
+                    mac.update(chunk);
+                    cipher.apply_keystream(chunk);
+                    processed += chunk.len();
+                    progress(processed);
+                }
+                mac.verify(&decryptor.mac).map_err(Error::InvalidMac)
+            };
+        inner(self, buf.as_mut(), &mut progress)
+    }
+
+    /// Verifies the MAC (authentication tag) of the scrypt encrypted data
+    /// format without decrypting the ciphertext.
+    ///
+    /// This does not require an output buffer, so it can be used to check that
+    /// the data is intact without writing the plaintext anywhere.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the MAC (authentication tag) of the scrypt encrypted
+    /// data format is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scryptenc::Decryptor;
+    /// #
+    /// let ciphertext = include_bytes!("../tests/data/data.txt.scrypt");
+    /// let passphrase = "passphrase";
+    ///
+    /// let cipher = Decryptor::new(&ciphertext, passphrase).unwrap();
+    /// assert!(cipher.verify().is_ok());
+    /// ```
+    #[inline]
+    pub fn verify(&self) -> Result<()> {
+        self.verify_with_progress(|_| ())
+    }
 
-            let mut cipher = Aes256Ctr128BE::new(&decryptor.dk.encrypt(), &GenericArray::default());
-            cipher.apply_keystream(buf);
-            let data = [&decryptor.header.as_bytes(), decryptor.ciphertext].concat();
-            verify_mac(&data, &decryptor.dk.mac(), &decryptor.mac)
+    /// Verifies the MAC (authentication tag) of the scrypt encrypted data
+    /// format without decrypting the ciphertext while reporting the progress.
+    ///
+    /// `progress` is called each time a chunk of the ciphertext is processed,
+    /// with the total number of bytes of the ciphertext processed so far.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the MAC (authentication tag) of the scrypt encrypted
+    /// data format is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scryptenc::Decryptor;
+    /// #
+    /// let ciphertext = include_bytes!("../tests/data/data.txt.scrypt");
+    /// let passphrase = "passphrase";
+    ///
+    /// let cipher = Decryptor::new(&ciphertext, passphrase).unwrap();
+    /// let mut processed = 0;
+    /// assert!(cipher.verify_with_progress(|n| processed = n).is_ok());
+    /// assert_eq!(processed, cipher.out_len());
+    /// ```
+    pub fn verify_with_progress(&self, mut progress: impl FnMut(usize)) -> Result<()> {
+        let inner = |decryptor: &Self, progress: &mut dyn FnMut(usize)| -> Result<()> {
+            let mut mac = decryptor.dk.hmac();
+            mac.update(&decryptor.header.as_bytes());
+            let mut processed = 0;
+            for chunk in decryptor.ciphertext.chunks(CHUNK_SIZE) {
+                mac.update(chunk);
+                processed += chunk.len();
+                progress(processed);
+            }
+            mac.verify(&decryptor.mac).map_err(Error::InvalidMac)
         };
-        inner(self, buf.as_mut())
+        inner(self, &mut progress)
     }
 
     /// Decrypts the ciphertext and into a newly allocated
@@ -149,6 +311,29 @@ impl<'c> Decryptor<'c> {
         Ok(buf)
     }
 
+    /// Returns the derived key.
+    ///
+    /// The derived key can decrypt the data without the passphrase by
+    /// [`Decryptor::with_derived_key`], so it must be kept as secret as the
+    /// passphrase.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scryptenc::Decryptor;
+    /// #
+    /// let ciphertext = include_bytes!("../tests/data/data.txt.scrypt");
+    /// let passphrase = "passphrase";
+    ///
+    /// let cipher = Decryptor::new(&ciphertext, passphrase).unwrap();
+    /// assert_eq!(cipher.derived_key().len(), scryptenc::DERIVED_KEY_SIZE);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn derived_key(&self) -> [u8; DERIVED_KEY_SIZE] {
+        self.dk.to_bytes()
+    }
+
     /// Returns the number of output bytes of the decrypted data.
     ///
     /// # Examples
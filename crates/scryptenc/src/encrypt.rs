@@ -9,7 +9,7 @@ use hmac::Mac;
 use scrypt::Params;
 
 use crate::{
-    Aes256Ctr128BE, HEADER_SIZE, HmacSha256, HmacSha256Key, HmacSha256Output, TAG_SIZE,
+    Aes256Ctr128BE, CHUNK_SIZE, HEADER_SIZE, TAG_SIZE,
     format::{DerivedKey, Header},
 };
 
@@ -44,7 +44,6 @@ impl<'m> Encryptor<'m> {
         Self::with_params(plaintext, passphrase, Params::default())
     }
 
-    #[allow(clippy::missing_panics_doc)]
     /// Creates a new `Encryptor` with the specified [`Params`].
     ///
     /// # Examples
@@ -63,25 +62,65 @@ impl<'m> Encryptor<'m> {
         passphrase: impl AsRef<[u8]>,
         params: Params,
     ) -> Self {
-        let inner = |plaintext: &'m [u8], passphrase: &[u8], params: Params| -> Self {
-            let mut header = Header::new(params);
-
-            // The derived key size is 64 bytes. The first 256 bits are for AES-256-CTR key,
-            // and the last 256 bits are for HMAC-SHA-256 key.
-            let mut dk = [u8::default(); DerivedKey::SIZE];
-            scrypt::scrypt(passphrase, &header.salt(), &header.params().into(), &mut dk)
-                .expect("derived key size should be 64 bytes");
-            let dk = DerivedKey::new(dk);
-
-            header.compute_checksum();
-            header.compute_mac(&dk.mac());
-            Self {
-                header,
-                dk,
-                plaintext,
-            }
-        };
-        inner(plaintext.as_ref(), passphrase.as_ref(), params)
+        Self::with_header(plaintext.as_ref(), passphrase.as_ref(), Header::new(params))
+    }
+
+    /// Creates a new `Encryptor` with the specified [`Params`] and salt.
+    ///
+    /// The same plaintext, passphrase, parameters and salt always result in
+    /// the same encrypted data. This is useful when the encrypted data should
+    /// be reproducible, but reusing a salt reveals which encrypted data have
+    /// the same plaintext and passphrase. Use [`Encryptor::with_params`] unless
+    /// deterministic output is required.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scryptenc::{Encryptor, scrypt::Params};
+    /// #
+    /// let data = b"Hello, world!\n";
+    /// let passphrase = "passphrase";
+    ///
+    /// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    /// let cipher = Encryptor::with_salt(data, passphrase, params, [u8::MAX; 32]);
+    /// ```
+    #[inline]
+    pub fn with_salt(
+        plaintext: &'m impl AsRef<[u8]>,
+        passphrase: impl AsRef<[u8]>,
+        params: Params,
+        salt: [u8; 32],
+    ) -> Self {
+        Self::with_header(
+            plaintext.as_ref(),
+            passphrase.as_ref(),
+            Header::with_salt(params, salt),
+        )
+    }
+
+    /// Creates a new `Encryptor` from the header.
+    fn with_header(plaintext: &'m [u8], passphrase: &[u8], header: Header) -> Self {
+        // The derived key size is 64 bytes. The first 256 bits are for AES-256-CTR key,
+        // and the last 256 bits are for HMAC-SHA-256 key.
+        let mut dk = [u8::default(); DerivedKey::SIZE];
+        scrypt::scrypt(passphrase, &header.salt(), &header.params().into(), &mut dk)
+            .expect("derived key size should be 64 bytes");
+        Self::with_derived_key(plaintext, header, DerivedKey::new(dk))
+    }
+
+    /// Creates a new `Encryptor` from the header and the derived key.
+    pub(crate) fn with_derived_key(
+        plaintext: &'m [u8],
+        mut header: Header,
+        dk: DerivedKey,
+    ) -> Self {
+        header.compute_checksum();
+        header.compute_mac(&dk.mac());
+        Self {
+            header,
+            dk,
+            plaintext,
+        }
     }
 
     /// Encrypts the plaintext into `buf`.
@@ -107,26 +146,63 @@ impl<'m> Encryptor<'m> {
     /// cipher.encrypt(&mut buf);
     /// # assert_ne!(buf.as_slice(), data);
     /// ```
+    #[inline]
     pub fn encrypt(&self, buf: &mut (impl AsMut<[u8]> + ?Sized)) {
-        let inner = |encryptor: &Self, buf: &mut [u8]| {
-            fn compute_mac(data: &[u8], key: &HmacSha256Key) -> HmacSha256Output {
-                let mut mac = HmacSha256::new_from_slice(key)
-                    .expect("HMAC-SHA-256 key size should be 256 bits");
-                mac.update(data);
-                mac.finalize().into_bytes()
-            }
+        self.encrypt_with_progress(buf, |_| ());
+    }
 
+    /// Encrypts the plaintext into `buf` while reporting the progress.
+    ///
+    /// `progress` is called each time a chunk of the plaintext is encrypted,
+    /// with the total number of bytes of the plaintext encrypted so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following are true:
+    ///
+    /// - `buf` and the encrypted data have different lengths.
+    /// - The end of the keystream will be reached with the given data length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scryptenc::{Encryptor, scrypt::Params};
+    /// #
+    /// let data = b"Hello, world!\n";
+    /// let passphrase = "passphrase";
+    ///
+    /// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    /// let cipher = Encryptor::with_params(data, passphrase, params);
+    /// let mut buf = [u8::default(); 142];
+    /// let mut processed = 0;
+    /// cipher.encrypt_with_progress(&mut buf, |n| processed = n);
+    /// assert_eq!(processed, data.len());
+    /// # assert_ne!(buf.as_slice(), data);
+    /// ```
+    pub fn encrypt_with_progress(
+        &self,
+        buf: &mut (impl AsMut<[u8]> + ?Sized),
+        mut progress: impl FnMut(usize),
+    ) {
+        let inner = |encryptor: &Self, buf: &mut [u8], progress: &mut dyn FnMut(usize)| {
             let bound = (HEADER_SIZE, encryptor.out_len() - TAG_SIZE);
             buf[..bound.0].copy_from_slice(&encryptor.header.as_bytes());
             let body = &mut buf[bound.0..bound.1];
             body.copy_from_slice(encryptor.plaintext);
 
             let mut cipher = Aes256Ctr128BE::new(&encryptor.dk.encrypt(), &GenericArray::default());
-            cipher.apply_keystream(body);
-            let mac = compute_mac(&buf[..bound.1], &encryptor.dk.mac());
-            buf[bound.1..].copy_from_slice(&mac);
+            let mut mac = encryptor.dk.hmac();
+            mac.update(&buf[..bound.0]);
+            let mut processed = 0;
+            for chunk in buf[bound.0..bound.1].chunks_mut(CHUNK_SIZE) {
+                cipher.apply_keystream(chunk);
+                mac.update(chunk);
+                processed += chunk.len();
+                progress(processed);
+            }
+            buf[bound.1..].copy_from_slice(&mac.finalize().into_bytes());
         };
-        inner(self, buf.as_mut());
+        inner(self, buf.as_mut(), &mut progress);
     }
 
     /// Encrypts the plaintext and into a newly allocated
@@ -156,6 +232,10 @@ impl<'m> Encryptor<'m> {
 
     /// Returns the number of output bytes of the encrypted data.
     ///
+    /// # Panics
+    ///
+    /// Panics if the length of the encrypted data overflows [`usize`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -229,3 +309,33 @@ pub fn encrypt_with_params(
 ) -> alloc::vec::Vec<u8> {
     Encryptor::with_params(&plaintext, passphrase, params).encrypt_to_vec()
 }
+
+#[allow(clippy::module_name_repetitions)]
+/// Encrypts `plaintext` with the specified [`Params`] and salt and into a
+/// newly allocated [`Vec`](alloc::vec::Vec).
+///
+/// This is a convenience function for using [`Encryptor::with_salt`] and
+/// [`Encryptor::encrypt_to_vec`].
+///
+/// # Examples
+///
+/// ```
+/// # use scryptenc::scrypt::Params;
+/// #
+/// let data = b"Hello, world!\n";
+/// let passphrase = "passphrase";
+///
+/// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// let ciphertext = scryptenc::encrypt_with_salt(data, passphrase, params, [u8::MAX; 32]);
+/// # assert_ne!(ciphertext, data);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encrypt_with_salt(
+    plaintext: impl AsRef<[u8]>,
+    passphrase: impl AsRef<[u8]>,
+    params: Params,
+    salt: [u8; 32],
+) -> alloc::vec::Vec<u8> {
+    Encryptor::with_salt(&plaintext, passphrase, params, salt).encrypt_to_vec()
+}
@@ -0,0 +1,906 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Envelope which can be decrypted with any of several passphrases.
+//!
+//! The scrypt encrypted data format binds the data to exactly one passphrase.
+//! The envelope encrypts the data with a random data key instead, and stores
+//! the data key wrapped with each passphrase in a separate slot. Any of the
+//! passphrases can decrypt the envelope, and passphrases can be added or
+//! removed without encrypting the data again.
+//!
+//! With the `x25519` feature, the data key can also be wrapped for X25519
+//! public keys (recipients), so the envelope can be decrypted with the
+//! corresponding secret key (identity) instead of a passphrase.
+//!
+//! # Format
+//!
+//! An envelope without recipients is stored as version 0:
+//!
+//! | Offset       | Size    | Description                               |
+//! | ------------ | ------- | ----------------------------------------- |
+//! | 0            | 8       | Magic number (`screnvlp`)                 |
+//! | 8            | 1       | Version (0)                               |
+//! | 9            | 1       | Number of passphrase slots *n* (1 to 255) |
+//! | 10           | 192 × n | Passphrase slots                          |
+//! | 10 + 192 × n | ≥ 128   | Body                                      |
+//!
+//! An envelope with recipients is stored as version 1:
+//!
+//! | Offset                 | Size    | Description                    |
+//! | ---------------------- | ------- | ------------------------------ |
+//! | 0                      | 8       | Magic number (`screnvlp`)      |
+//! | 8                      | 1       | Version (1)                    |
+//! | 9                      | 1       | Number of passphrase slots *n* |
+//! | 10                     | 1       | Number of recipient slots *m*  |
+//! | 11                     | 192 × n | Passphrase slots               |
+//! | 11 + 192 × n           | 224 × m | Recipient slots                |
+//! | 11 + 192 × n + 224 × m | ≥ 128   | Body                           |
+//!
+//! The total number of slots *n* + *m* must be between 1 and 255.
+//!
+//! Each passphrase slot is the scrypt encrypted data of the 64-byte data key,
+//! encrypted with one of the passphrases. The body is the scrypt encrypted
+//! data of the plaintext whose derived key is the data key, so the scrypt
+//! parameters and the salt in its header are not used.
+//!
+//! Each recipient slot is an ephemeral X25519 public key followed by the
+//! scrypt encrypted data of the data key. Its derived key is computed by
+//! HKDF-SHA-256 from the X25519 shared secret, with the ephemeral public key
+//! followed by the public key of the recipient as the salt, so the scrypt
+//! parameters and the salt in its header are not used either.
+//!
+//! # Security considerations
+//!
+//! Removing a passphrase does not change the data key. Anyone who decrypted
+//! the envelope with the removed passphrase could have kept the data key, so
+//! decrypt and encrypt the data again to revoke their access completely.
+//!
+//! The slots are not labeled, so decrypting the envelope may compute the
+//! derived key for every slot before the matching one is found. The recipient
+//! slots do not contain the public keys of the recipients either.
+//!
+//! # Examples
+//!
+//! ```
+//! use scryptenc::{envelope, scrypt::Params};
+//!
+//! let data = b"Hello, world!\n";
+//! let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+//!
+//! // Encrypt `data` for two passphrases.
+//! let ciphertext = envelope::encrypt(data, &["alice", "bob"], params).unwrap();
+//! assert!(envelope::is_envelope(&ciphertext));
+//!
+//! // Add a passphrase without encrypting `data` again.
+//! let ciphertext = envelope::add_passphrase(ciphertext, "alice", "carol", params).unwrap();
+//! assert_eq!(envelope::slots(&ciphertext).unwrap().len(), 3);
+//!
+//! // And remove another one.
+//! let ciphertext = envelope::remove_passphrase(ciphertext, "bob").unwrap();
+//! assert_eq!(envelope::decrypt(&ciphertext, "carol").unwrap(), data);
+//! assert!(envelope::decrypt(&ciphertext, "bob").is_err());
+//! ```
+
+use alloc::vec::Vec;
+use core::{fmt, result};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use scrypt::Params;
+
+#[cfg(feature = "x25519")]
+use hkdf::Hkdf;
+#[cfg(feature = "x25519")]
+use sha2::Sha256;
+#[cfg(feature = "x25519")]
+use x25519_dalek::{EphemeralSecret, PublicKey, SharedSecret, StaticSecret};
+
+use crate::{
+    DERIVED_KEY_SIZE, Decryptor, Encryptor, HEADER_SIZE, TAG_SIZE,
+    format::{DerivedKey, Header},
+};
+
+/// Magic number of the envelope.
+///
+/// This is the ASCII code for "screnvlp". This does not begin with the magic
+/// number of the scrypt encrypted data format, so an envelope is not mistaken
+/// for the scrypt encrypted data.
+const MAGIC_NUMBER: [u8; 8] = *b"screnvlp";
+
+/// Version of the envelope without recipients.
+const VERSION_0: u8 = 0;
+
+/// Version of the envelope with recipients.
+const VERSION_1: u8 = 1;
+
+/// The number of bytes of an X25519 public key.
+const PUBLIC_KEY_SIZE: usize = 32;
+
+/// The info string for deriving the key of a recipient slot with HKDF.
+#[cfg(feature = "x25519")]
+const HKDF_INFO: &[u8] = b"scryptenc envelope x25519";
+
+/// The number of bytes of a slot.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(scryptenc::envelope::SLOT_SIZE, 192);
+/// ```
+pub const SLOT_SIZE: usize = HEADER_SIZE + DERIVED_KEY_SIZE + TAG_SIZE;
+
+/// The number of bytes of a recipient slot.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(scryptenc::envelope::RECIPIENT_SLOT_SIZE, 224);
+/// ```
+pub const RECIPIENT_SLOT_SIZE: usize = PUBLIC_KEY_SIZE + SLOT_SIZE;
+
+/// The maximum number of slots.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(scryptenc::envelope::MAX_SLOTS, 255);
+/// ```
+pub const MAX_SLOTS: usize = u8::MAX as usize;
+
+/// The error type for the envelope.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The envelope was too short.
+    InvalidLength,
+
+    /// The magic number (file signature) was invalid.
+    InvalidMagicNumber,
+
+    /// The version was the unrecognized envelope version number.
+    UnknownVersion(u8),
+
+    /// The total number of slots was not between 1 and [`MAX_SLOTS`].
+    InvalidSlotCount(usize),
+
+    /// The slot index was out of range.
+    InvalidSlotIndex(usize),
+
+    /// The passphrase did not match any slot.
+    NoMatchingSlot,
+
+    /// The public key of the recipient, or the ephemeral public key of a
+    /// recipient slot, was a low-order point.
+    InvalidRecipient,
+
+    /// The identity did not match any recipient slot.
+    NoMatchingIdentity,
+
+    /// A slot was invalid.
+    Slot(crate::Error),
+
+    /// The body was invalid.
+    Body(crate::Error),
+}
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength => write!(f, "envelope is too short"),
+            Self::InvalidMagicNumber => write!(f, "invalid magic number"),
+            Self::UnknownVersion(version) => write!(f, "unknown version number `{version}`"),
+            Self::InvalidSlotCount(count) => write!(
+                f,
+                "number of slots must be between 1 and {MAX_SLOTS}, but it is {count}"
+            ),
+            Self::InvalidSlotIndex(index) => write!(f, "slot {index} does not exist"),
+            Self::NoMatchingSlot => write!(f, "passphrase does not match any slot"),
+            Self::InvalidRecipient => write!(f, "public key of the recipient is invalid"),
+            Self::NoMatchingIdentity => write!(f, "identity does not match any recipient"),
+            Self::Slot(err) => write!(f, "invalid slot: {err}"),
+            Self::Body(err) => err.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Slot(err) | Self::Body(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<crate::Error> for Error {
+    #[inline]
+    fn from(err: crate::Error) -> Self {
+        Self::Body(err)
+    }
+}
+
+/// A specialized [`Result`](result::Result) type for the envelope.
+pub type Result<T> = result::Result<T, Error>;
+
+/// The parsed envelope.
+struct Envelope<'a> {
+    slots: Vec<&'a [u8]>,
+    recipients: Vec<&'a [u8]>,
+    body: &'a [u8],
+}
+
+impl<'a> Envelope<'a> {
+    /// Parses `data` into the envelope.
+    fn parse(data: &'a [u8]) -> Result<Self> {
+        if data.len() < MAGIC_NUMBER.len() + 2 {
+            return Err(Error::InvalidLength);
+        }
+        if data[..MAGIC_NUMBER.len()] != MAGIC_NUMBER {
+            return Err(Error::InvalidMagicNumber);
+        }
+        let (slot_count, recipient_count, data) = match data[8] {
+            VERSION_0 => (usize::from(data[9]), 0, &data[10..]),
+            VERSION_1 => {
+                let (counts, data) = data[9..].split_at_checked(2).ok_or(Error::InvalidLength)?;
+                (usize::from(counts[0]), usize::from(counts[1]), data)
+            }
+            v => return Err(Error::UnknownVersion(v)),
+        };
+        let count = slot_count + recipient_count;
+        if !(1..=MAX_SLOTS).contains(&count) {
+            return Err(Error::InvalidSlotCount(count));
+        }
+        let (slots, data) = data
+            .split_at_checked(SLOT_SIZE * slot_count)
+            .ok_or(Error::InvalidLength)?;
+        let (recipients, body) = data
+            .split_at_checked(RECIPIENT_SLOT_SIZE * recipient_count)
+            .ok_or(Error::InvalidLength)?;
+        if body.len() < HEADER_SIZE + TAG_SIZE {
+            return Err(Error::InvalidLength);
+        }
+        let slots = slots.chunks_exact(SLOT_SIZE).collect();
+        let recipients = recipients.chunks_exact(RECIPIENT_SLOT_SIZE).collect();
+        Ok(Self {
+            slots,
+            recipients,
+            body,
+        })
+    }
+
+    /// Returns the total number of slots.
+    fn len(&self) -> usize {
+        self.slots.len() + self.recipients.len()
+    }
+
+    /// Finds the data key by trying each slot with `passphrase`.
+    ///
+    /// Only the slots whose header MAC does not match are skipped, so a
+    /// corrupted slot is reported instead of being mistaken for another
+    /// passphrase.
+    fn unwrap_key(&self, passphrase: &[u8]) -> Result<(usize, [u8; DERIVED_KEY_SIZE])> {
+        for (i, slot) in self.slots.iter().enumerate() {
+            let cipher = match Decryptor::new(slot, passphrase) {
+                Err(crate::Error::InvalidHeaderMac(_)) => continue,
+                c => c.map_err(Error::Slot)?,
+            };
+            let mut key = [u8::default(); DERIVED_KEY_SIZE];
+            cipher.decrypt(&mut key).map_err(Error::Slot)?;
+            return Ok((i, key));
+        }
+        Err(Error::NoMatchingSlot)
+    }
+
+    /// Finds the data key by trying each recipient slot with `identity`.
+    ///
+    /// As with [`Self::unwrap_key`], only the slots whose header MAC does not
+    /// match are skipped.
+    #[cfg(feature = "x25519")]
+    fn unwrap_key_with_identity(&self, identity: &StaticSecret) -> Result<[u8; DERIVED_KEY_SIZE]> {
+        let public = PublicKey::from(identity);
+        for slot in &self.recipients {
+            let (ephemeral, slot) = slot
+                .split_first_chunk::<PUBLIC_KEY_SIZE>()
+                .expect("recipient slot should be longer than the public key");
+            let ephemeral = PublicKey::from(*ephemeral);
+            let dk = recipient_key(&identity.diffie_hellman(&ephemeral), &ephemeral, &public)
+                .ok_or(Error::InvalidRecipient)?;
+            let cipher = match Decryptor::with_derived_key(&slot, dk) {
+                Err(crate::Error::InvalidHeaderMac(_)) => continue,
+                c => c.map_err(Error::Slot)?,
+            };
+            let mut key = [u8::default(); DERIVED_KEY_SIZE];
+            cipher.decrypt(&mut key).map_err(Error::Slot)?;
+            return Ok(key);
+        }
+        Err(Error::NoMatchingIdentity)
+    }
+
+    /// Converts this envelope to a byte vector.
+    fn to_vec(&self) -> Result<Vec<u8>> {
+        to_vec(&self.slots, &self.recipients, self.body)
+    }
+}
+
+/// Builds the envelope from the slots and the body.
+///
+/// The envelope is stored as version 0 if there are no recipient slots, and
+/// as version 1 otherwise.
+fn to_vec(
+    slots: &[impl AsRef<[u8]>],
+    recipients: &[impl AsRef<[u8]>],
+    body: &[u8],
+) -> Result<Vec<u8>> {
+    let count = slots.len() + recipients.len();
+    if !(1..=MAX_SLOTS).contains(&count) {
+        return Err(Error::InvalidSlotCount(count));
+    }
+    let mut buf = Vec::with_capacity(
+        MAGIC_NUMBER.len()
+            + 3
+            + SLOT_SIZE * slots.len()
+            + RECIPIENT_SLOT_SIZE * recipients.len()
+            + body.len(),
+    );
+    buf.extend_from_slice(&MAGIC_NUMBER);
+    if recipients.is_empty() {
+        buf.push(VERSION_0);
+    } else {
+        buf.push(VERSION_1);
+    }
+    buf.push(u8::try_from(slots.len()).expect("number of slots should be at most 255"));
+    if !recipients.is_empty() {
+        buf.push(u8::try_from(recipients.len()).expect("number of slots should be at most 255"));
+    }
+    for slot in slots
+        .iter()
+        .map(AsRef::as_ref)
+        .chain(recipients.iter().map(AsRef::as_ref))
+    {
+        buf.extend_from_slice(slot);
+    }
+    buf.extend_from_slice(body);
+    Ok(buf)
+}
+
+/// Wraps `key` with `passphrase` into a slot.
+fn wrap_key(key: &[u8; DERIVED_KEY_SIZE], passphrase: &[u8], params: Params) -> Vec<u8> {
+    Encryptor::with_params(key, passphrase, params).encrypt_to_vec()
+}
+
+/// Derives the key of a recipient slot from the shared secret.
+///
+/// Returns [`None`] if the shared secret is all zeros, which happens when
+/// either public key is a low-order point.
+#[cfg(feature = "x25519")]
+fn recipient_key(
+    shared: &SharedSecret,
+    ephemeral: &PublicKey,
+    recipient: &PublicKey,
+) -> Option<[u8; DERIVED_KEY_SIZE]> {
+    if !shared.was_contributory() {
+        return None;
+    }
+    let salt = [ephemeral.as_bytes().as_slice(), recipient.as_bytes()].concat();
+    let mut dk = [u8::default(); DERIVED_KEY_SIZE];
+    Hkdf::<Sha256>::new(Some(&salt), shared.as_bytes())
+        .expand(HKDF_INFO, &mut dk)
+        .expect("derived key size should be valid for HKDF-SHA-256");
+    Some(dk)
+}
+
+/// Wraps `key` for `recipient` into a recipient slot.
+#[cfg(feature = "x25519")]
+fn wrap_key_for_recipient(
+    key: &[u8; DERIVED_KEY_SIZE],
+    recipient: &PublicKey,
+    params: Params,
+) -> Result<Vec<u8>> {
+    let ephemeral = EphemeralSecret::random_from_rng(StdRng::from_entropy());
+    let ephemeral_public = PublicKey::from(&ephemeral);
+    let dk = recipient_key(
+        &ephemeral.diffie_hellman(recipient),
+        &ephemeral_public,
+        recipient,
+    )
+    .ok_or(Error::InvalidRecipient)?;
+    let mut slot = Vec::with_capacity(RECIPIENT_SLOT_SIZE);
+    slot.extend_from_slice(ephemeral_public.as_bytes());
+    slot.extend(
+        Encryptor::with_derived_key(key, Header::new(params), DerivedKey::new(dk)).encrypt_to_vec(),
+    );
+    Ok(slot)
+}
+
+/// Encrypts `plaintext` with `key` into the body.
+fn encrypt_body(plaintext: &[u8], key: [u8; DERIVED_KEY_SIZE], params: Params) -> Vec<u8> {
+    Encryptor::with_derived_key(plaintext, Header::new(params), DerivedKey::new(key))
+        .encrypt_to_vec()
+}
+
+/// Generates a random data key.
+fn generate_key() -> [u8; DERIVED_KEY_SIZE] {
+    let mut key = [u8::default(); DERIVED_KEY_SIZE];
+    StdRng::from_entropy().fill(&mut key);
+    key
+}
+
+/// Encrypts `plaintext` for each of `passphrases` into the envelope.
+///
+/// Each slot is encrypted with the specified [`Params`].
+///
+/// # Errors
+///
+/// Returns [`Err`] if the number of `passphrases` is not between 1 and
+/// [`MAX_SLOTS`].
+///
+/// # Examples
+///
+/// ```
+/// # use scryptenc::{envelope, scrypt::Params};
+/// #
+/// let data = b"Hello, world!\n";
+///
+/// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// let ciphertext = envelope::encrypt(data, &["alice", "bob"], params).unwrap();
+/// assert_eq!(envelope::slots(&ciphertext).unwrap().len(), 2);
+/// # assert_eq!(envelope::decrypt(&ciphertext, "bob").unwrap(), data);
+/// ```
+pub fn encrypt(
+    plaintext: impl AsRef<[u8]>,
+    passphrases: &[impl AsRef<[u8]>],
+    params: Params,
+) -> Result<Vec<u8>> {
+    if !(1..=MAX_SLOTS).contains(&passphrases.len()) {
+        return Err(Error::InvalidSlotCount(passphrases.len()));
+    }
+    let key = generate_key();
+    let slots = passphrases
+        .iter()
+        .map(|passphrase| wrap_key(&key, passphrase.as_ref(), params))
+        .collect::<Vec<_>>();
+    let body = encrypt_body(plaintext.as_ref(), key, params);
+    to_vec(&slots, &[] as &[&[u8]], &body)
+}
+
+/// Encrypts `plaintext` for each of `passphrases` and `recipients` into the
+/// envelope.
+///
+/// Each passphrase slot is encrypted with the specified [`Params`]. Either
+/// `passphrases` or `recipients` may be empty.
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - The total number of `passphrases` and `recipients` is not between 1 and
+///   [`MAX_SLOTS`].
+/// - Any of `recipients` is a low-order point.
+///
+/// # Examples
+///
+/// ```
+/// # use scryptenc::{
+/// #     envelope,
+/// #     scrypt::Params,
+/// #     x25519_dalek::{PublicKey, StaticSecret},
+/// # };
+/// #
+/// let data = b"Hello, world!\n";
+/// let identity = StaticSecret::from([u8::MAX; 32]);
+/// let recipient = PublicKey::from(&identity);
+///
+/// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// let ciphertext =
+///     envelope::encrypt_with_recipients(data, &["alice"], &[recipient], params).unwrap();
+/// assert_eq!(envelope::recipient_count(&ciphertext).unwrap(), 1);
+/// # assert_eq!(envelope::decrypt(&ciphertext, "alice").unwrap(), data);
+/// # assert_eq!(
+/// #     envelope::decrypt_with_identity(&ciphertext, &identity).unwrap(),
+/// #     data
+/// # );
+/// ```
+#[cfg(feature = "x25519")]
+pub fn encrypt_with_recipients(
+    plaintext: impl AsRef<[u8]>,
+    passphrases: &[impl AsRef<[u8]>],
+    recipients: &[PublicKey],
+    params: Params,
+) -> Result<Vec<u8>> {
+    let count = passphrases.len() + recipients.len();
+    if !(1..=MAX_SLOTS).contains(&count) {
+        return Err(Error::InvalidSlotCount(count));
+    }
+    let key = generate_key();
+    let slots = passphrases
+        .iter()
+        .map(|passphrase| wrap_key(&key, passphrase.as_ref(), params))
+        .collect::<Vec<_>>();
+    let recipients = recipients
+        .iter()
+        .map(|recipient| wrap_key_for_recipient(&key, recipient, params))
+        .collect::<Result<Vec<_>>>()?;
+    let body = encrypt_body(plaintext.as_ref(), key, params);
+    to_vec(&slots, &recipients, &body)
+}
+
+/// Decrypts the envelope with `passphrase` and into a newly allocated
+/// [`Vec`].
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - `envelope` is not a valid envelope.
+/// - `passphrase` does not match any slot.
+/// - Any slot which is tried is invalid.
+/// - The body is invalid.
+///
+/// # Examples
+///
+/// ```
+/// # use scryptenc::{envelope, scrypt::Params};
+/// #
+/// let data = b"Hello, world!\n";
+/// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// let ciphertext = envelope::encrypt(data, &["alice", "bob"], params).unwrap();
+///
+/// let plaintext = envelope::decrypt(&ciphertext, "alice").unwrap();
+/// assert_eq!(plaintext, data);
+/// ```
+pub fn decrypt(envelope: impl AsRef<[u8]>, passphrase: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    let inner = |envelope: &[u8], passphrase: &[u8]| -> Result<Vec<u8>> {
+        let envelope = Envelope::parse(envelope)?;
+        let (_, key) = envelope.unwrap_key(passphrase)?;
+        let plaintext = Decryptor::with_derived_key(&envelope.body, key)?.decrypt_to_vec()?;
+        Ok(plaintext)
+    };
+    inner(envelope.as_ref(), passphrase.as_ref())
+}
+
+/// Decrypts the envelope with `identity` and into a newly allocated [`Vec`].
+///
+/// This tries each recipient slot with `identity`, and does not compute the
+/// derived key of any passphrase slot.
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - `envelope` is not a valid envelope.
+/// - `identity` does not match any recipient slot.
+/// - Any recipient slot which is tried is invalid.
+/// - The body is invalid.
+///
+/// # Examples
+///
+/// ```
+/// # use scryptenc::{
+/// #     envelope,
+/// #     scrypt::Params,
+/// #     x25519_dalek::{PublicKey, StaticSecret},
+/// # };
+/// #
+/// let data = b"Hello, world!\n";
+/// let identity = StaticSecret::from([u8::MAX; 32]);
+/// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// let ciphertext = envelope::encrypt_with_recipients(
+///     data,
+///     &[] as &[&str],
+///     &[PublicKey::from(&identity)],
+///     params,
+/// )
+/// .unwrap();
+///
+/// let plaintext = envelope::decrypt_with_identity(&ciphertext, &identity).unwrap();
+/// assert_eq!(plaintext, data);
+/// ```
+#[cfg(feature = "x25519")]
+pub fn decrypt_with_identity(
+    envelope: impl AsRef<[u8]>,
+    identity: &StaticSecret,
+) -> Result<Vec<u8>> {
+    let inner = |envelope: &[u8]| -> Result<Vec<u8>> {
+        let envelope = Envelope::parse(envelope)?;
+        let key = envelope.unwrap_key_with_identity(identity)?;
+        let plaintext = Decryptor::with_derived_key(&envelope.body, key)?.decrypt_to_vec()?;
+        Ok(plaintext)
+    };
+    inner(envelope.as_ref())
+}
+
+/// Adds a slot for `new_passphrase` to the envelope.
+///
+/// `passphrase` must match one of the existing slots. The new slot is
+/// encrypted with the specified [`Params`], and the body is left unchanged.
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - `envelope` is not a valid envelope.
+/// - `passphrase` does not match any slot.
+/// - Any slot which is tried is invalid.
+/// - The envelope already has [`MAX_SLOTS`] slots.
+///
+/// # Examples
+///
+/// ```
+/// # use scryptenc::{envelope, scrypt::Params};
+/// #
+/// let data = b"Hello, world!\n";
+/// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// let ciphertext = envelope::encrypt(data, &["alice"], params).unwrap();
+///
+/// let ciphertext = envelope::add_passphrase(ciphertext, "alice", "bob", params).unwrap();
+/// assert_eq!(envelope::decrypt(&ciphertext, "bob").unwrap(), data);
+/// ```
+pub fn add_passphrase(
+    envelope: impl AsRef<[u8]>,
+    passphrase: impl AsRef<[u8]>,
+    new_passphrase: impl AsRef<[u8]>,
+    params: Params,
+) -> Result<Vec<u8>> {
+    let inner = |envelope: &[u8], passphrase: &[u8], new_passphrase: &[u8]| -> Result<Vec<u8>> {
+        let envelope = Envelope::parse(envelope)?;
+        if envelope.len() >= MAX_SLOTS {
+            return Err(Error::InvalidSlotCount(envelope.len() + 1));
+        }
+        let (_, key) = envelope.unwrap_key(passphrase)?;
+        let slot = wrap_key(&key, new_passphrase, params);
+        let mut slots = envelope.slots;
+        slots.push(&slot);
+        to_vec(&slots, &envelope.recipients, envelope.body)
+    };
+    inner(
+        envelope.as_ref(),
+        passphrase.as_ref(),
+        new_passphrase.as_ref(),
+    )
+}
+
+/// Removes the slot which `passphrase` matches from the envelope.
+///
+/// The body is left unchanged.
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - `envelope` is not a valid envelope.
+/// - `passphrase` does not match any slot.
+/// - Any slot which is tried is invalid.
+/// - The slot is the only slot of the envelope.
+///
+/// # Examples
+///
+/// ```
+/// # use scryptenc::{envelope, scrypt::Params};
+/// #
+/// let data = b"Hello, world!\n";
+/// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// let ciphertext = envelope::encrypt(data, &["alice", "bob"], params).unwrap();
+///
+/// let ciphertext = envelope::remove_passphrase(ciphertext, "bob").unwrap();
+/// assert!(envelope::decrypt(&ciphertext, "bob").is_err());
+/// # assert_eq!(envelope::decrypt(&ciphertext, "alice").unwrap(), data);
+/// ```
+pub fn remove_passphrase(
+    envelope: impl AsRef<[u8]>,
+    passphrase: impl AsRef<[u8]>,
+) -> Result<Vec<u8>> {
+    let inner = |envelope: &[u8], passphrase: &[u8]| -> Result<Vec<u8>> {
+        let mut envelope = Envelope::parse(envelope)?;
+        let (index, _) = envelope.unwrap_key(passphrase)?;
+        envelope.slots.remove(index);
+        envelope.to_vec()
+    };
+    inner(envelope.as_ref(), passphrase.as_ref())
+}
+
+/// Removes the passphrase slot at `index` from the envelope.
+///
+/// This does not require any passphrase, so this can remove the slot for a
+/// forgotten passphrase. The index of each slot is the same as in the result
+/// of [`slots`]. The recipient slots and the body are left unchanged.
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - `envelope` is not a valid envelope.
+/// - `index` is out of range.
+/// - The slot is the only slot of the envelope.
+///
+/// # Examples
+///
+/// ```
+/// # use scryptenc::{envelope, scrypt::Params};
+/// #
+/// let data = b"Hello, world!\n";
+/// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// let ciphertext = envelope::encrypt(data, &["alice", "bob"], params).unwrap();
+///
+/// let ciphertext = envelope::remove_slot(ciphertext, 0).unwrap();
+/// assert!(envelope::decrypt(&ciphertext, "alice").is_err());
+/// # assert_eq!(envelope::decrypt(&ciphertext, "bob").unwrap(), data);
+/// ```
+pub fn remove_slot(envelope: impl AsRef<[u8]>, index: usize) -> Result<Vec<u8>> {
+    let inner = |envelope: &[u8]| -> Result<Vec<u8>> {
+        let mut envelope = Envelope::parse(envelope)?;
+        if index >= envelope.slots.len() {
+            return Err(Error::InvalidSlotIndex(index));
+        }
+        envelope.slots.remove(index);
+        envelope.to_vec()
+    };
+    inner(envelope.as_ref())
+}
+
+/// Returns the scrypt parameters of each passphrase slot of the envelope.
+///
+/// This does not require any passphrase. The recipient slots are not
+/// included.
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - `envelope` is not a valid envelope.
+/// - The header of any slot is invalid.
+///
+/// # Examples
+///
+/// ```
+/// # use scryptenc::{envelope, scrypt::Params};
+/// #
+/// let data = b"Hello, world!\n";
+/// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// let ciphertext = envelope::encrypt(data, &["alice", "bob"], params).unwrap();
+///
+/// let slots = envelope::slots(&ciphertext).unwrap();
+/// assert_eq!(slots.len(), 2);
+/// assert!(slots.iter().all(|p| p.log_n() == 10));
+/// ```
+pub fn slots(envelope: impl AsRef<[u8]>) -> Result<Vec<crate::Params>> {
+    let inner = |envelope: &[u8]| -> Result<Vec<crate::Params>> {
+        let envelope = Envelope::parse(envelope)?;
+        let params = envelope
+            .slots
+            .into_iter()
+            .map(|slot| crate::Params::new(slot).map_err(Error::Slot))
+            .collect::<Result<_>>()?;
+        Ok(params)
+    };
+    inner(envelope.as_ref())
+}
+
+/// Returns the number of recipient slots of the envelope.
+///
+/// # Errors
+///
+/// Returns [`Err`] if `envelope` is not a valid envelope.
+///
+/// # Examples
+///
+/// ```
+/// # use scryptenc::{envelope, scrypt::Params};
+/// #
+/// let data = b"Hello, world!\n";
+/// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// let ciphertext = envelope::encrypt(data, &["alice", "bob"], params).unwrap();
+///
+/// assert_eq!(envelope::recipient_count(&ciphertext).unwrap(), 0);
+/// ```
+pub fn recipient_count(envelope: impl AsRef<[u8]>) -> Result<usize> {
+    Envelope::parse(envelope.as_ref()).map(|e| e.recipients.len())
+}
+
+/// Returns [`true`] if `data` looks like the envelope.
+///
+/// This only checks the magic number. Use the other functions of this module
+/// to validate it.
+///
+/// # Examples
+///
+/// ```
+/// # use scryptenc::{envelope, scrypt::Params};
+/// #
+/// let ciphertext = include_bytes!("../tests/data/data.txt.scrypt");
+/// assert!(!envelope::is_envelope(ciphertext));
+///
+/// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// let ciphertext = envelope::encrypt(b"Hello, world!\n", &["alice"], params).unwrap();
+/// assert!(envelope::is_envelope(ciphertext));
+/// ```
+#[must_use]
+#[inline]
+pub fn is_envelope(data: impl AsRef<[u8]>) -> bool {
+    data.as_ref().starts_with(&MAGIC_NUMBER)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str;
+
+    use super::*;
+
+    #[test]
+    fn magic_number() {
+        assert_eq!(str::from_utf8(&MAGIC_NUMBER).unwrap(), "screnvlp");
+        assert!(!MAGIC_NUMBER.starts_with(b"scrypt"));
+    }
+
+    #[test]
+    fn slot_size() {
+        let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+        assert_eq!(
+            wrap_key(&[u8::MAX; DERIVED_KEY_SIZE], b"passphrase", params).len(),
+            SLOT_SIZE
+        );
+    }
+
+    #[cfg(feature = "x25519")]
+    #[test]
+    fn recipient_slot_size() {
+        let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+        let recipient = PublicKey::from(&StaticSecret::from([u8::MAX; 32]));
+        assert_eq!(
+            wrap_key_for_recipient(&[u8::MAX; DERIVED_KEY_SIZE], &recipient, params)
+                .unwrap()
+                .len(),
+            RECIPIENT_SLOT_SIZE
+        );
+    }
+
+    #[cfg(feature = "x25519")]
+    #[test]
+    fn low_order_recipient() {
+        let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+        let recipient = PublicKey::from([u8::default(); 32]);
+        assert_eq!(
+            wrap_key_for_recipient(&[u8::MAX; DERIVED_KEY_SIZE], &recipient, params).unwrap_err(),
+            Error::InvalidRecipient
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(format!("{}", Error::InvalidLength), "envelope is too short");
+        assert_eq!(
+            format!("{}", Error::InvalidSlotCount(0)),
+            "number of slots must be between 1 and 255, but it is 0"
+        );
+        assert_eq!(
+            format!("{}", Error::InvalidSlotIndex(2)),
+            "slot 2 does not exist"
+        );
+        assert_eq!(
+            format!("{}", Error::NoMatchingSlot),
+            "passphrase does not match any slot"
+        );
+        assert_eq!(
+            format!("{}", Error::InvalidRecipient),
+            "public key of the recipient is invalid"
+        );
+        assert_eq!(
+            format!("{}", Error::NoMatchingIdentity),
+            "identity does not match any recipient"
+        );
+        assert_eq!(
+            format!("{}", Error::Body(crate::Error::InvalidChecksum)),
+            "checksum mismatch"
+        );
+    }
+
+    #[test]
+    fn to_vec_with_no_slots() {
+        assert_eq!(
+            to_vec(&[] as &[&[u8]], &[] as &[&[u8]], &[]).unwrap_err(),
+            Error::InvalidSlotCount(0)
+        );
+    }
+}
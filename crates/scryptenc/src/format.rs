@@ -73,6 +73,15 @@ pub const HEADER_SIZE: usize = Header::SIZE;
 /// ```
 pub const TAG_SIZE: usize = <HmacSha256 as OutputSizeUser>::OutputSize::USIZE;
 
+/// The number of bytes of the derived key.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(scryptenc::DERIVED_KEY_SIZE, 64);
+/// ```
+pub const DERIVED_KEY_SIZE: usize = DerivedKey::SIZE;
+
 /// Version of the scrypt encrypted data format.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 enum Version {
@@ -118,12 +127,16 @@ impl Header {
         + mem::size_of::<Checksum>()
         + <HeaderMac as OutputSizeUser>::OutputSize::USIZE;
 
-    /// Creates a new `Header`.
+    /// Creates a new `Header` with a random salt.
     pub fn new(params: scrypt::Params) -> Self {
+        Self::with_salt(params, StdRng::from_entropy().r#gen())
+    }
+
+    /// Creates a new `Header` with the specified salt.
+    pub fn with_salt(params: scrypt::Params, salt: Salt) -> Self {
         let magic_number = Self::MAGIC_NUMBER;
         let version = Version::default();
         let params = params.into();
-        let salt = StdRng::from_entropy().r#gen();
         let checksum = Checksum::default();
         let mac = HeaderMacOutput::default();
         Self {
@@ -260,6 +273,15 @@ impl DerivedKey {
         Self { encrypt, mac }
     }
 
+    /// Converts this derived key to a byte array.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut dk = [u8::default(); Self::SIZE];
+        dk[..32].copy_from_slice(&self.encrypt);
+        dk[32..].copy_from_slice(&self.mac);
+        dk
+    }
+
     /// Returns the key for encrypted.
     #[inline]
     pub const fn encrypt(&self) -> Aes256Ctr128BEKey {
@@ -271,6 +293,12 @@ impl DerivedKey {
     pub const fn mac(&self) -> HmacSha256Key {
         self.mac
     }
+
+    /// Returns HMAC-SHA-256 keyed with the key for a MAC.
+    #[inline]
+    pub fn hmac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.mac).expect("HMAC-SHA-256 key size should be 256 bits")
+    }
 }
 
 #[cfg(test)]
@@ -356,6 +384,13 @@ mod tests {
 
     #[test]
     fn derived_key_size() {
+        assert_eq!(DERIVED_KEY_SIZE, 64);
         assert_eq!(DerivedKey::SIZE, 64);
     }
+
+    #[test]
+    fn derived_key_to_bytes() {
+        let dk = core::array::from_fn(|i| u8::try_from(i).unwrap());
+        assert_eq!(DerivedKey::new(dk).to_bytes(), dk);
+    }
 }
@@ -93,11 +93,19 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "armor")]
+pub mod armor;
+#[cfg(feature = "compress")]
+pub mod compress;
 mod decrypt;
 mod encrypt;
+#[cfg(feature = "envelope")]
+pub mod envelope;
 mod error;
 mod format;
 mod params;
+#[cfg(feature = "alloc")]
+mod upgrade;
 
 use aes::Aes256;
 use ctr::Ctr128BE;
@@ -106,22 +114,31 @@ use hmac::{
     Hmac,
     digest::{Output, generic_array::GenericArray, typenum::U32},
 };
+#[cfg(feature = "armor")]
+pub use pem_rfc7468;
 pub use scrypt;
 use sha2::Sha256;
+#[cfg(feature = "x25519")]
+pub use x25519_dalek;
 
 pub use crate::{
     decrypt::Decryptor,
     encrypt::Encryptor,
     error::{Error, Result},
-    format::{HEADER_SIZE, TAG_SIZE},
+    format::{DERIVED_KEY_SIZE, HEADER_SIZE, TAG_SIZE},
     params::Params,
 };
 #[cfg(feature = "alloc")]
 pub use crate::{
     decrypt::decrypt,
-    encrypt::{encrypt, encrypt_with_params},
+    encrypt::{encrypt, encrypt_with_params, encrypt_with_salt},
+    upgrade::upgrade,
 };
 
+/// The number of bytes processed at a time when encrypting or decrypting the
+/// data.
+const CHUNK_SIZE: usize = 64 * 1024;
+
 /// A type alias for AES-256-CTR.
 type Aes256Ctr128BE = Ctr128BE<Aes256>;
 
@@ -117,6 +117,34 @@ impl Params {
     pub const fn p(&self) -> u32 {
         self.p
     }
+
+    /// Returns `true` if these parameters are at least as strong as `target`.
+    ///
+    /// This compares the amount of memory (`N * r`) and the amount of
+    /// computation (`N * r * p`) required to compute the derived key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use scryptenc::{Params, scrypt};
+    /// #
+    /// let ciphertext = include_bytes!("../tests/data/data.txt.scrypt");
+    ///
+    /// let params = Params::new(ciphertext).unwrap();
+    /// assert!(params.meets(scrypt::Params::new(10, 8, 1, scrypt::Params::RECOMMENDED_LEN).unwrap()));
+    /// assert!(!params.meets(scrypt::Params::new(17, 8, 1, scrypt::Params::RECOMMENDED_LEN).unwrap()));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn meets(&self, target: scrypt::Params) -> bool {
+        let memory = |log_n: u8, r: u32| (1_u128 << log_n) * u128::from(r);
+        let (memory, target_memory) = (
+            memory(self.log_n(), self.r()),
+            memory(target.log_n(), target.r()),
+        );
+        memory >= target_memory
+            && memory * u128::from(self.p()) >= target_memory * u128::from(target.p())
+    }
 }
 
 impl From<Params> for scrypt::Params {
@@ -0,0 +1,71 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Upgrades the scrypt parameters of the scrypt encrypted data.
+
+use scrypt::Params;
+
+use crate::{Decryptor, Encryptor, Result};
+
+/// Re-encrypts `ciphertext` with the specified [`Params`] if its scrypt
+/// parameters do not meet `params`.
+///
+/// The data is encrypted again under the same passphrase with a new salt.
+/// Returns [`None`] if the scrypt parameters of `ciphertext` already meet
+/// `params` (see [`Params::meets`](crate::Params::meets)). In this case,
+/// `ciphertext` is not decrypted, so the passphrase is not verified.
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - `ciphertext` is shorter than 128 bytes.
+/// - The magic number is invalid.
+/// - The version number is the unrecognized scrypt version number.
+/// - The scrypt parameters are invalid.
+/// - The checksum of the header mismatch.
+/// - The MAC (authentication tag) of the header is invalid.
+/// - The MAC (authentication tag) of the scrypt encrypted data format is
+///   invalid.
+///
+/// # Examples
+///
+/// ```
+/// # use scryptenc::scrypt::Params;
+/// #
+/// let data = b"Hello, world!\n";
+/// let ciphertext = include_bytes!("../tests/data/data.txt.scrypt");
+/// let passphrase = "passphrase";
+///
+/// let params = Params::new(11, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// let upgraded = scryptenc::upgrade(ciphertext, passphrase, params)
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(scryptenc::Params::new(&upgraded).unwrap().log_n(), 11);
+/// # assert_eq!(scryptenc::decrypt(upgraded, passphrase).unwrap(), data);
+///
+/// let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+/// assert!(
+///     scryptenc::upgrade(ciphertext, passphrase, params)
+///         .unwrap()
+///         .is_none()
+/// );
+/// ```
+#[inline]
+pub fn upgrade(
+    ciphertext: impl AsRef<[u8]>,
+    passphrase: impl AsRef<[u8]>,
+    params: Params,
+) -> Result<Option<alloc::vec::Vec<u8>>> {
+    let inner = |ciphertext: &[u8], passphrase: &[u8]| -> Result<Option<alloc::vec::Vec<u8>>> {
+        if crate::Params::new(ciphertext)?.meets(params) {
+            return Ok(None);
+        }
+        let plaintext = Decryptor::new(&ciphertext, passphrase)?.decrypt_to_vec()?;
+        Ok(Some(
+            Encryptor::with_params(&plaintext, passphrase, params).encrypt_to_vec(),
+        ))
+    };
+    inner(ciphertext.as_ref(), passphrase.as_ref())
+}
@@ -0,0 +1,78 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "armor")]
+
+use scryptenc::{armor, pem_rfc7468};
+
+// Generated using `scrypt` version 1.3.1.
+const TEST_DATA_ENC: &[u8] = include_bytes!("data/data.txt.scrypt");
+const TEST_DATA_ASC: &str = include_str!("data/data.txt.scrypt.asc");
+
+#[test]
+fn success() {
+    let armored = armor::encode(TEST_DATA_ENC);
+    assert!(armored.starts_with("-----BEGIN SCRYPT ENCRYPTED DATA-----\n"));
+    assert!(armored.ends_with("-----END SCRYPT ENCRYPTED DATA-----\n"));
+    assert!(armored.is_ascii());
+
+    let decoded = armor::decode(armored).unwrap();
+    assert_eq!(decoded, TEST_DATA_ENC);
+}
+
+#[test]
+fn encode() {
+    assert_eq!(armor::encode(TEST_DATA_ENC), TEST_DATA_ASC);
+}
+
+#[test]
+fn decode() {
+    assert_eq!(armor::decode(TEST_DATA_ASC).unwrap(), TEST_DATA_ENC);
+}
+
+#[test]
+fn decode_with_surrounding_text() {
+    let armored = format!("Attached below.\n\n{}\n", armor::encode(TEST_DATA_ENC));
+    assert!(armor::is_armored(&armored));
+    assert_eq!(armor::decode(armored).unwrap(), TEST_DATA_ENC);
+}
+
+#[test]
+fn decode_with_crlf() {
+    let armored = armor::encode(TEST_DATA_ENC).replace('\n', "\r\n");
+    assert_eq!(armor::decode(armored).unwrap(), TEST_DATA_ENC);
+}
+
+#[test]
+fn decode_with_unexpected_label() {
+    let armored = armor::encode(TEST_DATA_ENC).replace("SCRYPT ENCRYPTED DATA", "PRIVATE KEY");
+    assert_eq!(
+        armor::decode(armored).unwrap_err(),
+        pem_rfc7468::Error::UnexpectedTypeLabel {
+            expected: armor::LABEL
+        }
+    );
+}
+
+#[test]
+fn decode_invalid_data() {
+    assert!(armor::decode(TEST_DATA_ENC).is_err());
+    let armored = armor::encode(TEST_DATA_ENC).replacen('A', "!", 1);
+    assert!(armor::decode(armored).is_err());
+}
+
+#[test]
+fn is_armored() {
+    assert!(!armor::is_armored(TEST_DATA_ENC));
+    assert!(armor::is_armored(armor::encode(TEST_DATA_ENC)));
+    assert!(armor::is_armored(format!(
+        "\n  {}",
+        armor::encode(TEST_DATA_ENC)
+    )));
+    assert!(armor::is_armored(format!(
+        "Attached below.\n\n{}",
+        armor::encode(TEST_DATA_ENC)
+    )));
+    assert!(!armor::is_armored("Attached below.\n"));
+}
@@ -0,0 +1,88 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "compress")]
+
+use scryptenc::{
+    Decryptor, Encryptor,
+    compress::{self, Compression},
+    scrypt::Params,
+};
+
+const PASSPHRASE: &str = "passphrase";
+const TEST_DATA: &[u8] = include_bytes!("data/data.txt");
+
+fn round_trip(compression: Compression) {
+    let compressed = compress::compress(TEST_DATA, compression).unwrap();
+    let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    let ciphertext = Encryptor::with_params(&compressed, PASSPHRASE, params).encrypt_to_vec();
+
+    let plaintext = Decryptor::new(&ciphertext, PASSPHRASE)
+        .and_then(|c| c.decrypt_to_vec())
+        .unwrap();
+    assert_eq!(compress::detect(&plaintext), Some(compression));
+    assert_eq!(compress::decompress(plaintext).unwrap(), TEST_DATA);
+}
+
+#[test]
+fn success_with_gzip() {
+    round_trip(Compression::Gzip);
+}
+
+#[test]
+fn success_with_zstd() {
+    round_trip(Compression::Zstd);
+}
+
+#[test]
+fn reduce_size() {
+    let data = TEST_DATA.repeat(1024);
+    for compression in [Compression::Gzip, Compression::Zstd] {
+        let compressed = compress::compress(&data, compression).unwrap();
+        assert!(compressed.len() < data.len() / 10);
+    }
+}
+
+#[test]
+fn detect_uncompressed_data() {
+    assert_eq!(compress::detect(TEST_DATA), None);
+    assert_eq!(compress::detect([]), None);
+}
+
+#[test]
+fn detect_gzip_without_marker() {
+    // Empty gzip data generated using `gzip` without any extra field.
+    let data = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x03, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(compress::detect(data), None);
+}
+
+#[test]
+fn detect_zstd_without_marker() {
+    // Empty Zstandard frame.
+    let data = [0x28, 0xb5, 0x2f, 0xfd, 0x20, 0x00, 0x01, 0x00, 0x00];
+    assert_eq!(compress::detect(data), None);
+}
+
+#[test]
+fn decompress_uncompressed_data() {
+    let err = compress::decompress(TEST_DATA).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn decompress_with_limit() {
+    let data = TEST_DATA.repeat(1024);
+    for compression in [Compression::Gzip, Compression::Zstd] {
+        let compressed = compress::compress(&data, compression).unwrap();
+        assert_eq!(
+            compress::decompress_with_limit(&compressed, data.len() as u64).unwrap(),
+            data
+        );
+        let err = compress::decompress_with_limit(&compressed, data.len() as u64 - 1).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -3,7 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use scryptenc::{
-    Decryptor, Error, HEADER_SIZE, TAG_SIZE, hmac::digest::MacError, scrypt::errors::InvalidParams,
+    DERIVED_KEY_SIZE, Decryptor, Error, HEADER_SIZE, TAG_SIZE, hmac::digest::MacError,
+    scrypt::errors::InvalidParams,
 };
 
 const PASSPHRASE: &str = "passphrase";
@@ -19,6 +20,51 @@ fn success() {
     assert_eq!(buf, TEST_DATA);
 }
 
+#[test]
+fn success_with_progress() {
+    let cipher = Decryptor::new(&TEST_DATA_ENC, PASSPHRASE).unwrap();
+    let mut buf = [u8::default(); TEST_DATA.len()];
+    let mut processed = 0;
+    cipher
+        .decrypt_with_progress(&mut buf, |n| processed = n)
+        .unwrap();
+    assert_eq!(processed, TEST_DATA.len());
+    assert_eq!(buf, TEST_DATA);
+}
+
+#[test]
+fn verify() {
+    let cipher = Decryptor::new(&TEST_DATA_ENC, PASSPHRASE).unwrap();
+    assert!(cipher.verify().is_ok());
+}
+
+#[test]
+fn verify_with_progress() {
+    let cipher = Decryptor::new(&TEST_DATA_ENC, PASSPHRASE).unwrap();
+    let mut processed = 0;
+    cipher.verify_with_progress(|n| processed = n).unwrap();
+    assert_eq!(processed, TEST_DATA.len());
+}
+
+#[test]
+fn success_with_derived_key() {
+    let dk = Decryptor::new(&TEST_DATA_ENC, PASSPHRASE)
+        .unwrap()
+        .derived_key();
+    let cipher = Decryptor::with_derived_key(&TEST_DATA_ENC, dk).unwrap();
+    let mut buf = [u8::default(); TEST_DATA.len()];
+    cipher.decrypt(&mut buf).unwrap();
+    assert_eq!(buf, TEST_DATA);
+    assert_eq!(cipher.derived_key(), dk);
+}
+
+#[test]
+fn incorrect_derived_key() {
+    let err =
+        Decryptor::with_derived_key(&TEST_DATA_ENC, [u8::default(); DERIVED_KEY_SIZE]).unwrap_err();
+    assert_eq!(err, Error::InvalidHeaderMac(MacError));
+}
+
 #[cfg(feature = "alloc")]
 #[test]
 fn success_to_vec() {
@@ -130,6 +176,15 @@ fn invalid_mac() {
     assert_eq!(err, Error::InvalidMac(MacError));
 }
 
+#[test]
+fn verify_invalid_mac() {
+    let mut data: [u8; TEST_DATA_ENC.len()] = TEST_DATA_ENC.try_into().unwrap();
+    data[HEADER_SIZE] ^= 1;
+    let cipher = Decryptor::new(&data, PASSPHRASE).unwrap();
+    let err = cipher.verify().unwrap_err();
+    assert_eq!(err, Error::InvalidMac(MacError));
+}
+
 #[test]
 fn out_len() {
     let cipher = Decryptor::new(&TEST_DATA_ENC, PASSPHRASE).unwrap();
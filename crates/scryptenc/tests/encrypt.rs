@@ -50,6 +50,46 @@ fn success_with_params() {
     assert_eq!(buf, TEST_DATA);
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn success_with_progress() {
+    let data = vec![u8::MAX; 200_000];
+    let cipher = Encryptor::with_params(
+        &data,
+        PASSPHRASE,
+        Params::new(4, 10, 16, Params::RECOMMENDED_LEN).unwrap(),
+    );
+    let mut buf = vec![u8::default(); cipher.out_len()];
+    let mut progress = Vec::new();
+    cipher.encrypt_with_progress(&mut buf, |n| progress.push(n));
+    assert_eq!(progress, [65536, 131_072, 196_608, 200_000]);
+
+    let cipher = Decryptor::new(&buf, PASSPHRASE).unwrap();
+    let mut buf = vec![u8::default(); cipher.out_len()];
+    let mut progress = Vec::new();
+    cipher
+        .decrypt_with_progress(&mut buf, |n| progress.push(n))
+        .unwrap();
+    assert_eq!(progress, [65536, 131_072, 196_608, 200_000]);
+    assert_eq!(buf, data);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn success_with_salt() {
+    let expected = include_bytes!("data/data.txt.scrypt");
+    let salt = expected[16..48].try_into().unwrap();
+    let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    let ciphertext = Encryptor::with_salt(&TEST_DATA, PASSPHRASE, params, salt).encrypt_to_vec();
+    assert_eq!(ciphertext, expected);
+
+    let other =
+        Encryptor::with_salt(&TEST_DATA, PASSPHRASE, params, [u8::MAX; 32]).encrypt_to_vec();
+    assert_ne!(other, ciphertext);
+    assert_eq!(other[16..48], [u8::MAX; 32]);
+    assert_eq!(scryptenc::decrypt(other, PASSPHRASE).unwrap(), TEST_DATA);
+}
+
 #[cfg(feature = "alloc")]
 #[test]
 fn success_to_vec() {
@@ -212,3 +252,18 @@ fn success_convenience_function_with_params() {
     let plaintext = scryptenc::decrypt(ciphertext, PASSPHRASE).unwrap();
     assert_eq!(plaintext, TEST_DATA);
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+fn success_convenience_function_with_salt() {
+    let params = Params::new(4, 10, 16, Params::RECOMMENDED_LEN).unwrap();
+    let ciphertext = scryptenc::encrypt_with_salt(TEST_DATA, PASSPHRASE, params, [u8::MIN; 32]);
+    assert_eq!(
+        ciphertext,
+        scryptenc::encrypt_with_salt(TEST_DATA, PASSPHRASE, params, [u8::MIN; 32])
+    );
+    assert_eq!(ciphertext[16..48], [u8::MIN; 32]);
+
+    let plaintext = scryptenc::decrypt(ciphertext, PASSPHRASE).unwrap();
+    assert_eq!(plaintext, TEST_DATA);
+}
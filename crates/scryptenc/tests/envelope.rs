@@ -0,0 +1,375 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "envelope")]
+
+use scryptenc::{
+    HEADER_SIZE, TAG_SIZE,
+    envelope::{self, Error, SLOT_SIZE},
+    scrypt::Params,
+};
+
+const TEST_DATA: &[u8] = include_bytes!("data/data.txt");
+// Generated using `scrypt` version 1.3.1.
+const TEST_DATA_ENC: &[u8] = include_bytes!("data/data.txt.scrypt");
+
+fn params() -> Params {
+    Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap()
+}
+
+#[test]
+fn success() {
+    let ciphertext = envelope::encrypt(TEST_DATA, &["alice", "bob"], params()).unwrap();
+    assert_eq!(
+        ciphertext.len(),
+        10 + SLOT_SIZE * 2 + HEADER_SIZE + TEST_DATA.len() + TAG_SIZE
+    );
+    assert!(envelope::is_envelope(&ciphertext));
+    assert_eq!(envelope::decrypt(&ciphertext, "alice").unwrap(), TEST_DATA);
+    assert_eq!(envelope::decrypt(&ciphertext, "bob").unwrap(), TEST_DATA);
+}
+
+#[test]
+fn incorrect_passphrase() {
+    let ciphertext = envelope::encrypt(TEST_DATA, &["alice", "bob"], params()).unwrap();
+    assert_eq!(
+        envelope::decrypt(ciphertext, "carol").unwrap_err(),
+        Error::NoMatchingSlot
+    );
+}
+
+#[test]
+fn add_passphrase() {
+    let ciphertext = envelope::encrypt(TEST_DATA, &["alice"], params()).unwrap();
+    let params = Params::new(11, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    let added = envelope::add_passphrase(&ciphertext, "alice", "bob", params).unwrap();
+    assert_eq!(added.len(), ciphertext.len() + SLOT_SIZE);
+    assert_eq!(added[10 + SLOT_SIZE * 2..], ciphertext[10 + SLOT_SIZE..]);
+
+    let slots = envelope::slots(&added).unwrap();
+    assert_eq!(slots.len(), 2);
+    assert_eq!(slots[0].log_n(), 10);
+    assert_eq!(slots[1].log_n(), 11);
+    assert_eq!(envelope::decrypt(&added, "alice").unwrap(), TEST_DATA);
+    assert_eq!(envelope::decrypt(&added, "bob").unwrap(), TEST_DATA);
+}
+
+#[test]
+fn add_passphrase_with_incorrect_passphrase() {
+    let ciphertext = envelope::encrypt(TEST_DATA, &["alice"], params()).unwrap();
+    assert_eq!(
+        envelope::add_passphrase(ciphertext, "carol", "bob", params()).unwrap_err(),
+        Error::NoMatchingSlot
+    );
+}
+
+#[test]
+fn remove_passphrase() {
+    let ciphertext = envelope::encrypt(TEST_DATA, &["alice", "bob"], params()).unwrap();
+    let removed = envelope::remove_passphrase(&ciphertext, "alice").unwrap();
+    assert_eq!(removed.len(), ciphertext.len() - SLOT_SIZE);
+    assert_eq!(envelope::slots(&removed).unwrap().len(), 1);
+    assert_eq!(
+        envelope::decrypt(&removed, "alice").unwrap_err(),
+        Error::NoMatchingSlot
+    );
+    assert_eq!(envelope::decrypt(&removed, "bob").unwrap(), TEST_DATA);
+}
+
+#[test]
+fn remove_last_passphrase() {
+    let ciphertext = envelope::encrypt(TEST_DATA, &["alice"], params()).unwrap();
+    assert_eq!(
+        envelope::remove_passphrase(ciphertext, "alice").unwrap_err(),
+        Error::InvalidSlotCount(0)
+    );
+}
+
+#[test]
+fn remove_slot() {
+    let ciphertext = envelope::encrypt(TEST_DATA, &["alice", "bob"], params()).unwrap();
+    let removed = envelope::remove_slot(&ciphertext, 1).unwrap();
+    assert_eq!(envelope::decrypt(&removed, "alice").unwrap(), TEST_DATA);
+    assert_eq!(
+        envelope::decrypt(&removed, "bob").unwrap_err(),
+        Error::NoMatchingSlot
+    );
+    assert_eq!(
+        envelope::remove_slot(&ciphertext, 2).unwrap_err(),
+        Error::InvalidSlotIndex(2)
+    );
+}
+
+#[test]
+fn invalid_slot_count() {
+    assert_eq!(
+        envelope::encrypt(TEST_DATA, &[] as &[&str], params()).unwrap_err(),
+        Error::InvalidSlotCount(0)
+    );
+
+    let mut ciphertext = envelope::encrypt(TEST_DATA, &["alice"], params()).unwrap();
+    ciphertext[9] = 0;
+    assert_eq!(
+        envelope::decrypt(ciphertext, "alice").unwrap_err(),
+        Error::InvalidSlotCount(0)
+    );
+}
+
+#[test]
+fn invalid_length() {
+    assert_eq!(
+        envelope::decrypt([], "alice").unwrap_err(),
+        Error::InvalidLength
+    );
+
+    let ciphertext = envelope::encrypt(TEST_DATA, &["alice"], params()).unwrap();
+    assert_eq!(
+        envelope::decrypt(&ciphertext[..10 + SLOT_SIZE + 127], "alice").unwrap_err(),
+        Error::InvalidLength
+    );
+}
+
+#[test]
+fn invalid_magic_number() {
+    assert!(!envelope::is_envelope(TEST_DATA_ENC));
+    assert_eq!(
+        envelope::decrypt(TEST_DATA_ENC, "passphrase").unwrap_err(),
+        Error::InvalidMagicNumber
+    );
+}
+
+#[test]
+fn envelope_is_not_scrypt_encrypted_data() {
+    let ciphertext = envelope::encrypt(TEST_DATA, &["alice"], params()).unwrap();
+    assert_eq!(
+        scryptenc::Params::new(&ciphertext).unwrap_err(),
+        scryptenc::Error::InvalidMagicNumber
+    );
+}
+
+#[test]
+fn unknown_version() {
+    let mut ciphertext = envelope::encrypt(TEST_DATA, &["alice"], params()).unwrap();
+    ciphertext[8] = 2;
+    assert_eq!(
+        envelope::decrypt(ciphertext, "alice").unwrap_err(),
+        Error::UnknownVersion(2)
+    );
+}
+
+#[test]
+fn corrupted_body() {
+    let mut ciphertext = envelope::encrypt(TEST_DATA, &["alice"], params()).unwrap();
+    *ciphertext.last_mut().unwrap() ^= 1;
+    assert!(matches!(
+        envelope::decrypt(ciphertext, "alice").unwrap_err(),
+        Error::Body(scryptenc::Error::InvalidMac(_))
+    ));
+}
+
+#[test]
+fn corrupted_slot() {
+    let mut ciphertext = envelope::encrypt(TEST_DATA, &["alice", "bob"], params()).unwrap();
+    ciphertext[10 + 48] ^= 1;
+    assert_eq!(
+        envelope::decrypt(&ciphertext, "bob").unwrap_err(),
+        Error::Slot(scryptenc::Error::InvalidChecksum)
+    );
+
+    ciphertext[10] ^= 1;
+    assert_eq!(
+        envelope::slots(ciphertext).unwrap_err(),
+        Error::Slot(scryptenc::Error::InvalidMagicNumber)
+    );
+}
+
+#[test]
+fn swapped_slot() {
+    let a = envelope::encrypt(TEST_DATA, &["alice"], params()).unwrap();
+    let b = envelope::encrypt(TEST_DATA, &["alice"], params()).unwrap();
+    let mut swapped = a[..10].to_vec();
+    swapped.extend_from_slice(&b[10..10 + SLOT_SIZE]);
+    swapped.extend_from_slice(&a[10 + SLOT_SIZE..]);
+    assert!(matches!(
+        envelope::decrypt(swapped, "alice").unwrap_err(),
+        Error::Body(scryptenc::Error::InvalidHeaderMac(_))
+    ));
+}
+
+#[cfg(feature = "x25519")]
+mod x25519 {
+    use scryptenc::{
+        HEADER_SIZE, TAG_SIZE,
+        envelope::{self, Error, RECIPIENT_SLOT_SIZE, SLOT_SIZE},
+        x25519_dalek::{PublicKey, StaticSecret},
+    };
+
+    use super::{TEST_DATA, params};
+
+    fn identity(byte: u8) -> StaticSecret {
+        StaticSecret::from([byte; 32])
+    }
+
+    #[test]
+    fn success() {
+        let (alice, bob) = (identity(1), identity(2));
+        let ciphertext = envelope::encrypt_with_recipients(
+            TEST_DATA,
+            &["passphrase"],
+            &[PublicKey::from(&alice), PublicKey::from(&bob)],
+            params(),
+        )
+        .unwrap();
+        assert_eq!(
+            ciphertext.len(),
+            11 + SLOT_SIZE + RECIPIENT_SLOT_SIZE * 2 + HEADER_SIZE + TEST_DATA.len() + TAG_SIZE
+        );
+        assert_eq!(envelope::slots(&ciphertext).unwrap().len(), 1);
+        assert_eq!(envelope::recipient_count(&ciphertext).unwrap(), 2);
+        assert_eq!(
+            envelope::decrypt_with_identity(&ciphertext, &alice).unwrap(),
+            TEST_DATA
+        );
+        assert_eq!(
+            envelope::decrypt_with_identity(&ciphertext, &bob).unwrap(),
+            TEST_DATA
+        );
+        assert_eq!(
+            envelope::decrypt(&ciphertext, "passphrase").unwrap(),
+            TEST_DATA
+        );
+    }
+
+    #[test]
+    fn recipients_only() {
+        let alice = identity(1);
+        let ciphertext = envelope::encrypt_with_recipients(
+            TEST_DATA,
+            &[] as &[&str],
+            &[PublicKey::from(&alice)],
+            params(),
+        )
+        .unwrap();
+        assert!(envelope::slots(&ciphertext).unwrap().is_empty());
+        assert_eq!(
+            envelope::decrypt_with_identity(&ciphertext, &alice).unwrap(),
+            TEST_DATA
+        );
+        assert_eq!(
+            envelope::decrypt(&ciphertext, "passphrase").unwrap_err(),
+            Error::NoMatchingSlot
+        );
+    }
+
+    #[test]
+    fn without_recipients() {
+        let ciphertext =
+            envelope::encrypt_with_recipients(TEST_DATA, &["passphrase"], &[], params()).unwrap();
+        assert_eq!(ciphertext[8], 0);
+        assert_eq!(envelope::recipient_count(&ciphertext).unwrap(), 0);
+        assert_eq!(
+            envelope::encrypt_with_recipients(TEST_DATA, &[] as &[&str], &[], params())
+                .unwrap_err(),
+            Error::InvalidSlotCount(0)
+        );
+    }
+
+    #[test]
+    fn incorrect_identity() {
+        let ciphertext = envelope::encrypt_with_recipients(
+            TEST_DATA,
+            &["passphrase"],
+            &[PublicKey::from(&identity(1))],
+            params(),
+        )
+        .unwrap();
+        assert_eq!(
+            envelope::decrypt_with_identity(&ciphertext, &identity(2)).unwrap_err(),
+            Error::NoMatchingIdentity
+        );
+    }
+
+    #[test]
+    fn invalid_recipient() {
+        assert_eq!(
+            envelope::encrypt_with_recipients(
+                TEST_DATA,
+                &["passphrase"],
+                &[PublicKey::from([u8::default(); 32])],
+                params(),
+            )
+            .unwrap_err(),
+            Error::InvalidRecipient
+        );
+    }
+
+    #[test]
+    fn manage_passphrases() {
+        let alice = identity(1);
+        let ciphertext = envelope::encrypt_with_recipients(
+            TEST_DATA,
+            &["passphrase"],
+            &[PublicKey::from(&alice)],
+            params(),
+        )
+        .unwrap();
+        let added = envelope::add_passphrase(&ciphertext, "passphrase", "bob", params()).unwrap();
+        assert_eq!(envelope::slots(&added).unwrap().len(), 2);
+        assert_eq!(envelope::recipient_count(&added).unwrap(), 1);
+        assert_eq!(envelope::decrypt(&added, "bob").unwrap(), TEST_DATA);
+        assert_eq!(
+            envelope::decrypt_with_identity(&added, &alice).unwrap(),
+            TEST_DATA
+        );
+
+        let removed = envelope::remove_passphrase(&ciphertext, "passphrase").unwrap();
+        assert!(envelope::slots(&removed).unwrap().is_empty());
+        assert_eq!(
+            envelope::decrypt_with_identity(&removed, &alice).unwrap(),
+            TEST_DATA
+        );
+    }
+
+    #[test]
+    fn corrupted_recipient_slot() {
+        let alice = identity(1);
+        let mut ciphertext = envelope::encrypt_with_recipients(
+            TEST_DATA,
+            &[] as &[&str],
+            &[PublicKey::from(&alice)],
+            params(),
+        )
+        .unwrap();
+        ciphertext[11] ^= 1;
+        assert_eq!(
+            envelope::decrypt_with_identity(&ciphertext, &alice).unwrap_err(),
+            Error::NoMatchingIdentity
+        );
+        ciphertext[11] ^= 1;
+        ciphertext[11 + 32 + 48] ^= 1;
+        assert_eq!(
+            envelope::decrypt_with_identity(&ciphertext, &alice).unwrap_err(),
+            Error::Slot(scryptenc::Error::InvalidChecksum)
+        );
+    }
+
+    #[test]
+    fn invalid_length() {
+        let ciphertext = envelope::encrypt_with_recipients(
+            TEST_DATA,
+            &[] as &[&str],
+            &[PublicKey::from(&identity(1))],
+            params(),
+        )
+        .unwrap();
+        assert_eq!(
+            envelope::recipient_count(&ciphertext[..11 + RECIPIENT_SLOT_SIZE + 127]).unwrap_err(),
+            Error::InvalidLength
+        );
+        assert_eq!(
+            envelope::recipient_count(&ciphertext[..10]).unwrap_err(),
+            Error::InvalidLength
+        );
+    }
+}
@@ -37,6 +37,23 @@ fn p() {
     assert_eq!(params.p(), 1);
 }
 
+#[test]
+fn meets() {
+    use scryptenc::scrypt;
+
+    let params = Params::new(TEST_DATA_ENC).unwrap();
+    let target =
+        |log_n, r, p| scrypt::Params::new(log_n, r, p, scrypt::Params::RECOMMENDED_LEN).unwrap();
+    assert!(params.meets(target(10, 8, 1)));
+    assert!(params.meets(target(9, 8, 1)));
+    assert!(params.meets(target(10, 4, 2)));
+    assert!(!params.meets(target(11, 8, 1)));
+    assert!(!params.meets(target(10, 9, 1)));
+    assert!(!params.meets(target(10, 8, 2)));
+    assert!(params.meets(target(9, 16, 1)));
+    assert!(!params.meets(target(9, 17, 1)));
+}
+
 #[cfg(feature = "serde")]
 #[test]
 fn serialize() {
@@ -0,0 +1,52 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "alloc")]
+
+use scryptenc::{Error, scrypt::Params};
+
+const PASSPHRASE: &str = "passphrase";
+const TEST_DATA: &[u8] = include_bytes!("data/data.txt");
+// Generated using `scrypt` version 1.3.1.
+const TEST_DATA_ENC: &[u8] = include_bytes!("data/data.txt.scrypt");
+
+#[test]
+fn success() {
+    let params = Params::new(11, 8, 2, Params::RECOMMENDED_LEN).unwrap();
+    let upgraded = scryptenc::upgrade(TEST_DATA_ENC, PASSPHRASE, params)
+        .unwrap()
+        .unwrap();
+    assert_eq!(upgraded.len(), TEST_DATA_ENC.len());
+    assert_ne!(upgraded[16..48], TEST_DATA_ENC[16..48]);
+
+    let params = scryptenc::Params::new(&upgraded).unwrap();
+    assert_eq!(params.log_n(), 11);
+    assert_eq!(params.r(), 8);
+    assert_eq!(params.p(), 2);
+    assert_eq!(scryptenc::decrypt(upgraded, PASSPHRASE).unwrap(), TEST_DATA);
+}
+
+#[test]
+fn skip_if_params_meet_target() {
+    let params = Params::new(10, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    assert!(
+        scryptenc::upgrade(TEST_DATA_ENC, PASSPHRASE, params)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn incorrect_passphrase() {
+    let params = Params::new(11, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    let result = scryptenc::upgrade(TEST_DATA_ENC, "password", params).unwrap_err();
+    assert!(matches!(result, Error::InvalidHeaderMac(_)));
+}
+
+#[test]
+fn invalid_input_length() {
+    let params = Params::new(11, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    let result = scryptenc::upgrade([], PASSPHRASE, params).unwrap_err();
+    assert_eq!(result, Error::InvalidLength);
+}
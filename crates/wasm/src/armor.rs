@@ -0,0 +1,39 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! ASCII armor for the scrypt encrypted data format.
+
+use scryptenc::armor;
+use wasm_bindgen::{JsError, prelude::wasm_bindgen};
+
+/// Encodes `data` into the ASCII-armored data.
+///
+/// The encrypted data is encoded in Base64 and surrounded by the
+/// `-----BEGIN SCRYPT ENCRYPTED DATA-----` and
+/// `-----END SCRYPT ENCRYPTED DATA-----` lines.
+#[must_use]
+#[wasm_bindgen(js_name = armorEncode)]
+pub fn armor_encode(data: &[u8]) -> String {
+    armor::encode(data)
+}
+
+/// Decodes the ASCII-armored data and into a newly allocated `Uint8Array`.
+///
+/// # Errors
+///
+/// Returns an error if any of the following are true:
+///
+/// - `data` is not a valid ASCII-armored data.
+/// - The type label is not `SCRYPT ENCRYPTED DATA`.
+#[wasm_bindgen(js_name = armorDecode)]
+pub fn armor_decode(data: &str) -> Result<Vec<u8>, JsError> {
+    armor::decode(data).map_err(JsError::from)
+}
+
+/// Returns `true` if `data` looks like the ASCII-armored data.
+#[must_use]
+#[wasm_bindgen(js_name = isArmored)]
+pub fn is_armored(data: &[u8]) -> bool {
+    armor::is_armored(data)
+}
@@ -8,6 +8,7 @@
 // Lint levels of rustc.
 #![deny(missing_docs)]
 
+mod armor;
 mod decrypt;
 mod encrypt;
 mod params;
@@ -15,6 +16,7 @@ mod params;
 use wasm_bindgen::prelude::wasm_bindgen;
 
 pub use crate::{
+    armor::{armor_decode, armor_encode, is_armored},
     decrypt::decrypt,
     encrypt::{encrypt, encrypt_with_params},
     params::Params,
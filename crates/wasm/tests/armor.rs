@@ -0,0 +1,36 @@
+// SPDX-FileCopyrightText: 2026 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+// Generated using `scrypt` version 1.3.1.
+const TEST_DATA_ENC: &[u8] = include_bytes!("data/data.txt.scrypt");
+const TEST_DATA_ASC: &str = include_str!("data/data.txt.scrypt.asc");
+
+#[wasm_bindgen_test]
+fn encode() {
+    assert_eq!(scryptenc_wasm::armor_encode(TEST_DATA_ENC), TEST_DATA_ASC);
+}
+
+#[wasm_bindgen_test]
+fn decode() {
+    let decoded = scryptenc_wasm::armor_decode(TEST_DATA_ASC)
+        .map_err(JsValue::from)
+        .unwrap();
+    assert_eq!(decoded, TEST_DATA_ENC);
+}
+
+#[wasm_bindgen_test]
+fn decode_with_unexpected_label() {
+    let armored = TEST_DATA_ASC.replace("SCRYPT ENCRYPTED DATA", "PRIVATE KEY");
+    let result = scryptenc_wasm::armor_decode(&armored).map_err(JsValue::from);
+    assert!(result.is_err());
+}
+
+#[wasm_bindgen_test]
+fn is_armored() {
+    assert!(!scryptenc_wasm::is_armored(TEST_DATA_ENC));
+    assert!(scryptenc_wasm::is_armored(TEST_DATA_ASC.as_bytes()));
+}